@@ -1,14 +1,23 @@
 use anima_weave_core::graph::{Connection, Graph, NodeRef, PortRef};
+use anima_weave_node::{export_catalog_json, load_plugins_from_dir, verify_registrations};
+use anima_weave_remote::load_hosts_from_dir;
 use anima_weave_runtime::graph_runner::GraphRunner;
 use anima_weave_vessels::{create_node_factory, get_registered_node_types};
+use anima_weave_wasm::load_wasm_nodes_from_dir;
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use tokio::runtime::Runtime;
 
 /// Anima Weave CLI – 简化版
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Anima Weave simplified CLI", long_about = None)]
 struct Cli {
+    /// 插件目录，启动时加载其中的节点包动态库
+    #[arg(long, global = true, default_value = "plugins")]
+    plugin_dir: PathBuf,
+
     #[command(subcommand)]
     command: Command,
 }
@@ -17,6 +26,8 @@ struct Cli {
 enum Command {
     /// 列出已注册的节点类型
     ListNodes,
+    /// 以 JSON 导出节点目录（节点、端口、标签及转换）
+    Catalog,
     /// 测试自动关机逻辑
     TestShutdown,
 }
@@ -25,8 +36,26 @@ fn main() -> anyhow::Result<()> {
     env_logger::init();
     let cli = Cli::parse();
 
+    verify_registrations()?;
+
+    // 加载信息输出到 stderr，stdout 留给命令输出（如 catalog 的 JSON）
+    for plugin in load_plugins_from_dir(&cli.plugin_dir)? {
+        eprintln!(
+            "🔌 已加载插件 {} ({} 个节点)",
+            plugin.path.display(),
+            plugin.node_types.len()
+        );
+    }
+    for node_type in load_wasm_nodes_from_dir(&cli.plugin_dir)? {
+        eprintln!("🧩 已加载 WASM 节点 {}", node_type);
+    }
+    for node_type in load_hosts_from_dir(&cli.plugin_dir)? {
+        eprintln!("🛰️ 已注册外部节点 {}", node_type);
+    }
+
     match cli.command {
         Command::ListNodes => list_nodes(),
+        Command::Catalog => println!("{}", export_catalog_json()?),
         Command::TestShutdown => test_shutdown()?,
     }
 
@@ -34,8 +63,7 @@ fn main() -> anyhow::Result<()> {
 }
 
 fn list_nodes() {
-    let factory = create_node_factory();
-    println!("🌟 已注册节点类型 ({}):", factory.len());
+    println!("🌟 已注册节点类型 ({}):", create_node_factory().len());
     for node_type in get_registered_node_types() {
         println!(" - {}", node_type);
     }
@@ -50,22 +78,32 @@ fn test_shutdown() -> Result<()> {
         let graph = build_simple_graph();
         println!("📊 构建了图: {} 个节点", graph.nodes.len());
 
-        // 钩子 - 这里不直接退出，只打印
-        let hook: Option<Box<dyn Fn() + Send + Sync + 'static>> = Some(Box::new(|| {
-            println!("🎯 Shutdown triggered! All nodes have completed at least once.");
-        }));
-
         println!("🔧 构建GraphRunner...");
-        // build runner
-        let runner = GraphRunner::build_from_graph(graph, hook).await?;
-
-        println!("🚀 启动图执行...");
-        // launch
-        runner.launch().await?;
-
-        println!("⏳ 等待5秒观察执行...");
-        // 为了测试，添加延迟观察
-        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        let runner = GraphRunner::build_from_graph(graph).await?;
+
+        println!("🚀 运行图，等待所有节点结束...");
+        let result = runner.run(HashMap::new()).await?;
+
+        let mut nodes: Vec<_> = result.nodes.iter().collect();
+        nodes.sort_by_key(|(name, _)| name.as_str());
+        for (node_name, status) in nodes {
+            println!(" - {}: {:?}", node_name, status);
+        }
+        for (node_name, error) in &result.errors {
+            println!("❌ {} ({}): {}", node_name, error.kind(), error);
+        }
+        for error in &result.edge_errors {
+            println!("❌ {}", error);
+        }
+        for (port, label) in &result.outputs {
+            println!(
+                "📤 {}:{} = {}",
+                port.node_name,
+                port.port_name,
+                label.to_json()
+            );
+        }
+        runner.shutdown().await;
 
         println!("✅ 测试完成");
 
@@ -74,18 +112,9 @@ fn test_shutdown() -> Result<()> {
 }
 
 fn build_simple_graph() -> Graph {
-    let random1 = NodeRef {
-        name: "random1".to_string(),
-        node_type: "RandomNode".to_string(),
-    };
-    let random2 = NodeRef {
-        name: "random2".to_string(),
-        node_type: "RandomNode".to_string(),
-    };
-    let add = NodeRef {
-        name: "add".to_string(),
-        node_type: "AddNode".to_string(),
-    };
+    let random1 = NodeRef::new("random1", "RandomNode");
+    let random2 = NodeRef::new("random2", "RandomNode");
+    let add = NodeRef::new("add", "AddNode");
 
     // RandomNode1 -> AddNode (a)
     let conn1 = Connection {
@@ -114,5 +143,7 @@ fn build_simple_graph() -> Graph {
     Graph {
         nodes: vec![random1, random2, add],
         data_connections: vec![conn1, conn2],
+        control_connections: vec![],
+        inputs: vec![],
     }
 }
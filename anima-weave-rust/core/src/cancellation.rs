@@ -0,0 +1,103 @@
+//! 运行取消标记
+
+use std::future::{Future, poll_fn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+
+/// 运行取消标记，同一次运行的所有节点共享
+///
+/// 取消后节点不再开始新的执行，已在执行的节点照常结束。组合节点（如 map）
+/// 从 [`NodeContext::cancellation`](crate::NodeContext::cancellation) 取得它，
+/// 用 [`Self::child`] 派生内部运行的标记，并可以用 [`Self::cancelled`] 及时中止自己的工作
+#[derive(Debug, Clone, Default)]
+pub struct RunCancellation {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    /// 等待取消的任务
+    wakers: Mutex<Vec<Waker>>,
+    /// 父标记，取消时子标记一同取消
+    parent: Option<RunCancellation>,
+}
+
+impl RunCancellation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 派生子标记：父标记取消时子标记随之取消，取消子标记不影响父标记
+    pub fn child(&self) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                parent: Some(self.clone()),
+                ..Inner::default()
+            }),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        let wakers = std::mem::take(&mut *self.inner.wakers.lock().unwrap());
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+            || self
+                .inner
+                .parent
+                .as_ref()
+                .is_some_and(RunCancellation::is_cancelled)
+    }
+
+    /// 等到运行被取消
+    pub fn cancelled(&self) -> impl Future<Output = ()> + '_ {
+        poll_fn(move |cx| {
+            if self.register(cx.waker()) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+    }
+
+    /// 在本标记和各级父标记上登记等待者，已经取消时返回 true
+    fn register(&self, waker: &Waker) -> bool {
+        {
+            let mut wakers = self.inner.wakers.lock().unwrap();
+            // 持有锁之后检查，`cancel` 在这之后才会取走等待者
+            if self.inner.cancelled.load(Ordering::SeqCst) {
+                return true;
+            }
+            if !wakers.iter().any(|registered| registered.will_wake(waker)) {
+                wakers.push(waker.clone());
+            }
+        }
+        self.inner
+            .parent
+            .as_ref()
+            .is_some_and(|parent| parent.register(waker))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_child_follows_parent_only() {
+        let parent = RunCancellation::new();
+        let child = parent.child();
+        child.cancel();
+        assert!(child.is_cancelled());
+        assert!(!parent.is_cancelled());
+
+        let child = parent.child();
+        parent.cancel();
+        assert!(child.is_cancelled());
+    }
+}
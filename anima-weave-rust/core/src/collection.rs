@@ -0,0 +1,262 @@
+//! 集合标签：扇入端口把多个来源的输入合并为一个标签
+//!
+//! 元素可以是任意语义标签。JSON 形式中每个元素带上自己的标签类型名
+//! （`{"label": "NumberLabel", "value": {...}}`），反序列化时按类型名
+//! 查找 inventory 中注册的标签。
+
+use crate::label::{ConversionFn, LabelRegistration, SemanticLabel, TransformError};
+use serde_json::{Map, Value, json};
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+
+/// 有序的标签列表，对应 `Array[T]`
+#[derive(Debug, Clone, Default)]
+pub struct ArrayLabel {
+    pub items: Vec<Box<dyn SemanticLabel>>,
+}
+
+impl ArrayLabel {
+    pub fn new(items: Vec<Box<dyn SemanticLabel>>) -> Self {
+        Self { items }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 类型化读取所有元素，元素类型不同时先尝试转换为 `T`
+    pub fn items_as<T: SemanticLabel + Clone>(&self) -> Result<Vec<T>, TransformError> {
+        self.items
+            .iter()
+            .map(|item| downcast(item.as_ref()))
+            .collect()
+    }
+
+    /// 从 to_json() 的输出重建标签
+    pub fn from_json(value: &Value) -> Result<Box<dyn SemanticLabel>, TransformError> {
+        let items = value
+            .get("items")
+            .and_then(Value::as_array)
+            .ok_or_else(|| missing_field("ArrayLabel", "items"))?
+            .iter()
+            .map(item_from_json)
+            .collect::<Result<_, _>>()?;
+        Ok(Box::new(ArrayLabel { items }))
+    }
+}
+
+impl SemanticLabel for ArrayLabel {
+    fn clone_box(&self) -> Box<dyn SemanticLabel> {
+        Box::new(self.clone())
+    }
+
+    fn semantic_label_type() -> &'static str {
+        "ArrayLabel"
+    }
+
+    fn get_semantic_label_type(&self) -> &'static str {
+        Self::semantic_label_type()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn conversion_map(&self) -> HashMap<&'static str, ConversionFn> {
+        HashMap::new()
+    }
+
+    fn to_json(&self) -> Value {
+        let items: Vec<Value> = self
+            .items
+            .iter()
+            .map(|item| item_to_json(item.as_ref()))
+            .collect();
+        json!({ "items": items })
+    }
+}
+
+/// 按键索引的标签集合，键有序
+#[derive(Debug, Clone, Default)]
+pub struct RecordLabel {
+    pub fields: BTreeMap<String, Box<dyn SemanticLabel>>,
+}
+
+impl RecordLabel {
+    pub fn new(fields: BTreeMap<String, Box<dyn SemanticLabel>>) -> Self {
+        Self { fields }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&dyn SemanticLabel> {
+        self.fields.get(key).map(|field| field.as_ref())
+    }
+
+    /// 类型化读取一个字段，字段类型不同时先尝试转换为 `T`
+    pub fn field_as<T: SemanticLabel + Clone>(&self, key: &str) -> Result<T, TransformError> {
+        let field = self
+            .get(key)
+            .ok_or_else(|| missing_field("RecordLabel", key))?;
+        downcast(field)
+    }
+
+    /// 从 to_json() 的输出重建标签
+    pub fn from_json(value: &Value) -> Result<Box<dyn SemanticLabel>, TransformError> {
+        let fields = value
+            .get("fields")
+            .and_then(Value::as_object)
+            .ok_or_else(|| missing_field("RecordLabel", "fields"))?
+            .iter()
+            .map(|(key, field)| Ok((key.clone(), item_from_json(field)?)))
+            .collect::<Result<_, TransformError>>()?;
+        Ok(Box::new(RecordLabel { fields }))
+    }
+}
+
+impl SemanticLabel for RecordLabel {
+    fn clone_box(&self) -> Box<dyn SemanticLabel> {
+        Box::new(self.clone())
+    }
+
+    fn semantic_label_type() -> &'static str {
+        "RecordLabel"
+    }
+
+    fn get_semantic_label_type(&self) -> &'static str {
+        Self::semantic_label_type()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn conversion_map(&self) -> HashMap<&'static str, ConversionFn> {
+        HashMap::new()
+    }
+
+    fn to_json(&self) -> Value {
+        let fields: Map<String, Value> = self
+            .fields
+            .iter()
+            .map(|(key, field)| (key.clone(), item_to_json(field.as_ref())))
+            .collect();
+        json!({ "fields": fields })
+    }
+}
+
+inventory::submit! {
+    LabelRegistration::new("ArrayLabel", &[], ArrayLabel::from_json)
+}
+
+inventory::submit! {
+    LabelRegistration::new("RecordLabel", &[], RecordLabel::from_json)
+}
+
+fn item_to_json(item: &dyn SemanticLabel) -> Value {
+    json!({ "label": item.get_semantic_label_type(), "value": item.to_json() })
+}
+
+fn item_from_json(value: &Value) -> Result<Box<dyn SemanticLabel>, TransformError> {
+    let label = value
+        .get("label")
+        .and_then(Value::as_str)
+        .ok_or_else(|| missing_field("collection item", "label"))?;
+    let registration = inventory::iter::<LabelRegistration>
+        .into_iter()
+        .find(|registration| registration.name == label)
+        .ok_or_else(|| TransformError::ConversionFailed {
+            reason: format!("Unknown label '{}' in collection", label),
+        })?;
+    (registration.from_json)(value.get("value").unwrap_or(&Value::Null))
+}
+
+fn missing_field(owner: &str, field: &str) -> TransformError {
+    TransformError::ConversionFailed {
+        reason: format!("{} is missing field '{}'", owner, field),
+    }
+}
+
+fn downcast<T: SemanticLabel + Clone>(item: &dyn SemanticLabel) -> Result<T, TransformError> {
+    if let Some(item) = item.as_any().downcast_ref::<T>() {
+        return Ok(item.clone());
+    }
+    let converted = item.try_convert_to(T::semantic_label_type())?;
+    converted
+        .as_any()
+        .downcast_ref::<T>()
+        .cloned()
+        .ok_or_else(|| TransformError::IncompatibleTypes {
+            from: item.get_semantic_label_type(),
+            to: T::semantic_label_type(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semantic_label;
+
+    semantic_label! {
+        CollectionNumberLabel(value: f64) {
+            CollectionStringLabel => |this| CollectionStringLabel { value: this.value.to_string() },
+        }
+    }
+
+    semantic_label! {
+        CollectionStringLabel(value: String) {}
+    }
+
+    #[test]
+    fn test_array_items_and_json_round_trip() {
+        let array = ArrayLabel::new(vec![
+            Box::new(CollectionNumberLabel { value: 1.0 }),
+            Box::new(CollectionNumberLabel { value: 2.5 }),
+        ]);
+
+        let strings: Vec<CollectionStringLabel> = array.items_as().unwrap();
+        assert_eq!(strings[1].value, "2.5");
+
+        let json = array.to_json();
+        assert_eq!(
+            json["items"][0],
+            json!({"label": "CollectionNumberLabel", "value": {"value": 1.0}})
+        );
+        let restored = ArrayLabel::from_json(&json).unwrap();
+        let restored = restored.as_any().downcast_ref::<ArrayLabel>().unwrap();
+        let numbers: Vec<CollectionNumberLabel> = restored.items_as().unwrap();
+        assert_eq!(
+            numbers.iter().map(|n| n.value).collect::<Vec<_>>(),
+            [1.0, 2.5]
+        );
+    }
+
+    #[test]
+    fn test_record_fields_and_json_round_trip() {
+        let mut fields: BTreeMap<String, Box<dyn SemanticLabel>> = BTreeMap::new();
+        fields.insert(
+            "a".to_string(),
+            Box::new(CollectionNumberLabel { value: 3.0 }),
+        );
+        let record = RecordLabel::new(fields);
+
+        assert_eq!(
+            record.field_as::<CollectionStringLabel>("a").unwrap().value,
+            "3"
+        );
+        assert!(record.field_as::<CollectionNumberLabel>("b").is_err());
+
+        let restored = RecordLabel::from_json(&record.to_json()).unwrap();
+        let restored = restored.as_any().downcast_ref::<RecordLabel>().unwrap();
+        assert_eq!(
+            restored
+                .field_as::<CollectionNumberLabel>("a")
+                .unwrap()
+                .value,
+            3.0
+        );
+        assert!(RecordLabel::from_json(&json!({"fields": {"a": {"label": "Nope"}}})).is_err());
+    }
+}
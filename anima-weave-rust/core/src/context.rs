@@ -0,0 +1,323 @@
+use crate::cancellation::RunCancellation;
+use crate::error::NodeError;
+use crate::graph::PortRef;
+use crate::label::SemanticLabel;
+use crate::node::{NodeInfo, PortDef};
+use crate::policy::FailurePolicy;
+use crate::state::NodeState;
+use crate::types::{NodeDataInputs, NodeDataOutputs, NodeName};
+
+/// 节点执行上下文
+///
+/// 端口按节点实例名解析，节点实现不需要（也不应该）自己拼 PortRef。
+/// 读取输入和写入输出时，会自动把标签转换为端口声明的语义标签类型。
+#[derive(Debug)]
+pub struct NodeContext {
+    node_name: NodeName,
+    info: &'static NodeInfo,
+    inputs: NodeDataInputs,
+    outputs: NodeDataOutputs,
+    state: NodeState,
+    failure_policy: FailurePolicy,
+    cancellation: RunCancellation,
+}
+
+impl NodeContext {
+    pub fn new(
+        node_name: impl Into<NodeName>,
+        info: &'static NodeInfo,
+        inputs: NodeDataInputs,
+    ) -> Self {
+        Self {
+            node_name: node_name.into(),
+            info,
+            inputs,
+            outputs: NodeDataOutputs::new(),
+            state: NodeState::new(),
+            failure_policy: FailurePolicy::default(),
+            cancellation: RunCancellation::new(),
+        }
+    }
+
+    /// 使用节点实例的持久状态（默认是一份只属于本次执行的空状态）
+    pub fn with_state(mut self, state: NodeState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// 设置节点所在运行的失败策略（节点实例的覆盖优先）
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// 节点所在运行的失败策略，组合节点（如 map）按它处理内部的失败
+    pub fn failure_policy(&self) -> FailurePolicy {
+        self.failure_policy
+    }
+
+    /// 设置节点所在运行的取消标记
+    pub fn with_cancellation(mut self, cancellation: RunCancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// 节点所在运行的取消标记，组合节点把它传给内部的运行
+    pub fn cancellation(&self) -> &RunCancellation {
+        &self.cancellation
+    }
+
+    /// 节点实例名
+    pub fn node_name(&self) -> &str {
+        &self.node_name
+    }
+
+    /// 节点静态信息
+    pub fn info(&self) -> &'static NodeInfo {
+        self.info
+    }
+
+    /// 节点实例的持久状态，跨多次执行保留
+    pub fn state(&self) -> &NodeState {
+        &self.state
+    }
+
+    /// 当前节点实例上某个端口的引用
+    pub fn port_ref(&self, port_name: &str) -> PortRef {
+        PortRef {
+            node_name: self.node_name.clone(),
+            port_name: port_name.to_string(),
+        }
+    }
+
+    /// 输入端口上是否有数据
+    pub fn has_input(&self, port_name: &str) -> bool {
+        self.inputs.contains_key(&self.port_ref(port_name))
+    }
+
+    /// 读取输入端口的原始标签（不做转换）
+    pub fn raw_input(&self, port_name: &str) -> Option<&dyn SemanticLabel> {
+        self.inputs
+            .get(&self.port_ref(port_name))
+            .map(|label| label.as_ref())
+    }
+
+    /// 读取输入端口，并转换为端口声明的语义标签
+    ///
+    /// 收集型扇入端口收到的是 [`ArrayLabel`](crate::ArrayLabel) /
+    /// [`RecordLabel`](crate::RecordLabel)，原样返回
+    pub fn input_label(&self, port_name: &str) -> Result<Box<dyn SemanticLabel>, NodeError> {
+        let label = self.raw_input(port_name).ok_or_else(|| {
+            NodeError::invalid_input(port_name, format!("Missing required input '{}'", port_name))
+        })?;
+
+        match find_port(&self.info.input_ports, port_name) {
+            Some(port) if port.is_collection() => Ok(label.clone_box()),
+            Some(port) => convert_label(label, port.port_type.semantic_label()).map_err(|e| {
+                NodeError::invalid_input(
+                    port_name,
+                    format!("Input '{}' conversion failed: {}", port_name, e),
+                )
+            }),
+            None => Ok(label.clone_box()),
+        }
+    }
+
+    /// 类型化读取输入端口
+    ///
+    /// 如果收到的标签类型与 `T` 不同，会先尝试转换为 `T`
+    pub fn input<T: SemanticLabel + Clone>(&self, port_name: &str) -> Result<T, NodeError> {
+        let label = self.raw_input(port_name).ok_or_else(|| {
+            NodeError::invalid_input(port_name, format!("Missing required input '{}'", port_name))
+        })?;
+
+        let converted = convert_label(label, T::semantic_label_type()).map_err(|e| {
+            NodeError::invalid_input(
+                port_name,
+                format!(
+                    "Input '{}' must be {}: {}",
+                    port_name,
+                    T::semantic_label_type(),
+                    e
+                ),
+            )
+        })?;
+
+        converted
+            .as_any()
+            .downcast_ref::<T>()
+            .cloned()
+            .ok_or_else(|| {
+                NodeError::invalid_input(
+                    port_name,
+                    format!("Input '{}' must be {}", port_name, T::semantic_label_type()),
+                )
+            })
+    }
+
+    /// 类型化读取可选输入端口，端口无数据时返回 None
+    pub fn optional_input<T: SemanticLabel + Clone>(
+        &self,
+        port_name: &str,
+    ) -> Result<Option<T>, NodeError> {
+        if self.has_input(port_name) {
+            self.input(port_name).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 写入输出端口
+    ///
+    /// 端口必须在 NodeInfo 中声明；标签会被转换为端口声明的语义标签
+    pub fn output<L: SemanticLabel>(&mut self, port_name: &str, label: L) -> Result<(), NodeError> {
+        self.output_boxed(port_name, Box::new(label))
+    }
+
+    /// 写入输出端口（trait object 版本）
+    pub fn output_boxed(
+        &mut self,
+        port_name: &str,
+        label: Box<dyn SemanticLabel>,
+    ) -> Result<(), NodeError> {
+        let port = find_port(&self.info.output_ports, port_name).ok_or_else(|| {
+            NodeError::fatal(format!(
+                "Node {} has no output port '{}'",
+                self.info.name, port_name
+            ))
+        })?;
+
+        let label = if label.get_semantic_label_type() == port.port_type.semantic_label() {
+            label
+        } else {
+            convert_label(label.as_ref(), port.port_type.semantic_label()).map_err(|e| {
+                NodeError::fatal(format!("Output '{}' conversion failed: {}", port_name, e))
+            })?
+        };
+
+        self.outputs.insert(self.port_ref(port_name), label);
+        Ok(())
+    }
+
+    /// 已写入的输出
+    pub fn outputs(&self) -> &NodeDataOutputs {
+        &self.outputs
+    }
+
+    /// 取出所有输出，结束本次执行
+    pub fn into_outputs(self) -> NodeDataOutputs {
+        self.outputs
+    }
+}
+
+fn find_port<'a>(ports: &'a [PortDef], port_name: &str) -> Option<&'a PortDef> {
+    ports.iter().find(|port| port.name == port_name)
+}
+
+/// 将标签转换为目标语义标签类型，类型相同时直接克隆
+fn convert_label(
+    label: &dyn SemanticLabel,
+    target_type: &'static str,
+) -> Result<Box<dyn SemanticLabel>, crate::label::TransformError> {
+    if label.get_semantic_label_type() == target_type {
+        Ok(label.clone_box())
+    } else {
+        label.try_convert_to(target_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::ExecutionMode;
+    use crate::semantic_label;
+    use once_cell::sync::Lazy;
+
+    semantic_label! {
+        CtxNumberLabel(value: f64) {
+            CtxStringLabel => |this| CtxStringLabel { value: this.value.to_string() },
+        }
+    }
+
+    semantic_label! {
+        CtxStringLabel(value: String) {}
+    }
+
+    static INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "FormatNode",
+        description: "测试节点",
+        input_ports: vec![PortDef::required_data::<CtxStringLabel>("text")],
+        output_ports: vec![PortDef::output_data::<CtxStringLabel>("formatted")],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    fn ctx_with_input(node_name: &str, label: Box<dyn SemanticLabel>) -> NodeContext {
+        let mut inputs = NodeDataInputs::new();
+        inputs.insert(
+            PortRef {
+                node_name: node_name.to_string(),
+                port_name: "text".to_string(),
+            },
+            label,
+        );
+        NodeContext::new(node_name, &INFO, inputs)
+    }
+
+    #[test]
+    fn test_input_resolves_against_instance_name() {
+        let ctx = ctx_with_input("fmt1", Box::new(CtxStringLabel { value: "hi".into() }));
+
+        assert_eq!(ctx.input::<CtxStringLabel>("text").unwrap().value, "hi");
+        assert!(ctx.has_input("text"));
+        assert_eq!(ctx.port_ref("text").node_name, "fmt1");
+    }
+
+    #[test]
+    fn test_input_converts_to_requested_label() {
+        let ctx = ctx_with_input("fmt1", Box::new(CtxNumberLabel { value: 4.0 }));
+
+        assert_eq!(ctx.input::<CtxStringLabel>("text").unwrap().value, "4");
+        assert_eq!(
+            ctx.input_label("text").unwrap().get_semantic_label_type(),
+            "CtxStringLabel"
+        );
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let ctx = NodeContext::new("fmt1", &INFO, NodeDataInputs::new());
+
+        let err = ctx.input::<CtxStringLabel>("text").unwrap_err();
+        assert!(err.to_string().contains("Missing required input 'text'"));
+        assert_eq!(err.kind(), crate::NodeErrorKind::InvalidInput);
+        assert_eq!(err.port(), Some("text"));
+        assert!(
+            ctx.optional_input::<CtxStringLabel>("text")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_output_uses_instance_name_and_declared_label() {
+        let mut ctx = NodeContext::new("fmt1", &INFO, NodeDataInputs::new());
+        ctx.output("formatted", CtxNumberLabel { value: 1.5 })
+            .unwrap();
+
+        let outputs = ctx.into_outputs();
+        let label = &outputs[&PortRef {
+            node_name: "fmt1".to_string(),
+            port_name: "formatted".to_string(),
+        }];
+        assert_eq!(label.get_semantic_label_type(), "CtxStringLabel");
+    }
+
+    #[test]
+    fn test_output_unknown_port() {
+        let mut ctx = NodeContext::new("fmt1", &INFO, NodeDataInputs::new());
+        assert!(
+            ctx.output("nope", CtxStringLabel { value: "x".into() })
+                .is_err()
+        );
+    }
+}
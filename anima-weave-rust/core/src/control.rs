@@ -0,0 +1,80 @@
+//! 控制流：控制信号与控制输入端口的激活模式
+//!
+//! 控制端口承载 [`SignalLabel`]，端口值为 `+`（激活）、`−`（非激活）或 `⊥`（尚无信号），
+//! 在代码中分别表示为 `Some(true)`、`Some(false)` 和 `None`。
+
+use crate::semantic_label;
+use serde::{Deserialize, Serialize};
+
+semantic_label! {
+    /// 控制信号，`active` 为 false 表示非激活信号
+    SignalLabel(active: bool) {}
+}
+
+impl SignalLabel {
+    pub const ACTIVE: SignalLabel = SignalLabel { active: true };
+    pub const INACTIVE: SignalLabel = SignalLabel { active: false };
+}
+
+/// 控制输入端口的激活模式：多个来源的信号如何合成端口值
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ActivationMode {
+    /// 全部来源激活时为 `+`，任一来源非激活时为 `−`
+    #[default]
+    And,
+    /// 任一来源激活时为 `+`，全部来源非激活时为 `−`
+    Or,
+    /// 全部来源到达后，恰好一个激活时为 `+`，否则为 `−`
+    Xor,
+}
+
+impl ActivationMode {
+    /// 计算端口值，`signals` 为各来源的信号，`None` 表示该来源尚无信号
+    pub fn port_value(self, signals: &[Option<bool>]) -> Option<bool> {
+        let active = signals.iter().filter(|s| **s == Some(true)).count();
+        let inactive = signals.iter().filter(|s| **s == Some(false)).count();
+        let all_arrived = active + inactive == signals.len();
+
+        match self {
+            ActivationMode::And if inactive > 0 => Some(false),
+            ActivationMode::And => all_arrived.then_some(true),
+            ActivationMode::Or if active > 0 => Some(true),
+            ActivationMode::Or => all_arrived.then_some(false),
+            ActivationMode::Xor => all_arrived.then_some(active == 1),
+        }
+    }
+}
+
+impl std::fmt::Display for ActivationMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ActivationMode::And => "AND",
+            ActivationMode::Or => "OR",
+            ActivationMode::Xor => "XOR",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_port_value() {
+        use ActivationMode::*;
+
+        assert_eq!(And.port_value(&[Some(true), Some(true)]), Some(true));
+        assert_eq!(And.port_value(&[Some(true), None]), None);
+        assert_eq!(And.port_value(&[Some(false), None]), Some(false));
+
+        assert_eq!(Or.port_value(&[Some(true), None]), Some(true));
+        assert_eq!(Or.port_value(&[Some(false), None]), None);
+        assert_eq!(Or.port_value(&[Some(false), Some(false)]), Some(false));
+
+        assert_eq!(Xor.port_value(&[Some(true), None]), None);
+        assert_eq!(Xor.port_value(&[Some(true), Some(false)]), Some(true));
+        assert_eq!(Xor.port_value(&[Some(true), Some(true)]), Some(false));
+    }
+}
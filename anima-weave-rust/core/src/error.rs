@@ -0,0 +1,212 @@
+use crate::semantic_label;
+use crate::types::PortName;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 节点执行错误
+///
+/// 按失败的性质分类，runtime 的重试、错误路由和上报策略依据 [`NodeErrorKind`] 决策，
+/// 不需要解析错误文本。`details` 携带可选的结构化信息（如 HTTP 状态码）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NodeError {
+    /// 输入缺失、类型不符或取值不合法
+    InvalidInput {
+        port: PortName,
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        details: Option<Value>,
+    },
+    /// 暂时性失败（网络抖动、限流等），可以重试
+    Transient {
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        details: Option<Value>,
+    },
+    /// 不可恢复的失败
+    Fatal {
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        details: Option<Value>,
+    },
+    /// 执行被取消
+    Cancelled { reason: String },
+}
+
+/// 错误类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeErrorKind {
+    InvalidInput,
+    Transient,
+    Fatal,
+    Cancelled,
+}
+
+impl NodeError {
+    pub fn invalid_input(port: impl Into<PortName>, message: impl Into<String>) -> Self {
+        NodeError::InvalidInput {
+            port: port.into(),
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn transient(message: impl Into<String>) -> Self {
+        NodeError::Transient {
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn fatal(message: impl Into<String>) -> Self {
+        NodeError::Fatal {
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn cancelled(reason: impl Into<String>) -> Self {
+        NodeError::Cancelled {
+            reason: reason.into(),
+        }
+    }
+
+    /// 附加结构化信息，对 `Cancelled` 无效
+    pub fn with_details(mut self, value: Value) -> Self {
+        match &mut self {
+            NodeError::InvalidInput { details, .. }
+            | NodeError::Transient { details, .. }
+            | NodeError::Fatal { details, .. } => *details = Some(value),
+            NodeError::Cancelled { .. } => {}
+        }
+        self
+    }
+
+    pub fn kind(&self) -> NodeErrorKind {
+        match self {
+            NodeError::InvalidInput { .. } => NodeErrorKind::InvalidInput,
+            NodeError::Transient { .. } => NodeErrorKind::Transient,
+            NodeError::Fatal { .. } => NodeErrorKind::Fatal,
+            NodeError::Cancelled { .. } => NodeErrorKind::Cancelled,
+        }
+    }
+
+    /// 是否值得重试
+    pub fn is_retryable(&self) -> bool {
+        self.kind() == NodeErrorKind::Transient
+    }
+
+    /// 出错的输入端口
+    pub fn port(&self) -> Option<&str> {
+        match self {
+            NodeError::InvalidInput { port, .. } => Some(port),
+            _ => None,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            NodeError::InvalidInput { message, .. }
+            | NodeError::Transient { message, .. }
+            | NodeError::Fatal { message, .. } => message,
+            NodeError::Cancelled { reason } => reason,
+        }
+    }
+
+    pub fn details(&self) -> Option<&Value> {
+        match self {
+            NodeError::InvalidInput { details, .. }
+            | NodeError::Transient { details, .. }
+            | NodeError::Fatal { details, .. } => details.as_ref(),
+            NodeError::Cancelled { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Display for NodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeError::Cancelled { reason } => write!(f, "Cancelled: {}", reason),
+            other => f.write_str(other.message()),
+        }
+    }
+}
+
+impl std::fmt::Display for NodeErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            NodeErrorKind::InvalidInput => "invalid_input",
+            NodeErrorKind::Transient => "transient",
+            NodeErrorKind::Fatal => "fatal",
+            NodeErrorKind::Cancelled => "cancelled",
+        };
+        f.write_str(name)
+    }
+}
+
+impl std::error::Error for NodeError {}
+
+/// 未分类的错误视为不可恢复；包装了 `NodeError` 的 anyhow 错误保留原分类
+impl From<anyhow::Error> for NodeError {
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast::<NodeError>() {
+            Ok(error) => error,
+            Err(error) => NodeError::fatal(format!("{:#}", error)),
+        }
+    }
+}
+
+semantic_label! {
+    /// 节点执行失败的错误，失败策略为 `RouteError` 时发到节点的 `error` 输出端口
+    ErrorLabel(node_name: String, error: NodeError) {}
+}
+
+impl ErrorLabel {
+    pub fn new(node_name: impl Into<String>, error: NodeError) -> Self {
+        Self {
+            node_name: node_name.into(),
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_kinds_and_details() {
+        let error = NodeError::invalid_input("a", "must be positive")
+            .with_details(serde_json::json!({ "value": -1 }));
+        assert_eq!(error.kind(), NodeErrorKind::InvalidInput);
+        assert_eq!(error.port(), Some("a"));
+        assert_eq!(error.details().unwrap()["value"], -1);
+        assert!(!error.is_retryable());
+
+        assert!(NodeError::transient("rate limited").is_retryable());
+        assert_eq!(
+            NodeError::cancelled("run aborted").to_string(),
+            "Cancelled: run aborted"
+        );
+
+        let json = serde_json::to_value(NodeError::transient("busy")).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "kind": "transient", "message": "busy" })
+        );
+    }
+
+    #[test]
+    fn test_from_anyhow() {
+        let error: NodeError = anyhow::anyhow!("boom").into();
+        assert_eq!(error, NodeError::fatal("boom"));
+
+        // 经过 anyhow 传递的 NodeError 保留分类
+        let wrapped = Err::<(), _>(NodeError::transient("timeout"))
+            .context("calling api")
+            .unwrap_err();
+        assert_eq!(NodeError::from(wrapped).kind(), NodeErrorKind::Transient);
+    }
+}
@@ -1,7 +1,10 @@
+use crate::node::{ERROR_PORT, FanIn, NodeInfo, PortDef};
+use crate::policy::{FailurePolicy, RetryPolicy};
 use crate::types::{NodeName, NodeType, PortName};
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 /// 端口引用，用于表示图中的一个唯一端口
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -22,6 +25,56 @@ pub struct Connection {
 pub struct NodeRef {
     pub name: NodeName,
     pub node_type: NodeType,
+    /// 节点实例配置，由可配置节点（如 ScriptNode）在构造时解析
+    #[serde(default)]
+    pub config: serde_json::Value,
+    /// 覆盖运行级别的失败策略
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_policy: Option<FailurePolicy>,
+    /// 可重试错误的重试策略，不设置时不重试
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+    /// 单次执行的超时时间（毫秒），由 runtime 强制
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+impl NodeRef {
+    pub fn new(name: impl Into<NodeName>, node_type: impl Into<NodeType>) -> Self {
+        Self {
+            name: name.into(),
+            node_type: node_type.into(),
+            config: serde_json::Value::Null,
+            failure_policy: None,
+            retry: None,
+            timeout_ms: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: serde_json::Value) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = Some(policy);
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_ms = Some(timeout.as_millis() as u64);
+        self
+    }
+
+    /// 单次执行的超时时间
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_ms.map(Duration::from_millis)
+    }
 }
 
 /// 计算图完整定义
@@ -29,15 +82,42 @@ pub struct NodeRef {
 pub struct Graph {
     pub nodes: Vec<NodeRef>,
     pub data_connections: Vec<Connection>,
+    /// 控制连接：控制输出端口 -> 控制输入端口，一个控制输入可以有多个来源
+    #[serde(default)]
+    pub control_connections: Vec<Connection>,
+    /// 图输入：由运行参数提供值的数据输入端口，除扇入端口外不能再有数据连接
+    #[serde(default)]
+    pub inputs: Vec<PortRef>,
 }
 
 impl Graph {
-    /// 验证图的完整性
+    /// 验证图的结构完整性（不依赖节点端口信息）
     pub fn validate(&self) -> Result<()> {
         self.validate_no_cycles()?;
-        self.validate_connections()?;
-        self.validate_required_ports()?;
-        self.validate_single_connections()?;
+        self.validate_no_duplicate_connections()?;
+        self.validate_single_connections(|_| false)?;
+        Ok(())
+    }
+
+    /// 结合节点实例的端口信息验证整张图
+    ///
+    /// `node_infos` 以节点实例名索引；`can_convert(from, to)` 判断
+    /// 输出端口标签能否转换为输入端口标签。声明了扇入模式的端口可以有多个连接
+    pub fn validate_with_nodes(
+        &self,
+        node_infos: &HashMap<NodeName, &NodeInfo>,
+        can_convert: impl Fn(&str, &str) -> bool,
+    ) -> Result<()> {
+        self.validate_no_cycles()?;
+        self.validate_no_duplicate_connections()?;
+        self.validate_single_connections(|to| {
+            find_port(node_infos, to, |info| &info.input_ports)
+                .is_ok_and(|port| port.fan_in.is_some())
+        })?;
+        self.validate_merge_keys(node_infos)?;
+        self.validate_connections(node_infos, can_convert)?;
+        self.validate_inputs(node_infos)?;
+        self.validate_required_ports(node_infos)?;
         Ok(())
     }
 
@@ -52,7 +132,11 @@ impl Graph {
             adj_list.insert(node.name.clone(), Vec::new());
         }
 
-        for conn in &self.data_connections {
+        for conn in self
+            .data_connections
+            .iter()
+            .chain(&self.control_connections)
+        {
             if let Some(neighbors) = adj_list.get_mut(&conn.from.node_name) {
                 neighbors.push(conn.to.node_name.clone());
             }
@@ -60,13 +144,13 @@ impl Graph {
 
         // DFS 检测环
         for node in &self.nodes {
-            if !visited.contains(&node.name) {
-                if self.has_cycle_dfs(&node.name, &adj_list, &mut visited, &mut rec_stack) {
-                    return Err(anyhow!(
-                        "Graph contains cycle involving node: {}",
-                        node.name
-                    ));
-                }
+            if !visited.contains(&node.name)
+                && self.has_cycle_dfs(&node.name, &adj_list, &mut visited, &mut rec_stack)
+            {
+                return Err(anyhow!(
+                    "Graph contains cycle involving node: {}",
+                    node.name
+                ));
             }
         }
 
@@ -99,31 +183,175 @@ impl Graph {
         false
     }
 
-    /// 验证连接的类型兼容性
-    fn validate_connections(&self) -> Result<()> {
-        // TODO: 实现 label 转换检测
-        // 需要查询输出端口的 label 类型，检查是否能转换为输入端口类型
+    /// 验证连接两端的端口存在且类型兼容，数据连接只连数据端口，控制连接只连控制端口
+    fn validate_connections(
+        &self,
+        node_infos: &HashMap<NodeName, &NodeInfo>,
+        can_convert: impl Fn(&str, &str) -> bool,
+    ) -> Result<()> {
+        let connections = self
+            .data_connections
+            .iter()
+            .map(|conn| (conn, false))
+            .chain(self.control_connections.iter().map(|conn| (conn, true)));
+        for (conn, control) in connections {
+            // 未声明的 `error` 输出端口是 runtime 提供的错误端口
+            let from =
+                find_port(node_infos, &conn.from, |info| &info.output_ports).or_else(|err| {
+                    match conn.from.port_name.as_str() {
+                        ERROR_PORT => Ok(PortDef::error_output()),
+                        _ => Err(err),
+                    }
+                })?;
+            let to = find_port(node_infos, &conn.to, |info| &info.input_ports)?;
+
+            for (port_ref, port) in [(&conn.from, from), (&conn.to, to)] {
+                if port.is_control() != control {
+                    return Err(anyhow!(
+                        "{} connection cannot use {} port {}:{}",
+                        if control { "Control" } else { "Data" },
+                        if port.is_control() { "control" } else { "data" },
+                        port_ref.node_name,
+                        port_ref.port_name
+                    ));
+                }
+            }
+
+            let from_label = from.port_type.semantic_label();
+            let to_label = to.port_type.semantic_label();
+            if from_label != to_label && !can_convert(from_label, to_label) {
+                return Err(anyhow!(
+                    "Cannot connect {}:{} ({}) to {}:{} ({})",
+                    conn.from.node_name,
+                    conn.from.port_name,
+                    from_label,
+                    conn.to.node_name,
+                    conn.to.port_name,
+                    to_label
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 验证图输入都是存在的数据输入端口
+    fn validate_inputs(&self, node_infos: &HashMap<NodeName, &NodeInfo>) -> Result<()> {
+        for input in &self.inputs {
+            let port = find_port(node_infos, input, |info| &info.input_ports)?;
+            if port.is_control() {
+                return Err(anyhow!(
+                    "Graph input {}:{} is a control port",
+                    input.node_name,
+                    input.port_name
+                ));
+            }
+        }
         Ok(())
     }
 
-    /// 验证必填端口都有连接
-    fn validate_required_ports(&self) -> Result<()> {
-        // TODO: 实现必填端口检查
-        // 需要查询节点的 required_ports，确保每个都有连接
+    /// 验证必填端口都有连接（数据端口也可以是图输入）
+    fn validate_required_ports(&self, node_infos: &HashMap<NodeName, &NodeInfo>) -> Result<()> {
+        for node in &self.nodes {
+            let info = node_infos
+                .get(&node.name)
+                .ok_or_else(|| anyhow!("Missing node info for {}", node.name))?;
+
+            for port in info.input_ports.iter().filter(|port| port.required) {
+                let (connections, inputs): (&[Connection], &[PortRef]) = if port.is_control() {
+                    (&self.control_connections, &[])
+                } else {
+                    (&self.data_connections, &self.inputs)
+                };
+                let connected = connections
+                    .iter()
+                    .map(|conn| &conn.to)
+                    .chain(inputs)
+                    .any(|to| to.node_name == node.name && to.port_name == port.name);
+                if !connected {
+                    return Err(anyhow!(
+                        "Required input port {}:{} is not connected",
+                        node.name,
+                        port.name
+                    ));
+                }
+            }
+        }
         Ok(())
     }
 
-    /// 验证每个端口至多一个连接
-    fn validate_single_connections(&self) -> Result<()> {
+    /// 验证没有重复的连接和图输入：同一对端口之间的多条连接共用一个队列，
+    /// 扇入端口会把它们当成同一个来源
+    fn validate_no_duplicate_connections(&self) -> Result<()> {
+        let connections = [&self.data_connections, &self.control_connections];
+        for connections in connections {
+            let mut seen = HashSet::new();
+            for conn in connections {
+                if !seen.insert((&conn.from, &conn.to)) {
+                    return Err(anyhow!(
+                        "Duplicate connection {}:{} -> {}:{}",
+                        conn.from.node_name,
+                        conn.from.port_name,
+                        conn.to.node_name,
+                        conn.to.port_name
+                    ));
+                }
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for input in &self.inputs {
+            if !seen.insert(input) {
+                return Err(anyhow!(
+                    "Duplicate graph input {}:{}",
+                    input.node_name,
+                    input.port_name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 验证 `MergeByKey` 端口的来源节点名互不相同：记录以来源节点名为键，
+    /// 图输入的键是端口所在的节点
+    fn validate_merge_keys(&self, node_infos: &HashMap<NodeName, &NodeInfo>) -> Result<()> {
+        let mut keys: HashMap<&PortRef, HashSet<&NodeName>> = HashMap::new();
+        let sources = self
+            .data_connections
+            .iter()
+            .map(|conn| (&conn.to, &conn.from.node_name))
+            .chain(self.inputs.iter().map(|input| (input, &input.node_name)));
+        for (to, key) in sources {
+            let merge_by_key = find_port(node_infos, to, |info| &info.input_ports)
+                .is_ok_and(|port| port.fan_in == Some(FanIn::MergeByKey));
+            if merge_by_key && !keys.entry(to).or_default().insert(key) {
+                return Err(anyhow!(
+                    "Input port {}:{} merges by key but has several sources from node {}",
+                    to.node_name,
+                    to.port_name,
+                    key
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 验证每个数据输入端口至多一个连接（控制输入按激活模式合成多个来源），
+    /// 图输入视为一个连接；`fan_in(port)` 为 true 的端口不受限制
+    fn validate_single_connections(&self, fan_in: impl Fn(&PortRef) -> bool) -> Result<()> {
         let mut input_ports = HashSet::new();
 
-        for conn in &self.data_connections {
-            let input_port = (&conn.to.node_name, &conn.to.port_name);
-            if input_ports.contains(&input_port) {
+        let targets = self
+            .data_connections
+            .iter()
+            .map(|conn| &conn.to)
+            .chain(&self.inputs);
+        for to in targets {
+            let input_port = (&to.node_name, &to.port_name);
+            if input_ports.contains(&input_port) && !fan_in(to) {
                 return Err(anyhow!(
                     "Input port {}:{} has multiple connections",
-                    conn.to.node_name,
-                    conn.to.port_name
+                    to.node_name,
+                    to.port_name
                 ));
             }
             input_ports.insert(input_port);
@@ -132,3 +360,256 @@ impl Graph {
         Ok(())
     }
 }
+
+fn find_port<'a>(
+    node_infos: &HashMap<NodeName, &'a NodeInfo>,
+    port_ref: &PortRef,
+    ports: impl Fn(&'a NodeInfo) -> &'a Vec<PortDef>,
+) -> Result<&'a PortDef> {
+    let info = node_infos
+        .get(&port_ref.node_name)
+        .ok_or_else(|| anyhow!("Connection references unknown node {}", port_ref.node_name))?;
+    ports(info)
+        .iter()
+        .find(|port| port.name == port_ref.port_name)
+        .ok_or_else(|| {
+            anyhow!(
+                "Node {} ({}) has no port '{}'",
+                port_ref.node_name,
+                info.name,
+                port_ref.port_name
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorLabel;
+    use crate::node::{ExecutionMode, FanIn};
+    use crate::semantic_label;
+
+    semantic_label! {
+        GraphNumberLabel(value: f64) {}
+    }
+
+    semantic_label! {
+        GraphStringLabel(value: String) {}
+    }
+
+    fn info(inputs: Vec<PortDef>, outputs: Vec<PortDef>) -> &'static NodeInfo {
+        Box::leak(Box::new(NodeInfo {
+            name: "TestNode",
+            description: "",
+            input_ports: inputs,
+            output_ports: outputs,
+            mode: ExecutionMode::Concurrent,
+        }))
+    }
+
+    fn connect(from: (&str, &str), to: (&str, &str)) -> Connection {
+        Connection {
+            from: PortRef {
+                node_name: from.0.to_string(),
+                port_name: from.1.to_string(),
+            },
+            to: PortRef {
+                node_name: to.0.to_string(),
+                port_name: to.1.to_string(),
+            },
+        }
+    }
+
+    fn graph(connections: Vec<Connection>) -> (Graph, HashMap<NodeName, &'static NodeInfo>) {
+        let graph = Graph {
+            nodes: vec![
+                NodeRef::new("source", "Source"),
+                NodeRef::new("sink", "Sink"),
+            ],
+            data_connections: connections,
+            control_connections: vec![],
+            inputs: vec![],
+        };
+        let infos = HashMap::from([
+            (
+                "source".to_string(),
+                info(
+                    vec![],
+                    vec![PortDef::output_data::<GraphNumberLabel>("out")],
+                ),
+            ),
+            (
+                "sink".to_string(),
+                info(
+                    vec![PortDef::required_data::<GraphStringLabel>("text")],
+                    vec![],
+                ),
+            ),
+        ]);
+        (graph, infos)
+    }
+
+    #[test]
+    fn test_incompatible_labels_are_rejected() {
+        let (graph, infos) = graph(vec![connect(("source", "out"), ("sink", "text"))]);
+
+        assert!(graph.validate_with_nodes(&infos, |_, _| false).is_err());
+        assert!(graph.validate_with_nodes(&infos, |_, _| true).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_port_is_rejected() {
+        let (graph, infos) = graph(vec![connect(("source", "nope"), ("sink", "text"))]);
+
+        let err = graph.validate_with_nodes(&infos, |_, _| true).unwrap_err();
+        assert!(err.to_string().contains("has no port 'nope'"));
+    }
+
+    #[test]
+    fn test_implicit_error_port() {
+        let (mut graph, mut infos) = graph(vec![connect(("source", "error"), ("sink", "text"))]);
+
+        // error 端口承载 ErrorLabel，不需要声明
+        let err = graph.validate_with_nodes(&infos, |_, _| false).unwrap_err();
+        assert!(err.to_string().contains("ErrorLabel"), "{}", err);
+
+        infos.insert(
+            "sink".to_string(),
+            info(vec![PortDef::required_data::<ErrorLabel>("text")], vec![]),
+        );
+        assert!(graph.validate_with_nodes(&infos, |_, _| false).is_ok());
+
+        graph.data_connections = vec![connect(("source", "errors"), ("sink", "text"))];
+        assert!(graph.validate_with_nodes(&infos, |_, _| true).is_err());
+    }
+
+    #[test]
+    fn test_control_connections() {
+        let (mut graph, mut infos) = graph(vec![connect(("source", "out"), ("sink", "text"))]);
+        infos.insert(
+            "source".to_string(),
+            info(
+                vec![],
+                vec![
+                    PortDef::output_data::<GraphNumberLabel>("out"),
+                    PortDef::control_output("done"),
+                    PortDef::control_output("ready"),
+                ],
+            ),
+        );
+        infos.insert(
+            "sink".to_string(),
+            info(
+                vec![
+                    PortDef::required_data::<GraphStringLabel>("text"),
+                    PortDef::control_input("trigger"),
+                ],
+                vec![],
+            ),
+        );
+
+        // 控制输入可以有多个来源
+        graph.control_connections = vec![
+            connect(("source", "done"), ("sink", "trigger")),
+            connect(("source", "ready"), ("sink", "trigger")),
+        ];
+        assert!(graph.validate_with_nodes(&infos, |_, _| true).is_ok());
+
+        // 数据连接不能连到控制端口
+        graph
+            .data_connections
+            .push(connect(("source", "done"), ("sink", "trigger")));
+        let err = graph.validate_with_nodes(&infos, |_, _| true).unwrap_err();
+        assert!(err.to_string().contains("control port"), "{}", err);
+    }
+
+    #[test]
+    fn test_unconnected_required_port_is_rejected() {
+        let (mut graph, infos) = graph(vec![]);
+
+        let err = graph.validate_with_nodes(&infos, |_, _| true).unwrap_err();
+        assert!(err.to_string().contains("sink:text"));
+
+        // 声明为图输入后由运行参数提供
+        let text = connect(("source", "out"), ("sink", "text")).to;
+        graph.inputs = vec![text.clone()];
+        assert!(graph.validate_with_nodes(&infos, |_, _| true).is_ok());
+
+        // 图输入不能同时有数据连接
+        graph.data_connections = vec![connect(("source", "out"), ("sink", "text"))];
+        let err = graph.validate_with_nodes(&infos, |_, _| true).unwrap_err();
+        assert!(err.to_string().contains("multiple connections"), "{}", err);
+    }
+
+    #[test]
+    fn test_fan_in_port_accepts_multiple_connections() {
+        let connections = vec![
+            connect(("source", "out"), ("sink", "text")),
+            connect(("source", "other"), ("sink", "text")),
+        ];
+        let (graph, mut infos) = graph(connections);
+        infos.insert(
+            "source".to_string(),
+            info(
+                vec![],
+                vec![
+                    PortDef::output_data::<GraphNumberLabel>("out"),
+                    PortDef::output_data::<GraphNumberLabel>("other"),
+                ],
+            ),
+        );
+
+        let err = graph.validate_with_nodes(&infos, |_, _| true).unwrap_err();
+        assert!(err.to_string().contains("multiple connections"), "{}", err);
+
+        infos.insert(
+            "sink".to_string(),
+            info(
+                vec![
+                    PortDef::required_data::<GraphStringLabel>("text").with_fan_in(FanIn::Collect),
+                ],
+                vec![],
+            ),
+        );
+        assert!(graph.validate_with_nodes(&infos, |_, _| true).is_ok());
+        // 每个来源仍然按端口声明的标签检查
+        assert!(graph.validate_with_nodes(&infos, |_, _| false).is_err());
+        // 不知道端口信息时仍然只允许一个连接
+        assert!(graph.validate().is_err());
+
+        // 按来源节点名合并时，同一节点的两个端口会得到同一个键
+        infos.insert(
+            "sink".to_string(),
+            info(
+                vec![
+                    PortDef::required_data::<GraphStringLabel>("text")
+                        .with_fan_in(FanIn::MergeByKey),
+                ],
+                vec![],
+            ),
+        );
+        let err = graph.validate_with_nodes(&infos, |_, _| true).unwrap_err();
+        assert!(err.to_string().contains("merges by key"), "{}", err);
+    }
+
+    #[test]
+    fn test_duplicate_connections_are_rejected() {
+        let connections = vec![
+            connect(("source", "out"), ("sink", "text")),
+            connect(("source", "out"), ("sink", "text")),
+        ];
+        let (graph, mut infos) = graph(connections);
+        infos.insert(
+            "sink".to_string(),
+            info(
+                vec![
+                    PortDef::required_data::<GraphStringLabel>("text").with_fan_in(FanIn::Collect),
+                ],
+                vec![],
+            ),
+        );
+
+        let err = graph.validate_with_nodes(&infos, |_, _| true).unwrap_err();
+        assert!(err.to_string().contains("Duplicate connection"), "{}", err);
+    }
+}
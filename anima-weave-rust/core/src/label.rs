@@ -80,6 +80,12 @@ pub trait SemanticLabel: Send + Sync + Debug + 'static {
     /// - 生成类型转换文档
     fn conversion_map(&self) -> HashMap<&'static str, ConversionFn>;
 
+    /// 序列化为 JSON 对象（字段名 -> 值）
+    ///
+    /// 标签跨边界传递（WASM 模块、外部进程等）时使用的序列化形式，
+    /// 反序列化见 LabelRegistration::from_json
+    fn to_json(&self) -> serde_json::Value;
+
     /// 获取支持的转换目标类型列表（用于静态分析）
     fn supported_conversions(&self) -> Vec<&'static str> {
         self.conversion_map().keys().cloned().collect()
@@ -101,7 +107,16 @@ pub trait SemanticLabel: Send + Sync + Debug + 'static {
     ) -> Result<Box<dyn SemanticLabel>, TransformError> {
         let conversion_map = self.conversion_map();
 
-        match conversion_map.get(target_type) {
+        // 转换表的 key 是宏中书写的类型路径（如 "super::StringLabel"），
+        // 因此精确匹配失败时再按路径最后一段匹配标签类型名
+        let conversion_fn = conversion_map.get(target_type).or_else(|| {
+            conversion_map
+                .iter()
+                .find(|(path, _)| label_type_name(path) == label_type_name(target_type))
+                .map(|(_, conversion_fn)| conversion_fn)
+        });
+
+        match conversion_fn {
             Some(conversion_fn) => conversion_fn(self.as_any()),
             None => Err(TransformError::IncompatibleTypes {
                 from: self.get_semantic_label_type(),
@@ -111,6 +126,43 @@ pub trait SemanticLabel: Send + Sync + Debug + 'static {
     }
 }
 
+/// 从类型路径中取出标签类型名，例如 "super::StringLabel" -> "StringLabel"
+pub fn label_type_name(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path).trim()
+}
+
+/// 从 JSON 反序列化标签的函数类型
+pub type LabelFromJsonFn = fn(&serde_json::Value) -> Result<Box<dyn SemanticLabel>, TransformError>;
+
+/// 语义标签注册信息
+///
+/// `semantic_label!` 定义的标签会通过 inventory 自动注册，
+/// 插件也可以用它显式注册自己的标签
+#[derive(Debug, Clone, Copy)]
+pub struct LabelRegistration {
+    pub name: &'static str,
+    /// 可转换到的目标标签（宏中书写的类型路径）
+    pub conversions: &'static [&'static str],
+    /// 从 `SemanticLabel::to_json` 的输出重建标签
+    pub from_json: LabelFromJsonFn,
+}
+
+impl LabelRegistration {
+    pub const fn new(
+        name: &'static str,
+        conversions: &'static [&'static str],
+        from_json: LabelFromJsonFn,
+    ) -> Self {
+        Self {
+            name,
+            conversions,
+            from_json,
+        }
+    }
+}
+
+inventory::collect!(LabelRegistration);
+
 /// 定义语义标签的宏 - 自动化所有样板代码
 ///
 /// 使用方式：
@@ -143,12 +195,16 @@ pub trait SemanticLabel: Send + Sync + Debug + 'static {
 /// - 转换目标类型必须存在（编译器验证路径）
 /// - 转换函数签名自动匹配 ConversionFn
 ///
+/// 字段类型需要实现 serde 的 Serialize 和 Deserialize，用于 JSON 序列化
+///
 /// 自动生成：
 /// - struct 定义 (Debug + Clone)
 /// - type_name() 返回类型名字符串
 /// - as_any() 标准实现  
 /// - conversion_map() 基于转换规则
 /// - try_convert_to() 默认实现
+/// - to_json() / from_json() JSON 序列化
+/// - label_registration() 及 inventory 自动注册
 #[macro_export]
 macro_rules! semantic_label {
     // 支持完整类型路径的语法：编译时类型检查
@@ -207,6 +263,64 @@ macro_rules! semantic_label {
 
                 map
             }
+
+            fn to_json(&self) -> $crate::__private::serde_json::Value {
+                #[allow(unused_mut)]
+                let mut map = $crate::__private::serde_json::Map::new();
+                $(
+                    map.insert(
+                        stringify!($field).to_string(),
+                        $crate::__private::serde_json::to_value(&self.$field)
+                            .unwrap_or($crate::__private::serde_json::Value::Null),
+                    );
+                )*
+                $crate::__private::serde_json::Value::Object(map)
+            }
+        }
+
+        impl $name {
+            /// 从 to_json() 的输出重建标签
+            #[allow(unused_variables)]
+            pub fn from_json(
+                value: &$crate::__private::serde_json::Value,
+            ) -> Result<Box<dyn $crate::label::SemanticLabel>, $crate::label::TransformError> {
+                Ok(Box::new($name {
+                    $(
+                        $field: $crate::__private::serde_json::from_value(
+                            value.get(stringify!($field)).cloned().ok_or_else(|| {
+                                $crate::label::TransformError::ConversionFailed {
+                                    reason: format!(
+                                        "{} is missing field '{}'",
+                                        stringify!($name),
+                                        stringify!($field)
+                                    ),
+                                }
+                            })?,
+                        )
+                        .map_err(|e| $crate::label::TransformError::ConversionFailed {
+                            reason: format!("{}.{}: {}", stringify!($name), stringify!($field), e),
+                        })?,
+                    )*
+                }))
+            }
+
+            /// 标签注册信息，供插件显式注册使用
+            #[allow(dead_code)]
+            pub fn label_registration() -> $crate::label::LabelRegistration {
+                $crate::label::LabelRegistration::new(
+                    stringify!($name),
+                    &[$(stringify!($target_type)),*],
+                    $name::from_json,
+                )
+            }
+        }
+
+        $crate::__private::inventory::submit! {
+            $crate::label::LabelRegistration::new(
+                stringify!($name),
+                &[$(stringify!($target_type)),*],
+                $name::from_json,
+            )
         }
     };
 }
@@ -342,6 +456,41 @@ mod tests {
         assert_eq!(number_label.value, 4.0); // 1.5 + 2.5
     }
 
+    #[test]
+    fn test_conversion_by_label_type_name() {
+        assert_eq!(label_type_name("super::StringLabel"), "StringLabel");
+        assert_eq!(label_type_name("StringLabel"), "StringLabel");
+
+        let string_label = TestStringLabel {
+            value: "hi".to_string(),
+        };
+        let result = string_label
+            .try_convert_to("tests::TestNumberLabel")
+            .unwrap();
+        assert_eq!(result.get_semantic_label_type(), "TestNumberLabel");
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let label = TestComplexLabel {
+            x: 1.5,
+            y: 2.0,
+            name: "p".to_string(),
+        };
+
+        let json = label.to_json();
+        assert_eq!(json, serde_json::json!({"x": 1.5, "y": 2.0, "name": "p"}));
+
+        let restored = TestComplexLabel::from_json(&json).unwrap();
+        let restored = restored
+            .as_any()
+            .downcast_ref::<TestComplexLabel>()
+            .unwrap();
+        assert_eq!(restored.name, "p");
+
+        assert!(TestComplexLabel::from_json(&serde_json::json!({"x": 1.0})).is_err());
+    }
+
     #[test]
     fn test_unsupported_conversion() {
         let label = TestStringLabel {
@@ -1,13 +1,40 @@
+pub mod cancellation;
+pub mod collection;
+pub mod context;
+pub mod control;
+pub mod error;
 pub mod graph;
 pub mod label;
 pub mod node;
+pub mod policy;
+pub mod state;
 pub mod types;
 
 // 重新导出核心类型
+pub use cancellation::RunCancellation;
+pub use collection::{ArrayLabel, RecordLabel};
+pub use context::NodeContext;
+pub use control::{ActivationMode, SignalLabel};
+pub use error::{ErrorLabel, NodeError, NodeErrorKind};
 pub use graph::{Graph, PortRef};
-pub use label::SemanticLabel;
-pub use node::{Node, NodeInfo, PortDef};
+pub use label::{LabelRegistration, SemanticLabel};
+pub use node::{
+    AsyncNode, ERROR_PORT, ExecutionMode, FanIn, Node, NodeFuture, NodeImpl, NodeInfo, PortDef,
+    PortType,
+};
+pub use policy::{FailurePolicy, RetryPolicy};
+pub use state::NodeState;
 pub use types::{NodeDataInputs, NodeDataOutputs, NodeName, PortName};
 
+/// anima-weave-core 版本，插件加载时用于兼容性校验
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// Error type for AnimaWeave operations
 pub type AnimaWeaveError = anyhow::Error;
+
+/// 宏生成代码使用的依赖，不属于公开 API
+#[doc(hidden)]
+pub mod __private {
+    pub use inventory;
+    pub use serde_json;
+}
@@ -1,6 +1,16 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
-use crate::types::{NodeDataInputs, NodeDataOutputs, PortName};
+use crate::context::NodeContext;
+use crate::control::{ActivationMode, SignalLabel};
+use crate::error::{ErrorLabel, NodeError};
+use crate::label::SemanticLabel;
+use crate::state::NodeState;
+use crate::types::PortName;
 
 // Re-exporting from graph for convenience
 
@@ -9,12 +19,71 @@ use crate::types::{NodeDataInputs, NodeDataOutputs, PortName};
 pub enum PortType {
     /// 数据端口，可以承载任意 SemanticLabel
     Data { semantic_label: &'static str },
+    /// 控制端口，承载 SignalLabel；`activation` 只对输入端口有意义
+    Control { activation: ActivationMode },
+}
+
+impl PortType {
+    /// 端口声明的语义标签类型名
+    pub fn semantic_label(&self) -> &'static str {
+        match self {
+            PortType::Data { semantic_label } => semantic_label,
+            PortType::Control { .. } => {
+                <SignalLabel as crate::label::SemanticLabel>::semantic_label_type()
+            }
+        }
+    }
+
+    /// 按语义标签确定端口类型：`SignalLabel` 端口即控制端口（AND 激活），其余为数据端口
+    pub fn for_label(semantic_label: &'static str) -> Self {
+        if semantic_label == <SignalLabel as crate::label::SemanticLabel>::semantic_label_type() {
+            PortType::Control {
+                activation: ActivationMode::And,
+            }
+        } else {
+            PortType::Data { semantic_label }
+        }
+    }
+
+    pub fn is_control(&self) -> bool {
+        matches!(self, PortType::Control { .. })
+    }
 }
 
 impl std::fmt::Display for PortType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             PortType::Data { semantic_label } => write!(f, "Data({})", semantic_label),
+            PortType::Control { activation } => write!(f, "Control({})", activation),
+        }
+    }
+}
+
+/// runtime 为每个节点提供的错误输出端口名，承载 [`ErrorLabel`]
+///
+/// 节点不需要声明这个端口，见 [`crate::FailurePolicy::RouteError`]
+pub const ERROR_PORT: &str = "error";
+
+/// 数据输入端口的扇入模式：端口接收多个连接时如何合并各来源的输入
+///
+/// 端口声明的语义标签是单个来源的标签，连接按它验证和转换
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FanIn {
+    /// 等待所有来源，按连接顺序收集为 [`ArrayLabel`](crate::ArrayLabel)
+    Collect,
+    /// 第一个到达的数据胜出，其余来源同一轮的输入被丢弃
+    FirstArrival,
+    /// 等待所有来源，按来源节点名合并为 [`RecordLabel`](crate::RecordLabel)
+    MergeByKey,
+}
+
+impl std::fmt::Display for FanIn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FanIn::Collect => f.write_str("collect"),
+            FanIn::FirstArrival => f.write_str("first_arrival"),
+            FanIn::MergeByKey => f.write_str("merge_by_key"),
         }
     }
 }
@@ -25,36 +94,131 @@ pub struct PortDef {
     pub name: PortName,
     pub port_type: PortType,
     pub required: bool,
+    /// 扇入模式，设置后数据输入端口可以有多个连接
+    pub fan_in: Option<FanIn>,
+    /// 可选输入端口的默认值：端口未连接、上游被跳过或等待超时时使用
+    pub default: Option<Box<dyn SemanticLabel>>,
+    /// 已连接的可选输入端口在其他输入就绪后最多等待多久，超时后不等它执行；
+    /// 不设置时一直等到输入或跳过消息到达
+    pub wait: Option<Duration>,
 }
 
 impl PortDef {
     pub fn required_data<T: crate::label::SemanticLabel>(name: &'static str) -> Self {
         Self {
             name: name.to_string(),
-            port_type: PortType::Data {
-                semantic_label: T::semantic_label_type(),
-            },
+            port_type: PortType::for_label(T::semantic_label_type()),
             required: true,
+            fan_in: None,
+            default: None,
+            wait: None,
         }
     }
 
     pub fn optional_data<T: crate::label::SemanticLabel>(name: &'static str) -> Self {
         Self {
             name: name.to_string(),
-            port_type: PortType::Data {
-                semantic_label: T::semantic_label_type(),
-            },
+            port_type: PortType::for_label(T::semantic_label_type()),
             required: false,
+            fan_in: None,
+            default: None,
+            wait: None,
         }
     }
 
     pub fn output_data<T: crate::label::SemanticLabel>(name: &'static str) -> Self {
         Self {
             name: name.to_string(),
-            port_type: PortType::Data {
-                semantic_label: T::semantic_label_type(),
+            port_type: PortType::for_label(T::semantic_label_type()),
+            required: true,
+            fan_in: None,
+            default: None,
+            wait: None,
+        }
+    }
+
+    /// 控制输入端口，使用 AND 激活模式
+    ///
+    /// 控制输入默认可选：未连接时不参与激活判断
+    pub fn control_input(name: &'static str) -> Self {
+        Self::control_input_with(name, ActivationMode::And)
+    }
+
+    pub fn control_input_with(name: &'static str, activation: ActivationMode) -> Self {
+        Self {
+            name: name.to_string(),
+            port_type: PortType::Control { activation },
+            required: false,
+            fan_in: None,
+            default: None,
+            wait: None,
+        }
+    }
+
+    pub fn control_output(name: &'static str) -> Self {
+        Self {
+            name: name.to_string(),
+            port_type: PortType::Control {
+                activation: ActivationMode::And,
             },
             required: true,
+            fan_in: None,
+            default: None,
+            wait: None,
+        }
+    }
+
+    /// 设置数据输入端口的扇入模式
+    pub fn with_fan_in(mut self, fan_in: FanIn) -> Self {
+        self.fan_in = Some(fan_in);
+        self
+    }
+
+    /// 设置可选输入端口的默认值
+    pub fn with_default<L: SemanticLabel>(mut self, label: L) -> Self {
+        self.default = Some(Box::new(label));
+        self
+    }
+
+    /// 设置已连接的可选输入端口的最长等待时间
+    pub fn with_wait(mut self, wait: Duration) -> Self {
+        self.wait = Some(wait);
+        self
+    }
+
+    pub fn is_control(&self) -> bool {
+        self.port_type.is_control()
+    }
+
+    /// 收到的是合并后的集合标签（[`FanIn::Collect`] / [`FanIn::MergeByKey`]），
+    /// 而不是端口声明的标签
+    pub fn is_collection(&self) -> bool {
+        matches!(self.fan_in, Some(FanIn::Collect | FanIn::MergeByKey))
+    }
+
+    /// 隐式的错误输出端口
+    pub fn error_output() -> &'static PortDef {
+        static ERROR_OUTPUT: LazyLock<PortDef> =
+            LazyLock::new(|| PortDef::output_data::<ErrorLabel>(ERROR_PORT));
+        &ERROR_OUTPUT
+    }
+}
+
+/// 节点的并发执行模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ExecutionMode {
+    /// 可以与其他节点同时执行
+    #[default]
+    Concurrent,
+    /// 独占执行：执行期间图中没有其他节点在运行
+    Sequential,
+}
+
+impl std::fmt::Display for ExecutionMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutionMode::Concurrent => f.write_str("Concurrent"),
+            ExecutionMode::Sequential => f.write_str("Sequential"),
         }
     }
 }
@@ -66,15 +230,112 @@ pub struct NodeInfo {
     pub description: &'static str,
     pub input_ports: Vec<PortDef>,
     pub output_ports: Vec<PortDef>,
+    pub mode: ExecutionMode,
+}
+
+impl NodeInfo {
+    /// 输入端口的默认值
+    pub fn input_default(&self, port_name: &str) -> Option<&dyn SemanticLabel> {
+        self.input_ports
+            .iter()
+            .find(|port| port.name == port_name)
+            .and_then(|port| port.default.as_deref())
+    }
 }
 
 /// 可执行节点接口
 pub trait Node: Send + Sync + Debug {
     fn info(&self) -> &'static NodeInfo;
 
-    fn execute(&self, inputs: NodeDataInputs) -> Result<NodeDataOutputs, anyhow::Error>;
+    /// 执行节点逻辑：通过 `ctx` 读取输入、写入输出
+    ///
+    /// 失败时返回的 [`NodeError`] 决定 runtime 如何处理（重试、上报等）；
+    /// `anyhow::Error` 可以直接用 `?` 传播，视为不可恢复的失败
+    fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError>;
+
+    /// 节点实例启动时调用一次，可以在这里建立连接、初始化状态
+    ///
+    /// 返回错误时节点实例不会启动
+    fn on_start(&self, _state: &NodeState) -> Result<(), NodeError> {
+        Ok(())
+    }
+
+    /// 节点实例停止时调用一次，用于清理资源
+    fn on_stop(&self, _state: &NodeState) -> Result<(), NodeError> {
+        Ok(())
+    }
 
     fn node_type(&self) -> &'static str {
         self.info().name
     }
 }
+
+/// 异步节点执行返回的 Future
+pub type NodeFuture<'a> = Pin<Box<dyn Future<Output = Result<(), NodeError>> + Send + 'a>>;
+
+/// 异步节点接口
+///
+/// 适用于 HTTP、文件、LLM 等 I/O 型节点，执行期间不会阻塞 runtime 工作线程
+pub trait AsyncNode: Send + Sync + Debug {
+    fn info(&self) -> &'static NodeInfo;
+
+    /// 执行节点逻辑：通过 `ctx` 读取输入、写入输出
+    fn execute<'a>(&'a self, ctx: &'a mut NodeContext) -> NodeFuture<'a>;
+
+    /// 节点实例启动时调用一次，见 [`Node::on_start`]
+    fn on_start<'a>(&'a self, _state: &'a NodeState) -> NodeFuture<'a> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// 节点实例停止时调用一次，见 [`Node::on_stop`]
+    fn on_stop<'a>(&'a self, _state: &'a NodeState) -> NodeFuture<'a> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn node_type(&self) -> &'static str {
+        self.info().name
+    }
+}
+
+/// 节点实现 - 同步节点或异步节点
+///
+/// registry 和 runtime 都通过它统一处理两种节点
+#[derive(Debug, Clone)]
+pub enum NodeImpl {
+    /// 同步节点，runtime 会把它放到阻塞线程池中执行
+    Sync(Arc<dyn Node>),
+    /// 异步节点，runtime 直接 await
+    Async(Arc<dyn AsyncNode>),
+}
+
+impl NodeImpl {
+    pub fn info(&self) -> &'static NodeInfo {
+        match self {
+            NodeImpl::Sync(node) => node.info(),
+            NodeImpl::Async(node) => node.info(),
+        }
+    }
+
+    pub fn node_type(&self) -> &'static str {
+        match self {
+            NodeImpl::Sync(node) => node.node_type(),
+            NodeImpl::Async(node) => node.node_type(),
+        }
+    }
+
+    pub fn is_async(&self) -> bool {
+        matches!(self, NodeImpl::Async(_))
+    }
+}
+
+impl From<Box<dyn Node>> for NodeImpl {
+    fn from(node: Box<dyn Node>) -> Self {
+        NodeImpl::Sync(Arc::from(node))
+    }
+}
+
+impl From<Box<dyn AsyncNode>> for NodeImpl {
+    fn from(node: Box<dyn AsyncNode>) -> Self {
+        NodeImpl::Async(Arc::from(node))
+    }
+}
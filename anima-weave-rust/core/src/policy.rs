@@ -0,0 +1,115 @@
+//! 节点执行失败时 runtime 的处理策略：失败传播与重试
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 失败策略，可以在运行级别设置，并按节点实例覆盖
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailurePolicy {
+    /// 立即取消整个运行，不再启动新的节点执行
+    FailFast,
+    /// 依赖失败节点的下游节点标记为跳过，互不依赖的分支继续执行
+    #[default]
+    SkipDownstream,
+    /// 把错误作为 `ErrorLabel` 发到 `error` 输出端口，由图自行处理；
+    /// 其余输出的下游标记为跳过
+    RouteError,
+}
+
+/// 节点实例的重试策略
+///
+/// 只有 [`NodeError::is_retryable`](crate::NodeError::is_retryable) 的错误会重试。
+/// 第 n 次重试前等待 `initial_backoff_ms * multiplier^(n-1)`，不超过 `max_backoff_ms`，
+/// 再按 `jitter` 比例随机缩短，避免多个节点同时重试
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// 最多执行次数，包括第一次
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub multiplier: f64,
+    /// 抖动比例，取值 0.0..=1.0
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 10_000,
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    /// 不重试
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff_ms = initial.as_millis() as u64;
+        self.max_backoff_ms = max.as_millis() as u64;
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// 第 `attempt` 次执行（从 1 开始）失败后是否还能重试
+    pub fn should_retry(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts
+    }
+
+    /// 第 `attempt` 次执行失败后的等待时间，`sample` 是 `[0, 1)` 内的随机数
+    pub fn backoff(&self, attempt: u32, sample: f64) -> Duration {
+        let exponent = attempt.saturating_sub(1) as i32;
+        let base = (self.initial_backoff_ms as f64 * self.multiplier.powi(exponent))
+            .min(self.max_backoff_ms as f64);
+        let jitter = self.jitter.clamp(0.0, 1.0) * sample.clamp(0.0, 1.0);
+        Duration::from_millis((base * (1.0 - jitter)) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_backoff() {
+        let policy = RetryPolicy::new(4)
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(300))
+            .with_jitter(0.5);
+
+        assert_eq!(policy.backoff(1, 0.0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2, 0.0), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3, 0.0), Duration::from_millis(300));
+        assert_eq!(policy.backoff(2, 1.0), Duration::from_millis(100));
+
+        assert!(policy.should_retry(3));
+        assert!(!policy.should_retry(4));
+        assert!(!RetryPolicy::none().should_retry(1));
+
+        let parsed: RetryPolicy = serde_json::from_str(r#"{"max_attempts": 5}"#).unwrap();
+        assert_eq!(parsed, RetryPolicy::new(5));
+    }
+}
@@ -0,0 +1,124 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+type StateMap = HashMap<TypeId, Box<dyn Any + Send>>;
+
+/// 节点实例的持久状态
+///
+/// 按类型存取，每种类型最多一个值。状态属于节点实例（actor），
+/// 在 `on_start`、每次 `execute` 和 `on_stop` 之间共享；克隆得到的是同一份状态。
+///
+/// 访问通过闭包进行，锁不会跨越 `.await`，异步节点也可以放心使用。
+#[derive(Clone, Default)]
+pub struct NodeState {
+    values: Arc<Mutex<StateMap>>,
+}
+
+impl NodeState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 存入一个值，返回同类型的旧值
+    pub fn insert<T: Send + 'static>(&self, value: T) -> Option<T> {
+        self.lock()
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|old| old.downcast().ok().map(|old| *old))
+    }
+
+    /// 取出并移除一个值
+    pub fn remove<T: Send + 'static>(&self) -> Option<T> {
+        self.lock()
+            .remove(&TypeId::of::<T>())
+            .and_then(|value| value.downcast().ok().map(|value| *value))
+    }
+
+    /// 是否存有该类型的值
+    pub fn contains<T: Send + 'static>(&self) -> bool {
+        self.lock().contains_key(&TypeId::of::<T>())
+    }
+
+    /// 读取值的副本
+    pub fn get<T: Clone + Send + 'static>(&self) -> Option<T> {
+        self.with(|value: &mut T| value.clone())
+    }
+
+    /// 修改已存在的值，不存在时返回 None
+    pub fn with<T: Send + 'static, R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        self.lock()
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_mut())
+            .map(f)
+    }
+
+    /// 修改值，不存在时先用 `init` 创建
+    pub fn with_or_insert<T: Send + 'static, R>(
+        &self,
+        init: impl FnOnce() -> T,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> R {
+        let mut values = self.lock();
+        let value = values
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(init()))
+            .downcast_mut()
+            .expect("state entry matches its TypeId");
+        f(value)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, StateMap> {
+        // 节点 panic 不应让状态永久不可用
+        self.values.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl std::fmt::Debug for NodeState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeState")
+            .field("len", &self.lock().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct History(Vec<String>);
+
+    #[test]
+    fn test_typed_values() {
+        let state = NodeState::new();
+        assert!(state.get::<u32>().is_none());
+
+        assert_eq!(state.insert(1u32), None);
+        assert_eq!(state.insert(2u32), Some(1));
+        state.insert(History(vec!["hi".to_string()]));
+
+        assert_eq!(state.get::<u32>(), Some(2));
+        assert_eq!(state.with(|count: &mut u32| *count += 1), Some(()));
+        assert_eq!(state.get::<u32>(), Some(3));
+        assert_eq!(state.get::<History>().unwrap().0, vec!["hi"]);
+
+        assert_eq!(state.remove::<u32>(), Some(3));
+        assert!(!state.contains::<u32>());
+    }
+
+    #[test]
+    fn test_clones_share_state() {
+        let state = NodeState::new();
+        let shared = state.clone();
+
+        let len = shared.with_or_insert(
+            || History(Vec::new()),
+            |history| {
+                history.0.push("a".to_string());
+                history.0.len()
+            },
+        );
+        assert_eq!(len, 1);
+        assert_eq!(state.get::<History>(), Some(History(vec!["a".to_string()])));
+    }
+}
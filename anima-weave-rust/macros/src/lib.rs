@@ -0,0 +1,397 @@
+//! `anima-weave-macros` Crate: 节点定义过程宏
+//!
+//! `#[node]` 把一个普通函数变成 AnimaWeave 节点：
+//!
+//! ```ignore
+//! use anima_weave_node::node;
+//!
+//! #[node(name = "AddNode", description = "数学加法节点", category = "math", tags = ["arithmetic"])]
+//! fn add(a: NumberLabel, b: NumberLabel) -> NumberLabel {
+//!     NumberLabel { value: a.value + b.value }
+//! }
+//! ```
+//!
+//! 生成内容：
+//! - 以 `name` 命名的单元结构体（Debug + Default + Clone + Copy）
+//! - `NodeInfo` 静态定义：参数即输入端口，`Option<T>` 参数为可选端口
+//! - `Node`（或 `async fn` 对应的 `AsyncNode`）实现：输入提取、函数调用、输出写入
+//! - inventory 自动注册，附带元数据：`version`（默认为所在 crate 的版本）、
+//!   `category`、`tags = [...]`、`author`
+//! - 执行模式 `mode = "Sequential"`：独占执行，默认 `"Concurrent"`
+//!
+//! 返回值约定：
+//! - `T`：单个输出端口，端口名由 `output = "..."` 指定，默认 `result`
+//! - `(A, B, ...)`：多个输出端口，端口名由 `outputs = ["a", "b", ...]` 指定
+//! - `()`：无输出端口
+//! - `Result<..., E>`：同上，`Err` 作为节点执行失败返回，`E` 需要能转换为 `NodeError`
+//!   （`NodeError` 本身或 `anyhow::Error`）
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::parse::Parser;
+use syn::{
+    Expr, ExprLit, FnArg, GenericArgument, Ident, ItemFn, Lit, LitStr, Pat, PathArguments,
+    ReturnType, Type, parse_macro_input,
+};
+
+/// 节点定义属性宏，详见 crate 文档
+#[proc_macro_attribute]
+pub fn node(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item_fn = parse_macro_input!(item as ItemFn);
+
+    let mut args = NodeArgs::default();
+    let parser = syn::meta::parser(|meta| args.parse(meta));
+    if let Err(err) = parser.parse(attr) {
+        return err.to_compile_error().into();
+    }
+
+    match expand(args, item_fn) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[derive(Default)]
+struct NodeArgs {
+    name: Option<LitStr>,
+    description: Option<LitStr>,
+    outputs: Option<Vec<LitStr>>,
+    version: Option<LitStr>,
+    category: Option<LitStr>,
+    tags: Vec<LitStr>,
+    author: Option<LitStr>,
+    mode: Option<Ident>,
+}
+
+impl NodeArgs {
+    fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("description") {
+            self.description = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("output") {
+            self.outputs = Some(vec![meta.value()?.parse()?]);
+        } else if meta.path.is_ident("outputs") {
+            self.outputs = Some(parse_str_array(&meta, "outputs")?);
+        } else if meta.path.is_ident("version") {
+            let version: LitStr = meta.value()?.parse()?;
+            if !is_semver(&version.value()) {
+                return Err(syn::Error::new_spanned(
+                    version,
+                    "version must be a semver version like \"1.2.0\"",
+                ));
+            }
+            self.version = Some(version);
+        } else if meta.path.is_ident("category") {
+            self.category = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("tags") {
+            self.tags = parse_str_array(&meta, "tags")?;
+        } else if meta.path.is_ident("author") {
+            self.author = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("mode") {
+            let mode: LitStr = meta.value()?.parse()?;
+            if !matches!(mode.value().as_str(), "Concurrent" | "Sequential") {
+                return Err(syn::Error::new_spanned(
+                    mode,
+                    "mode must be \"Concurrent\" or \"Sequential\"",
+                ));
+            }
+            self.mode = Some(Ident::new(&mode.value(), mode.span()));
+        } else {
+            return Err(meta.error("unsupported node attribute"));
+        }
+        Ok(())
+    }
+}
+
+/// 解析 `key = ["a", "b"]`
+fn parse_str_array(meta: &syn::meta::ParseNestedMeta, key: &str) -> syn::Result<Vec<LitStr>> {
+    let expr: Expr = meta.value()?.parse()?;
+    let Expr::Array(array) = expr else {
+        return Err(meta.error(format!("expected `{} = [\"a\", \"b\"]`", key)));
+    };
+    array
+        .elems
+        .into_iter()
+        .map(|elem| match elem {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => Ok(lit),
+            other => Err(syn::Error::new_spanned(other, "expected string literal")),
+        })
+        .collect()
+}
+
+/// 粗略检查 `MAJOR.MINOR.PATCH[-pre][+build]`，完整校验在注册时进行
+fn is_semver(version: &str) -> bool {
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let parts: Vec<_> = core.split('.').collect();
+    parts.len() == 3
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+struct InputPort {
+    ident: Ident,
+    label: Type,
+    optional: bool,
+}
+
+fn expand(args: NodeArgs, item_fn: ItemFn) -> syn::Result<TokenStream2> {
+    let node_name = args
+        .name
+        .ok_or_else(|| syn::Error::new(Span::call_site(), "missing `name = \"...\"` in #[node]"))?;
+    let description = args
+        .description
+        .unwrap_or_else(|| LitStr::new("", Span::call_site()));
+    let struct_ident = Ident::new(&node_name.value(), node_name.span());
+    let info_ident = format_ident!(
+        "__ANIMA_WEAVE_NODE_INFO_{}",
+        node_name.value().to_uppercase()
+    );
+    let version = match &args.version {
+        Some(version) => quote!(#version),
+        None => quote!(env!("CARGO_PKG_VERSION")),
+    };
+    let mode = args
+        .mode
+        .unwrap_or_else(|| Ident::new("Concurrent", Span::call_site()));
+    let category = args
+        .category
+        .unwrap_or_else(|| LitStr::new("", Span::call_site()));
+    let tags = &args.tags;
+    let author = args
+        .author
+        .unwrap_or_else(|| LitStr::new("", Span::call_site()));
+    let metadata = quote! {
+        .with_version(#version)
+        .with_category(#category)
+        .with_tags(&[#(#tags),*])
+        .with_author(#author)
+    };
+    let fn_ident = &item_fn.sig.ident;
+    let vis = &item_fn.vis;
+    let is_async = item_fn.sig.asyncness.is_some();
+    let docs: Vec<_> = item_fn
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .collect();
+
+    let inputs = item_fn
+        .sig
+        .inputs
+        .iter()
+        .map(input_port)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let (fallible, return_type) = split_result(&item_fn.sig.output);
+    let output_labels = output_labels(return_type.as_ref());
+    let output_names = args
+        .outputs
+        .unwrap_or_else(|| vec![LitStr::new("result", Span::call_site())]);
+    if !output_labels.is_empty() && output_names.len() != output_labels.len() {
+        return Err(syn::Error::new_spanned(
+            &item_fn.sig.output,
+            format!(
+                "node returns {} outputs but {} port names were given",
+                output_labels.len(),
+                output_names.len()
+            ),
+        ));
+    }
+
+    let input_port_defs = inputs.iter().map(|port| {
+        let name = LitStr::new(&port.ident.to_string(), port.ident.span());
+        let label = &port.label;
+        if port.optional {
+            quote!(::anima_weave_node::PortDef::optional_data::<#label>(#name))
+        } else {
+            quote!(::anima_weave_node::PortDef::required_data::<#label>(#name))
+        }
+    });
+    let output_port_defs = output_labels
+        .iter()
+        .zip(&output_names)
+        .map(|(label, name)| quote!(::anima_weave_node::PortDef::output_data::<#label>(#name)));
+
+    let extract_inputs = inputs.iter().map(|port| {
+        let ident = &port.ident;
+        let name = LitStr::new(&ident.to_string(), ident.span());
+        let label = &port.label;
+        if port.optional {
+            quote!(let #ident = ctx.optional_input::<#label>(#name)?;)
+        } else {
+            quote!(let #ident = ctx.input::<#label>(#name)?;)
+        }
+    });
+    let args_idents = inputs.iter().map(|port| &port.ident);
+
+    let mut call = quote!(#fn_ident(#(#args_idents),*));
+    if is_async {
+        call = quote!(#call.await);
+    }
+    if fallible {
+        call = quote!(#call.map_err(::core::convert::Into::<::anima_weave_node::__private::NodeError>::into)?);
+    }
+
+    let write_outputs = match output_labels.len() {
+        0 => quote!(let () = #call;),
+        1 => {
+            let name = &output_names[0];
+            quote!(ctx.output(#name, #call)?;)
+        }
+        count => {
+            let values: Vec<_> = (0..count)
+                .map(|i| format_ident!("__output_{}", i))
+                .collect();
+            quote! {
+                let (#(#values),*) = #call;
+                #(ctx.output(#output_names, #values)?;)*
+            }
+        }
+    };
+
+    let node_impl = if is_async {
+        quote! {
+            impl ::anima_weave_node::AsyncNode for #struct_ident {
+                fn info(&self) -> &'static ::anima_weave_node::NodeInfo {
+                    &#info_ident
+                }
+
+                fn execute<'a>(
+                    &'a self,
+                    ctx: &'a mut ::anima_weave_node::NodeContext,
+                ) -> ::anima_weave_node::NodeFuture<'a> {
+                    ::std::boxed::Box::pin(async move {
+                        #(#extract_inputs)*
+                        #write_outputs
+                        Ok(())
+                    })
+                }
+            }
+
+            ::anima_weave_node::__private::inventory::submit! {
+                ::anima_weave_node::NodeRegistration::new_async(#node_name, || {
+                    ::std::boxed::Box::new(#struct_ident)
+                })
+                #metadata
+            }
+        }
+    } else {
+        quote! {
+            impl ::anima_weave_node::Node for #struct_ident {
+                fn info(&self) -> &'static ::anima_weave_node::NodeInfo {
+                    &#info_ident
+                }
+
+                fn execute(
+                    &self,
+                    ctx: &mut ::anima_weave_node::NodeContext,
+                ) -> ::core::result::Result<(), ::anima_weave_node::__private::NodeError> {
+                    #(#extract_inputs)*
+                    #write_outputs
+                    Ok(())
+                }
+            }
+
+            ::anima_weave_node::__private::inventory::submit! {
+                ::anima_weave_node::NodeRegistration::new(#node_name, || {
+                    ::std::boxed::Box::new(#struct_ident)
+                })
+                #metadata
+            }
+        }
+    };
+
+    Ok(quote! {
+        #item_fn
+
+        #(#docs)*
+        #[derive(Debug, Default, Clone, Copy)]
+        #vis struct #struct_ident;
+
+        impl #struct_ident {
+            pub fn new() -> Self {
+                Self
+            }
+        }
+
+        #[allow(non_upper_case_globals)]
+        static #info_ident: ::anima_weave_node::__private::once_cell::sync::Lazy<::anima_weave_node::NodeInfo> =
+            ::anima_weave_node::__private::once_cell::sync::Lazy::new(|| ::anima_weave_node::NodeInfo {
+                name: #node_name,
+                description: #description,
+                input_ports: vec![#(#input_port_defs),*],
+                output_ports: vec![#(#output_port_defs),*],
+                mode: ::anima_weave_node::ExecutionMode::#mode,
+            });
+
+        #node_impl
+    })
+}
+
+fn input_port(arg: &FnArg) -> syn::Result<InputPort> {
+    let FnArg::Typed(pat_type) = arg else {
+        return Err(syn::Error::new_spanned(
+            arg,
+            "node functions cannot take self",
+        ));
+    };
+    let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+        return Err(syn::Error::new_spanned(
+            &pat_type.pat,
+            "node inputs must be plain identifiers",
+        ));
+    };
+
+    let (optional, label) = match generic_inner(&pat_type.ty, "Option") {
+        Some(inner) => (true, inner.clone()),
+        None => (false, pat_type.ty.as_ref().clone()),
+    };
+
+    Ok(InputPort {
+        ident: pat_ident.ident.clone(),
+        label,
+        optional,
+    })
+}
+
+/// 拆出 `Result<T, E>` 中的 `T`，返回 (是否可失败, 返回类型)
+fn split_result(output: &ReturnType) -> (bool, Option<Type>) {
+    match output {
+        ReturnType::Default => (false, None),
+        ReturnType::Type(_, ty) => match generic_inner(ty, "Result") {
+            Some(inner) => (true, Some(inner.clone())),
+            None => (false, Some(ty.as_ref().clone())),
+        },
+    }
+}
+
+fn output_labels(return_type: Option<&Type>) -> Vec<Type> {
+    match return_type {
+        None => Vec::new(),
+        Some(Type::Tuple(tuple)) => tuple.elems.iter().cloned().collect(),
+        Some(ty) => vec![ty.clone()],
+    }
+}
+
+/// 如果 `ty` 是 `wrapper<T, ...>`，返回第一个泛型参数 `T`
+fn generic_inner<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
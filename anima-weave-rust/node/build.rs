@@ -0,0 +1,19 @@
+//! 记录编译所用的 rustc 版本，插件加载时据此校验 ABI 兼容性
+
+use std::process::Command;
+
+fn main() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .unwrap_or_default();
+
+    println!(
+        "cargo:rustc-env=ANIMA_WEAVE_RUSTC_VERSION={}",
+        version.trim()
+    );
+    println!("cargo:rerun-if-env-changed=RUSTC");
+}
@@ -0,0 +1,217 @@
+//! Node catalog export
+//!
+//! 把节点工厂和标签注册表导出为 JSON，供 Web 编辑器展示节点面板、
+//! 端口和可用的标签转换。
+
+use crate::factory::{GLOBAL_LABEL_REGISTRY, create_node_factory};
+use crate::registry::NodeConstructor;
+use anima_weave_core::label::label_type_name;
+use anima_weave_core::{ActivationMode, ExecutionMode, FanIn, NodeImpl, PortDef, PortType};
+use serde::Serialize;
+
+/// 完整的节点目录
+#[derive(Debug, Clone, Serialize)]
+pub struct Catalog {
+    pub nodes: Vec<CatalogNode>,
+    pub labels: Vec<CatalogLabel>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogNode {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub version: &'static str,
+    pub category: &'static str,
+    pub tags: &'static [&'static str],
+    pub author: &'static str,
+    /// 异步节点（I/O 型）
+    pub is_async: bool,
+    /// 端口由实例配置决定，下面的端口为空配置下的结果
+    pub configurable: bool,
+    pub mode: ExecutionMode,
+    pub inputs: Vec<CatalogPort>,
+    pub outputs: Vec<CatalogPort>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogPort {
+    pub name: String,
+    pub label: &'static str,
+    pub required: bool,
+    /// 控制端口（SignalLabel）
+    pub control: bool,
+    /// 控制输入端口的激活模式
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activation: Option<ActivationMode>,
+    /// 数据输入端口的扇入模式
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fan_in: Option<FanIn>,
+    /// 可选输入端口的默认值（`SemanticLabel::to_json` 的输出）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    /// 已连接的可选输入端口的最长等待时间（毫秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogLabel {
+    pub name: &'static str,
+    /// 可转换到的标签类型名
+    pub conversions: Vec<&'static str>,
+}
+
+impl From<&PortDef> for CatalogPort {
+    fn from(port: &PortDef) -> Self {
+        Self {
+            name: port.name.clone(),
+            label: port.port_type.semantic_label(),
+            required: port.required,
+            control: port.is_control(),
+            activation: match port.port_type {
+                PortType::Control { activation } => Some(activation),
+                PortType::Data { .. } => None,
+            },
+            fan_in: port.fan_in,
+            default: port.default.as_ref().map(|label| label.to_json()),
+            wait_ms: port.wait.map(|wait| wait.as_millis() as u64),
+        }
+    }
+}
+
+/// 生成当前已注册节点和标签的目录，按名称排序
+pub fn catalog() -> Catalog {
+    let registrations: Vec<_> = create_node_factory().values().cloned().collect();
+
+    let mut nodes: Vec<CatalogNode> = registrations
+        .into_iter()
+        .map(|registration| {
+            let node = registration.constructor.construct();
+            let info = node.as_ref().map(NodeImpl::info);
+            let ports = |select: fn(&anima_weave_core::NodeInfo) -> &Vec<PortDef>| {
+                info.map(|info| select(info).iter().map(CatalogPort::from).collect())
+                    .unwrap_or_default()
+            };
+
+            CatalogNode {
+                name: registration.name,
+                description: info.map(|info| info.description).unwrap_or_default(),
+                version: registration.metadata.version,
+                category: registration.metadata.category,
+                tags: registration.metadata.tags,
+                author: registration.metadata.author,
+                is_async: node.as_ref().is_some_and(NodeImpl::is_async),
+                configurable: matches!(registration.constructor, NodeConstructor::Configured(_)),
+                mode: info.map(|info| info.mode).unwrap_or_default(),
+                inputs: ports(|info| &info.input_ports),
+                outputs: ports(|info| &info.output_ports),
+            }
+        })
+        .collect();
+    nodes.sort_by_key(|node| node.name);
+
+    let mut labels: Vec<CatalogLabel> = GLOBAL_LABEL_REGISTRY
+        .read()
+        .unwrap()
+        .values()
+        .map(|registration| CatalogLabel {
+            name: registration.name,
+            conversions: registration
+                .conversions
+                .iter()
+                .map(|target| label_type_name(target))
+                .collect(),
+        })
+        .collect();
+    labels.sort_by_key(|label| label.name);
+
+    Catalog { nodes, labels }
+}
+
+/// 以 JSON 导出节点目录
+pub fn export_catalog_json() -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&catalog())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factory::register_node;
+    use crate::registry::NodeRegistration;
+    use anima_weave_core::{Node, NodeContext, NodeError, NodeInfo};
+    use once_cell::sync::Lazy;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    anima_weave_core::semantic_label! {
+        CatalogNumberLabel(value: f64) {}
+    }
+
+    static CATALOG_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "CatalogTestNode",
+        description: "目录测试节点",
+        input_ports: vec![
+            PortDef::optional_data::<CatalogNumberLabel>("scale")
+                .with_default(CatalogNumberLabel { value: 1.0 })
+                .with_wait(Duration::from_millis(50)),
+        ],
+        output_ports: vec![],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    #[derive(Debug)]
+    struct CatalogTestNode;
+
+    impl Node for CatalogTestNode {
+        fn info(&self) -> &'static NodeInfo {
+            &CATALOG_NODE_INFO
+        }
+
+        fn execute(&self, _ctx: &mut NodeContext) -> Result<(), NodeError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_catalog_contains_metadata() {
+        register_node(
+            NodeRegistration::dynamic("CatalogTestNode", || {
+                NodeImpl::Sync(Arc::new(CatalogTestNode))
+            })
+            .with_version("1.2.3")
+            .with_category("test")
+            .with_tags(&["a", "b"])
+            .with_author("felix"),
+        )
+        .unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&export_catalog_json().unwrap()).unwrap();
+        let node = json["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|node| node["name"] == "CatalogTestNode")
+            .unwrap();
+        assert_eq!(node["version"], "1.2.3");
+        assert_eq!(node["category"], "test");
+        assert_eq!(node["tags"], serde_json::json!(["a", "b"]));
+        assert_eq!(node["description"], "目录测试节点");
+        let scale = &node["inputs"][0];
+        assert_eq!(scale["required"], false);
+        assert_eq!(scale["default"], serde_json::json!({"value": 1.0}));
+        assert_eq!(scale["wait_ms"], 50);
+        assert!(json["labels"].is_array());
+    }
+
+    #[test]
+    fn test_invalid_version_is_rejected() {
+        let result = register_node(
+            NodeRegistration::dynamic("CatalogBadVersionNode", || {
+                NodeImpl::Sync(Arc::new(CatalogTestNode))
+            })
+            .with_version("latest"),
+        );
+        assert!(result.is_err());
+    }
+}
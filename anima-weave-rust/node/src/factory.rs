@@ -1,26 +1,180 @@
 //! Node factory system
+//!
+//! 工厂在首次访问时从 inventory 收集编译期注册的节点和标签，
+//! 之后插件等运行时来源可以继续向其中注册。
+//! 编译期注册的同名节点无法在收集时报错，启动时需调用 `verify_registrations` 检查。
 
 use crate::registry::NodeRegistration;
-use anima_weave_core::Node;
+use anima_weave_core::label::label_type_name;
+use anima_weave_core::{LabelRegistration, NodeImpl, SemanticLabel};
+use anyhow::{Result, anyhow};
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{RwLock, RwLockReadGuard};
 
-static GLOBAL_NODE_FACTORY: Lazy<HashMap<&'static str, fn() -> Box<dyn Node>>> = Lazy::new(|| {
-    inventory::iter::<NodeRegistration>()
-        .map(|reg| (reg.name, reg.constructor))
-        .collect()
+pub type NodeFactory = HashMap<&'static str, NodeRegistration>;
+pub type LabelRegistry = HashMap<&'static str, LabelRegistration>;
+
+/// inventory 中重复出现的节点名，先注册者保留
+static INVENTORY_CONFLICTS: Lazy<Vec<&'static str>> = Lazy::new(|| {
+    let mut seen = HashSet::new();
+    let mut conflicts: Vec<_> = inventory::iter::<NodeRegistration>()
+        .filter(|reg| !seen.insert(reg.name))
+        .map(|reg| reg.name)
+        .collect();
+    conflicts.sort();
+    conflicts.dedup();
+    conflicts
+});
+
+static GLOBAL_NODE_FACTORY: Lazy<RwLock<NodeFactory>> = Lazy::new(|| {
+    let mut factory = NodeFactory::new();
+    for reg in inventory::iter::<NodeRegistration>() {
+        factory.entry(reg.name).or_insert_with(|| reg.clone());
+    }
+    RwLock::new(factory)
 });
 
-pub fn create_node_factory() -> &'static HashMap<&'static str, fn() -> Box<dyn Node>> {
-    &GLOBAL_NODE_FACTORY
+pub(crate) static GLOBAL_LABEL_REGISTRY: Lazy<RwLock<LabelRegistry>> = Lazy::new(|| {
+    RwLock::new(
+        inventory::iter::<LabelRegistration>()
+            .map(|reg| (reg.name, *reg))
+            .collect(),
+    )
+});
+
+pub fn create_node_factory() -> RwLockReadGuard<'static, NodeFactory> {
+    GLOBAL_NODE_FACTORY.read().unwrap()
+}
+
+pub fn create_node_by_type(node_type: &str) -> Option<NodeImpl> {
+    let constructor = create_node_factory().get(node_type)?.constructor.clone();
+    constructor.construct()
 }
 
-pub fn create_node_by_type(node_type: &str) -> Option<Box<dyn Node>> {
-    GLOBAL_NODE_FACTORY
+/// 按节点类型和实例配置创建节点
+pub fn create_node_with_config(node_type: &str, config: &serde_json::Value) -> Result<NodeImpl> {
+    let constructor = create_node_factory()
         .get(node_type)
-        .map(|constructor| constructor())
+        .map(|registration| registration.constructor.clone())
+        .ok_or_else(|| anyhow!("Unknown node type: {}", node_type))?;
+    constructor.construct_with_config(config)
+}
+
+/// 节点注册信息（含元数据）
+pub fn get_node_registration(node_type: &str) -> Option<NodeRegistration> {
+    create_node_factory().get(node_type).cloned()
 }
 
 pub fn get_registered_node_types() -> Vec<&'static str> {
-    GLOBAL_NODE_FACTORY.keys().copied().collect()
+    create_node_factory().keys().copied().collect()
+}
+
+/// 运行时注册节点，名称已存在或版本号不是合法 semver 时返回错误
+pub fn register_node(registration: NodeRegistration) -> Result<()> {
+    register_nodes(vec![registration])
+}
+
+/// 运行时注册一组节点，全部通过校验后才写入工厂
+///
+/// 任一节点名称已存在、在组内重复或版本号不是合法 semver 时返回错误，
+/// 不会留下部分注册
+pub fn register_nodes(registrations: Vec<NodeRegistration>) -> Result<()> {
+    let mut factory = GLOBAL_NODE_FACTORY.write().unwrap();
+    let mut seen = HashSet::new();
+    for registration in &registrations {
+        validate_version(registration)?;
+        if let Some(existing) = factory.get(registration.name) {
+            return Err(anyhow!(
+                "Node type '{}' is already registered (version {})",
+                registration.name,
+                existing.metadata.version
+            ));
+        }
+        if !seen.insert(registration.name) {
+            return Err(anyhow!(
+                "Node type '{}' is registered twice",
+                registration.name
+            ));
+        }
+    }
+    for registration in registrations {
+        factory.insert(registration.name, registration);
+    }
+    Ok(())
+}
+
+/// 检查编译期注册的节点：名称不能重复，版本号必须是合法 semver
+///
+/// 启动时（加载插件之前）调用，GraphRunner 构建时也会检查
+pub fn verify_registrations() -> Result<()> {
+    if !INVENTORY_CONFLICTS.is_empty() {
+        return Err(anyhow!(
+            "Conflicting node registrations: {}",
+            INVENTORY_CONFLICTS.join(", ")
+        ));
+    }
+    inventory::iter::<NodeRegistration>().try_for_each(validate_version)
+}
+
+fn validate_version(registration: &NodeRegistration) -> Result<()> {
+    semver::Version::parse(registration.metadata.version)
+        .map(|_| ())
+        .map_err(|e| {
+            anyhow!(
+                "Node type '{}' has invalid version '{}': {}",
+                registration.name,
+                registration.metadata.version,
+                e
+            )
+        })
+}
+
+/// 运行时注册语义标签
+///
+/// 标签按名称识别，重复注册同名标签会被忽略并返回 false
+pub fn register_label(registration: LabelRegistration) -> bool {
+    let mut registry = GLOBAL_LABEL_REGISTRY.write().unwrap();
+    if registry.contains_key(registration.name) {
+        return false;
+    }
+    registry.insert(registration.name, registration);
+    true
+}
+
+pub fn get_registered_label_types() -> Vec<&'static str> {
+    GLOBAL_LABEL_REGISTRY
+        .read()
+        .unwrap()
+        .keys()
+        .copied()
+        .collect()
+}
+
+pub fn get_label_registration(label_type: &str) -> Option<LabelRegistration> {
+    GLOBAL_LABEL_REGISTRY
+        .read()
+        .unwrap()
+        .get(label_type)
+        .copied()
+}
+
+/// 标签 `from` 是否声明了到 `to` 的转换
+pub fn can_convert_label(from: &str, to: &str) -> bool {
+    get_label_registration(from).is_some_and(|registration| {
+        registration
+            .conversions
+            .iter()
+            .any(|target| label_type_name(target) == label_type_name(to))
+    })
+}
+
+/// 按标签类型名从 JSON 重建标签（`SemanticLabel::to_json` 的逆操作）
+pub fn label_from_json(
+    label_type: &str,
+    value: &serde_json::Value,
+) -> Result<Box<dyn SemanticLabel>> {
+    let registration = get_label_registration(label_type)
+        .ok_or_else(|| anyhow!("Label type '{}' is not registered", label_type))?;
+    (registration.from_json)(value).map_err(|e| anyhow!("{}: {}", label_type, e))
 }
@@ -3,13 +3,38 @@
 //! This crate defines the `Node` trait and other essential components
 //! for running and managing individual nodes within the graph.
 
+pub mod catalog;
 pub mod factory;
 pub mod macros;
+pub mod plugin;
 pub mod registry;
+pub mod spec;
 
 // 导出核心接口
-pub use anima_weave_core::{Node, NodeInfo, PortDef};
-pub use factory::{create_node_by_type, create_node_factory, get_registered_node_types};
-pub use registry::NodeRegistration;
+pub use anima_weave_core::{
+    AsyncNode, ExecutionMode, Node, NodeContext, NodeError, NodeErrorKind, NodeFuture, NodeImpl,
+    NodeInfo, NodeState, PortDef,
+};
+pub use anima_weave_macros::node;
+pub use catalog::{Catalog, CatalogLabel, CatalogNode, CatalogPort, catalog, export_catalog_json};
+pub use factory::{
+    LabelRegistry, NodeFactory, can_convert_label, create_node_by_type, create_node_factory,
+    create_node_with_config, get_label_registration, get_node_registration,
+    get_registered_label_types, get_registered_node_types, label_from_json, register_label,
+    register_node, register_nodes, verify_registrations,
+};
+pub use plugin::{LoadedPlugin, load_plugin, load_plugins_from_dir};
+pub use registry::{
+    ConfiguredConstructor, DynamicConstructor, NodeConstructor, NodeMetadata, NodeRegistration,
+};
+pub use spec::{PortSpec, static_node_info, static_node_info_with_mode};
 
 // 宏会自动导出到crate根部，不需要手动重新导出
+
+/// `#[node]` 宏生成代码使用的依赖，不属于公开 API
+#[doc(hidden)]
+pub mod __private {
+    pub use anima_weave_core::NodeError;
+    pub use inventory;
+    pub use once_cell;
+}
@@ -1,5 +1,6 @@
 //! Node registration macros
 
+/// 注册同步节点，版本号取定义节点的 crate 版本
 #[macro_export]
 macro_rules! register_node {
     // Form 1: only type, derive name via stringify!
@@ -12,12 +13,56 @@ macro_rules! register_node {
             $crate::registry::NodeRegistration::new($name, || {
                 Box::new(<$node_type>::default())
             })
+            .with_version(env!("CARGO_PKG_VERSION"))
         }
     };
     // Form 3: custom constructor closure returning Box<dyn Node>
     ($name:expr, $constructor:expr) => {
         inventory::submit! {
             $crate::registry::NodeRegistration::new($name, $constructor)
+                .with_version(env!("CARGO_PKG_VERSION"))
         }
     };
 }
+
+/// 注册异步节点，用法与 `register_node!` 相同
+#[macro_export]
+macro_rules! register_async_node {
+    // Form 1: only type, derive name via stringify!
+    ($node_type:ty) => {
+        $crate::register_async_node!(stringify!($node_type), $node_type);
+    };
+    // Form 2: custom name and type
+    ($name:expr, $node_type:ty) => {
+        inventory::submit! {
+            $crate::registry::NodeRegistration::new_async($name, || {
+                Box::new(<$node_type>::default())
+            })
+            .with_version(env!("CARGO_PKG_VERSION"))
+        }
+    };
+    // Form 3: custom constructor closure returning Box<dyn AsyncNode>
+    ($name:expr, $constructor:expr) => {
+        inventory::submit! {
+            $crate::registry::NodeRegistration::new_async($name, $constructor)
+                .with_version(env!("CARGO_PKG_VERSION"))
+        }
+    };
+}
+
+/// 导出插件声明，供宿主通过 `plugin::load_plugin` 加载
+///
+/// 参数是 `fn(&mut PluginRegistrar)` 注册函数
+#[macro_export]
+macro_rules! export_plugin {
+    ($register:expr) => {
+        #[unsafe(no_mangle)]
+        pub static anima_weave_plugin_declaration: $crate::plugin::PluginDeclaration =
+            $crate::plugin::PluginDeclaration {
+                abi_version: $crate::plugin::PLUGIN_ABI_VERSION,
+                rustc_version: $crate::plugin::RUSTC_VERSION,
+                core_version: $crate::plugin::CORE_VERSION,
+                register: $register,
+            };
+    };
+}
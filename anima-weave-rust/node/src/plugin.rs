@@ -0,0 +1,293 @@
+//! Node plugin system
+//!
+//! 节点包以 `cdylib` 形式发布，启动时从插件目录加载，注册到全局节点工厂。
+//!
+//! 插件侧用法：
+//!
+//! ```ignore
+//! use anima_weave_node::{NodeRegistration, export_plugin, plugin::PluginRegistrar};
+//!
+//! fn register(registrar: &mut PluginRegistrar) {
+//!     registrar.register_label(MyLabel::label_registration());
+//!     registrar.register_node(NodeRegistration::new("MyNode", || Box::new(MyNode)));
+//! }
+//!
+//! export_plugin!(register);
+//! ```
+//!
+//! Rust 没有稳定 ABI，trait object 跨动态库传递要求插件与宿主使用同一 rustc
+//! 和同一版本的 anima-weave-core 编译，加载时会逐项校验。
+//! 已加载的动态库不会被卸载：节点的 vtable 和静态 NodeInfo 都位于库内。
+
+use crate::factory::{register_label, register_nodes};
+use crate::registry::NodeRegistration;
+use anima_weave_core::LabelRegistration;
+use anyhow::{Context, Result, anyhow};
+use libloading::Library;
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 插件 ABI 版本，PluginDeclaration、注册结构、NodeInfo 或节点 trait 变化时递增
+pub const PLUGIN_ABI_VERSION: u32 = 6;
+
+/// 编译宿主/插件所用的 rustc 版本
+pub const RUSTC_VERSION: &str = env!("ANIMA_WEAVE_RUSTC_VERSION");
+
+/// 插件链接的 anima-weave-core 版本
+pub const CORE_VERSION: &str = anima_weave_core::VERSION;
+
+/// 插件导出的声明符号名
+pub const PLUGIN_DECLARATION_SYMBOL: &[u8] = b"anima_weave_plugin_declaration\0";
+
+/// 插件声明，由 `export_plugin!` 生成
+///
+/// `abi_version` 必须是第一个字段：即使其余布局不兼容，也能安全读出版本号
+#[repr(C)]
+pub struct PluginDeclaration {
+    pub abi_version: u32,
+    pub rustc_version: &'static str,
+    pub core_version: &'static str,
+    pub register: fn(&mut PluginRegistrar),
+}
+
+/// 插件注册器，收集插件提供的节点和标签
+#[derive(Default)]
+pub struct PluginRegistrar {
+    nodes: Vec<NodeRegistration>,
+    labels: Vec<LabelRegistration>,
+}
+
+impl PluginRegistrar {
+    pub fn register_node(&mut self, registration: NodeRegistration) {
+        self.nodes.push(registration);
+    }
+
+    pub fn register_label(&mut self, registration: LabelRegistration) {
+        self.labels.push(registration);
+    }
+
+    /// 校验并写入全局工厂
+    ///
+    /// 节点先整体注册：名称冲突或版本号不合法时整个插件被拒绝，不会留下部分注册；
+    /// 标签按名称识别，宿主已有的同名标签直接复用
+    fn apply(self) -> Result<(Vec<&'static str>, Vec<&'static str>)> {
+        let node_types = self
+            .nodes
+            .iter()
+            .map(|registration| registration.name)
+            .collect();
+        register_nodes(self.nodes)?;
+
+        let label_types = self
+            .labels
+            .into_iter()
+            .filter(|registration| register_label(*registration))
+            .map(|registration| registration.name)
+            .collect();
+
+        Ok((node_types, label_types))
+    }
+}
+
+/// 已加载插件的信息
+#[derive(Debug, Clone)]
+pub struct LoadedPlugin {
+    pub path: PathBuf,
+    pub node_types: Vec<&'static str>,
+    /// 插件新增的标签（不含宿主已有的同名标签）
+    pub label_types: Vec<&'static str>,
+}
+
+/// 已加载的动态库，进程结束前保持加载
+static LOADED_LIBRARIES: Lazy<Mutex<Vec<Library>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// 加载单个插件
+pub fn load_plugin(path: impl AsRef<Path>) -> Result<LoadedPlugin> {
+    let path = path.as_ref();
+
+    // SAFETY: 加载动态库会执行其初始化代码，插件目录中的库视为可信
+    let library = unsafe { Library::new(path) }
+        .with_context(|| format!("Failed to load plugin {}", path.display()))?;
+
+    // SAFETY: 符号由 export_plugin! 以 PluginDeclaration 类型导出；
+    // 在校验 abi_version 之前只读取 repr(C) 的第一个字段
+    let declaration: &PluginDeclaration = unsafe {
+        let symbol = library
+            .get::<*const PluginDeclaration>(PLUGIN_DECLARATION_SYMBOL)
+            .with_context(|| format!("{} is not an AnimaWeave plugin", path.display()))?;
+        &**symbol
+    };
+
+    if declaration.abi_version != PLUGIN_ABI_VERSION {
+        return Err(anyhow!(
+            "Plugin {} has ABI version {}, expected {}",
+            path.display(),
+            declaration.abi_version,
+            PLUGIN_ABI_VERSION
+        ));
+    }
+    if declaration.rustc_version != RUSTC_VERSION {
+        return Err(anyhow!(
+            "Plugin {} was built with '{}', host uses '{}'",
+            path.display(),
+            declaration.rustc_version,
+            RUSTC_VERSION
+        ));
+    }
+    if declaration.core_version != CORE_VERSION {
+        return Err(anyhow!(
+            "Plugin {} was built against anima-weave-core {}, host uses {}",
+            path.display(),
+            declaration.core_version,
+            CORE_VERSION
+        ));
+    }
+
+    let mut registrar = PluginRegistrar::default();
+    (declaration.register)(&mut registrar);
+    // 注册失败时没有写入任何节点或标签，动态库可以卸载；
+    // 注册成功后节点和标签都指向库内代码，库必须保持加载
+    let (node_types, label_types) = registrar
+        .apply()
+        .with_context(|| format!("Failed to register plugin {}", path.display()))?;
+    LOADED_LIBRARIES.lock().unwrap().push(library);
+
+    log::info!(
+        "Loaded plugin {}: {} nodes, {} labels",
+        path.display(),
+        node_types.len(),
+        label_types.len()
+    );
+
+    Ok(LoadedPlugin {
+        path: path.to_path_buf(),
+        node_types,
+        label_types,
+    })
+}
+
+/// 加载目录下所有插件（按文件名排序），目录不存在时视为没有插件
+pub fn load_plugins_from_dir(dir: impl AsRef<Path>) -> Result<Vec<LoadedPlugin>> {
+    let dir = dir.as_ref();
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read plugin directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path.extension().and_then(|ext| ext.to_str())
+                    == Some(std::env::consts::DLL_EXTENSION)
+        })
+        .collect();
+    paths.sort();
+
+    paths.iter().map(load_plugin).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factory::{create_node_by_type, get_label_registration};
+    use anima_weave_core::label::TransformError;
+    use anima_weave_core::{ExecutionMode, Node, NodeContext, NodeError, NodeInfo};
+
+    static PLUGIN_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "PluginTestNode",
+        description: "测试用插件节点",
+        input_ports: vec![],
+        output_ports: vec![],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    #[derive(Debug)]
+    struct PluginTestNode;
+
+    impl Node for PluginTestNode {
+        fn info(&self) -> &'static NodeInfo {
+            &PLUGIN_NODE_INFO
+        }
+
+        fn execute(&self, _ctx: &mut NodeContext) -> Result<(), NodeError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_registrar_registers_nodes_and_labels() {
+        let mut registrar = PluginRegistrar::default();
+        registrar.register_node(NodeRegistration::new("PluginTestNode", || {
+            Box::new(PluginTestNode)
+        }));
+        registrar.register_label(LabelRegistration::new("PluginTestLabel", &[], |_| {
+            Err(TransformError::ConversionFailed {
+                reason: "not deserializable".to_string(),
+            })
+        }));
+
+        let (node_types, label_types) = registrar.apply().unwrap();
+        assert_eq!(node_types, vec!["PluginTestNode"]);
+        assert_eq!(label_types, vec!["PluginTestLabel"]);
+        assert!(create_node_by_type("PluginTestNode").is_some());
+        assert!(get_label_registration("PluginTestLabel").is_some());
+    }
+
+    #[test]
+    fn test_registrar_rejects_duplicate_nodes() {
+        let mut registrar = PluginRegistrar::default();
+        registrar.register_node(NodeRegistration::new("PluginDuplicateNode", || {
+            Box::new(PluginTestNode)
+        }));
+        registrar.register_node(NodeRegistration::new("PluginDuplicateNode", || {
+            Box::new(PluginTestNode)
+        }));
+
+        assert!(registrar.apply().is_err());
+        assert!(create_node_by_type("PluginDuplicateNode").is_none());
+    }
+
+    #[test]
+    fn test_registrar_rejects_invalid_version_before_registering() {
+        let mut registrar = PluginRegistrar::default();
+        registrar.register_node(NodeRegistration::new("PluginValidNode", || {
+            Box::new(PluginTestNode)
+        }));
+        registrar.register_node(
+            NodeRegistration::new("PluginBadVersionNode", || Box::new(PluginTestNode))
+                .with_version("not-semver"),
+        );
+        registrar.register_label(LabelRegistration::new("PluginUnusedLabel", &[], |_| {
+            Err(TransformError::ConversionFailed {
+                reason: "not deserializable".to_string(),
+            })
+        }));
+
+        let err = registrar.apply().unwrap_err();
+        assert!(err.to_string().contains("invalid version"), "{}", err);
+        assert!(create_node_by_type("PluginValidNode").is_none());
+        assert!(get_label_registration("PluginUnusedLabel").is_none());
+    }
+
+    #[test]
+    fn test_load_invalid_plugin() {
+        // 文件名带上进程号，并发的测试进程不会互相覆盖
+        let path = std::env::temp_dir().join(format!(
+            "anima-weave-invalid-plugin-{}.{}",
+            std::process::id(),
+            std::env::consts::DLL_EXTENSION
+        ));
+        std::fs::write(&path, b"not a library").unwrap();
+
+        assert!(load_plugin(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_missing_plugin_dir() {
+        let plugins = load_plugins_from_dir("/nonexistent/anima-weave-plugins").unwrap();
+        assert!(plugins.is_empty());
+    }
+}
@@ -1,15 +1,131 @@
 //! Node registration system
 
-use anima_weave_core::Node;
+use anima_weave_core::{AsyncNode, Node, NodeImpl};
+use anyhow::Result;
+use std::sync::Arc;
 
+/// 运行时构造节点的闭包，用于 WASM 模块等运行时才确定的节点
+pub type DynamicConstructor = Arc<dyn Fn() -> NodeImpl + Send + Sync>;
+
+/// 按节点实例配置（`NodeRef::config`）构造节点，端口可以由配置决定
+pub type ConfiguredConstructor = fn(&serde_json::Value) -> Result<NodeImpl>;
+
+/// 节点构造函数 - 同步节点、异步节点、运行时动态节点或可配置节点
+#[derive(Clone)]
+pub enum NodeConstructor {
+    Sync(fn() -> Box<dyn Node>),
+    Async(fn() -> Box<dyn AsyncNode>),
+    Dynamic(DynamicConstructor),
+    Configured(ConfiguredConstructor),
+}
+
+impl NodeConstructor {
+    /// 构造一个新的节点实例
+    ///
+    /// 可配置节点以空配置构造，配置无效时返回 None
+    pub fn construct(&self) -> Option<NodeImpl> {
+        self.construct_with_config(&serde_json::Value::Null).ok()
+    }
+
+    /// 按实例配置构造节点，非可配置节点忽略配置
+    pub fn construct_with_config(&self, config: &serde_json::Value) -> Result<NodeImpl> {
+        Ok(match self {
+            NodeConstructor::Sync(constructor) => constructor().into(),
+            NodeConstructor::Async(constructor) => constructor().into(),
+            NodeConstructor::Dynamic(constructor) => constructor(),
+            NodeConstructor::Configured(constructor) => constructor(config)?,
+        })
+    }
+}
+
+/// 节点元数据，供编辑器和节点目录使用
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeMetadata {
+    /// semver 版本号
+    pub version: &'static str,
+    pub category: &'static str,
+    pub tags: &'static [&'static str],
+    pub author: &'static str,
+}
+
+impl NodeMetadata {
+    pub const DEFAULT: Self = Self {
+        version: "0.0.0",
+        category: "",
+        tags: &[],
+        author: "",
+    };
+}
+
+impl Default for NodeMetadata {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[derive(Clone)]
 pub struct NodeRegistration {
     pub name: &'static str,
-    pub constructor: fn() -> Box<dyn Node>,
+    pub constructor: NodeConstructor,
+    pub metadata: NodeMetadata,
 }
 
 impl NodeRegistration {
     pub const fn new(name: &'static str, constructor: fn() -> Box<dyn Node>) -> Self {
-        Self { name, constructor }
+        Self {
+            name,
+            constructor: NodeConstructor::Sync(constructor),
+            metadata: NodeMetadata::DEFAULT,
+        }
+    }
+
+    pub const fn new_async(name: &'static str, constructor: fn() -> Box<dyn AsyncNode>) -> Self {
+        Self {
+            name,
+            constructor: NodeConstructor::Async(constructor),
+            metadata: NodeMetadata::DEFAULT,
+        }
+    }
+
+    /// 可配置节点，每个实例按 `NodeRef::config` 构造
+    pub const fn configured(name: &'static str, constructor: ConfiguredConstructor) -> Self {
+        Self {
+            name,
+            constructor: NodeConstructor::Configured(constructor),
+            metadata: NodeMetadata::DEFAULT,
+        }
+    }
+
+    /// 运行时动态注册的节点，只能通过 factory::register_node 注册
+    pub fn dynamic(
+        name: &'static str,
+        constructor: impl Fn() -> NodeImpl + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name,
+            constructor: NodeConstructor::Dynamic(Arc::new(constructor)),
+            metadata: NodeMetadata::DEFAULT,
+        }
+    }
+
+    pub const fn with_version(mut self, version: &'static str) -> Self {
+        self.metadata.version = version;
+        self
+    }
+
+    pub const fn with_category(mut self, category: &'static str) -> Self {
+        self.metadata.category = category;
+        self
+    }
+
+    pub const fn with_tags(mut self, tags: &'static [&'static str]) -> Self {
+        self.metadata.tags = tags;
+        self
+    }
+
+    pub const fn with_author(mut self, author: &'static str) -> Self {
+        self.metadata.author = author;
+        self
     }
 }
 
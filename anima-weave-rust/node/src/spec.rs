@@ -0,0 +1,148 @@
+//! Runtime port declarations
+//!
+//! WASM 清单、节点实例配置等运行时来源按名称引用标签声明端口，
+//! 这里把它们解析为 PortDef / 'static NodeInfo。
+
+use crate::factory::get_label_registration;
+use anima_weave_core::node::PortType;
+use anima_weave_core::{ExecutionMode, FanIn, NodeInfo, PortDef};
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// 运行时声明的端口
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PortSpec {
+    pub name: String,
+    /// 语义标签类型名，必须已在宿主中注册
+    pub label: String,
+    #[serde(default = "default_required")]
+    pub required: bool,
+    /// 输入端口的扇入模式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fan_in: Option<FanIn>,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+impl PortSpec {
+    /// 解析为 PortDef，标签未注册时返回错误
+    pub fn to_port_def(&self) -> Result<PortDef> {
+        let registration = get_label_registration(&self.label)
+            .ok_or_else(|| anyhow!("Port '{}' uses unknown label '{}'", self.name, self.label))?;
+        Ok(PortDef {
+            name: self.name.clone(),
+            port_type: PortType::for_label(registration.name),
+            required: self.required,
+            fan_in: self.fan_in,
+            default: None,
+            wait: None,
+        })
+    }
+}
+
+type NodeInfoKey = (String, String, Vec<PortSpec>, Vec<PortSpec>, ExecutionMode);
+
+static NODE_INFOS: Lazy<Mutex<HashMap<NodeInfoKey, &'static NodeInfo>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 由运行时声明构造 'static NodeInfo
+///
+/// Node::info 要求 'static，运行时声明的节点信息只能泄漏；
+/// 相同声明只泄漏一次，反复构造同一配置的节点不会持续占用内存
+pub fn static_node_info(
+    name: &str,
+    description: &str,
+    inputs: &[PortSpec],
+    outputs: &[PortSpec],
+) -> Result<&'static NodeInfo> {
+    static_node_info_with_mode(
+        name,
+        description,
+        inputs,
+        outputs,
+        ExecutionMode::Concurrent,
+    )
+}
+
+/// 同 [`static_node_info`]，指定节点的执行模式
+pub fn static_node_info_with_mode(
+    name: &str,
+    description: &str,
+    inputs: &[PortSpec],
+    outputs: &[PortSpec],
+    mode: ExecutionMode,
+) -> Result<&'static NodeInfo> {
+    let key = (
+        name.to_string(),
+        description.to_string(),
+        inputs.to_vec(),
+        outputs.to_vec(),
+        mode,
+    );
+    let mut infos = NODE_INFOS.lock().unwrap();
+    if let Some(info) = infos.get(&key) {
+        return Ok(info);
+    }
+
+    for (direction, ports) in [("input", inputs), ("output", outputs)] {
+        let mut names = HashSet::new();
+        if let Some(port) = ports.iter().find(|port| !names.insert(&port.name)) {
+            return Err(anyhow!(
+                "Duplicate {} port '{}' in node '{}'",
+                direction,
+                port.name,
+                name
+            ));
+        }
+    }
+
+    let info = NodeInfo {
+        name: Box::leak(name.to_string().into_boxed_str()),
+        description: Box::leak(description.to_string().into_boxed_str()),
+        input_ports: inputs
+            .iter()
+            .map(PortSpec::to_port_def)
+            .collect::<Result<_>>()?,
+        output_ports: outputs
+            .iter()
+            .map(PortSpec::to_port_def)
+            .collect::<Result<_>>()?,
+        mode,
+    };
+    let info: &'static NodeInfo = Box::leak(Box::new(info));
+    infos.insert(key, info);
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(name: &str) -> PortSpec {
+        PortSpec {
+            name: name.to_string(),
+            label: "SpecUnknownLabel".to_string(),
+            required: true,
+            fan_in: None,
+        }
+    }
+
+    #[test]
+    fn test_duplicate_port_names_rejected() {
+        let err = static_node_info("SpecNode", "", &[], &[port("a"), port("a")]).unwrap_err();
+        assert!(
+            err.to_string().contains("Duplicate output port 'a'"),
+            "{}",
+            err
+        );
+
+        // 输入和输出各自命名，同名不冲突；这里因标签未注册而失败
+        let err = static_node_info("SpecNode", "", &[port("a")], &[port("a")]).unwrap_err();
+        assert!(err.to_string().contains("unknown label"), "{}", err);
+    }
+}
@@ -0,0 +1,265 @@
+//! NodeHost - 长驻的外部节点宿主进程
+
+use crate::protocol::{Catalog, Request, Response};
+use anima_weave_core::NodeError;
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 宿主清单文件名后缀
+pub const HOST_MANIFEST_SUFFIX: &str = ".host.json";
+
+/// 宿主进程配置
+///
+/// ```json
+/// { "command": "deno", "args": ["run", "--allow-read", "host.ts"] }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct HostConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// 工作目录，从清单加载时默认为清单所在目录
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+    /// 单个请求的超时时间
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_timeout_ms() -> u64 {
+    30_000
+}
+
+impl HostConfig {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            cwd: None,
+            timeout_ms: default_timeout_ms(),
+        }
+    }
+
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 读取宿主清单，相对的工作目录按清单所在目录解析
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read host manifest {}", path.display()))?;
+        let mut config: HostConfig = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid host manifest {}", path.display()))?;
+
+        let dir = path.parent().unwrap_or(Path::new("."));
+        config.cwd = Some(match config.cwd.take() {
+            Some(cwd) if cwd.is_relative() => dir.join(cwd),
+            Some(cwd) => cwd,
+            None => dir.to_path_buf(),
+        });
+        Ok(config)
+    }
+}
+
+type PendingRequests = Arc<Mutex<HashMap<u64, Sender<Result<Value>>>>>;
+
+/// 正在运行的宿主进程
+struct HostProcess {
+    child: Child,
+    stdin: ChildStdin,
+    pending: PendingRequests,
+    alive: Arc<AtomicBool>,
+}
+
+impl HostProcess {
+    fn spawn(config: &HostConfig) -> Result<Self> {
+        let mut command = Command::new(&config.command);
+        command
+            .args(&config.args)
+            .envs(&config.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit());
+        if let Some(cwd) = &config.cwd {
+            command.current_dir(cwd);
+        }
+
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("Failed to spawn node host '{}'", config.command))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        let pending: PendingRequests = Arc::default();
+        let alive = Arc::new(AtomicBool::new(true));
+
+        let reader_pending = pending.clone();
+        let reader_alive = alive.clone();
+        let command_name = config.command.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                match serde_json::from_str::<Response>(&line) {
+                    Ok(response) => dispatch(&reader_pending, response),
+                    Err(_) => log::debug!("[{}] {}", command_name, line),
+                }
+            }
+
+            // stdout 关闭即视为宿主退出，所有等待中的请求失败
+            reader_alive.store(false, Ordering::SeqCst);
+            for (_, sender) in reader_pending.lock().unwrap().drain() {
+                let _ = sender.send(Err(unavailable(format!(
+                    "Node host '{}' exited",
+                    command_name
+                ))));
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            pending,
+            alive,
+        })
+    }
+
+    fn is_alive(&mut self) -> bool {
+        self.alive.load(Ordering::SeqCst) && matches!(self.child.try_wait(), Ok(None))
+    }
+}
+
+impl Drop for HostProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// 宿主不可用（退出、无响应）的错误
+///
+/// 包装为暂时性的 [`NodeError`]，节点的重试策略适用：重试时宿主会被重启
+fn unavailable(message: String) -> anyhow::Error {
+    NodeError::transient(message).into()
+}
+
+fn dispatch(pending: &PendingRequests, response: Response) {
+    let Some(sender) = pending.lock().unwrap().remove(&response.id) else {
+        log::warn!(
+            "Node host sent response for unknown request {}",
+            response.id
+        );
+        return;
+    };
+    let result = match (response.error, response.result) {
+        (Some(error), _) => Err(anyhow!(error)),
+        (None, result) => Ok(result.unwrap_or(Value::Null)),
+    };
+    let _ = sender.send(result);
+}
+
+/// 外部节点宿主
+///
+/// 宿主进程按需启动；发现进程已退出、或者请求超时时，下一个请求会重新启动它。
+/// 请求可以并发发出，响应按 id 分发。
+pub struct NodeHost {
+    config: HostConfig,
+    process: Mutex<Option<HostProcess>>,
+    next_id: AtomicU64,
+    restarts: AtomicU32,
+}
+
+impl std::fmt::Debug for NodeHost {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeHost")
+            .field("command", &self.config.command)
+            .field("restarts", &self.restart_count())
+            .finish()
+    }
+}
+
+impl NodeHost {
+    /// 启动宿主进程
+    pub fn spawn(config: HostConfig) -> Result<Self> {
+        let process = HostProcess::spawn(&config)?;
+        Ok(Self {
+            config,
+            process: Mutex::new(Some(process)),
+            next_id: AtomicU64::new(1),
+            restarts: AtomicU32::new(0),
+        })
+    }
+
+    /// 宿主进程因崩溃或无响应被重启的次数
+    pub fn restart_count(&self) -> u32 {
+        self.restarts.load(Ordering::SeqCst)
+    }
+
+    /// 查询宿主提供的节点
+    pub fn catalog(&self) -> Result<Catalog> {
+        let result = self.request("catalog", None)?;
+        serde_json::from_value(result).context("Invalid catalog from node host")
+    }
+
+    /// 发送请求并等待响应
+    pub fn request(&self, method: &'static str, params: Option<Value>) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (sender, receiver) = mpsc::channel();
+
+        let (pending, alive) = {
+            let mut process = self.process.lock().unwrap();
+            let alive = process.as_mut().is_some_and(HostProcess::is_alive);
+            if !alive {
+                if process.is_some() {
+                    self.restarts.fetch_add(1, Ordering::SeqCst);
+                    log::warn!(
+                        "Node host '{}' exited or stopped responding, restarting",
+                        self.config.command
+                    );
+                }
+                *process = Some(HostProcess::spawn(&self.config)?);
+            }
+            let running = process.as_mut().expect("host process is running");
+
+            running.pending.lock().unwrap().insert(id, sender);
+            let line = serde_json::to_string(&Request { id, method, params })?;
+            let written = writeln!(running.stdin, "{}", line).and_then(|_| running.stdin.flush());
+            if let Err(e) = written {
+                running.pending.lock().unwrap().remove(&id);
+                running.alive.store(false, Ordering::SeqCst);
+                return Err(unavailable(format!("Failed to write to node host: {}", e)));
+            }
+            (running.pending.clone(), running.alive.clone())
+        };
+
+        match receiver.recv_timeout(Duration::from_millis(self.config.timeout_ms)) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => {
+                // 无响应的宿主视为已退出，下一个请求（例如重试）会重启它
+                pending.lock().unwrap().remove(&id);
+                alive.store(false, Ordering::SeqCst);
+                Err(unavailable(format!(
+                    "Node host request '{}' timed out after {}ms",
+                    method, self.config.timeout_ms
+                )))
+            }
+            Err(RecvTimeoutError::Disconnected) => Err(unavailable("Node host exited".to_string())),
+        }
+    }
+}
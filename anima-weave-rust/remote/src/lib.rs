@@ -0,0 +1,252 @@
+//! `anima-weave-remote` Crate: 外部进程节点
+//!
+//! 节点逻辑运行在长驻的子进程（如 Deno/TypeScript 宿主）中，
+//! 运行时通过 stdin/stdout 上的 JSON lines 协议查询节点目录并执行节点，
+//! 协议见 [`protocol`]。发现的节点注册到全局节点工厂，与原生节点一样使用。
+
+pub mod host;
+pub mod protocol;
+
+pub use host::{HOST_MANIFEST_SUFFIX, HostConfig, NodeHost};
+pub use protocol::{Catalog, RemoteNodeSpec};
+
+use anima_weave_core::{Node, NodeContext, NodeError, NodeImpl, NodeInfo};
+use anima_weave_node::{NodeRegistration, label_from_json, register_nodes, static_node_info};
+use anyhow::{Context, Result};
+use protocol::{ExecuteParams, ExecuteResult};
+use serde_json::Map;
+use std::path::Path;
+use std::sync::Arc;
+
+/// 由外部宿主执行的节点
+#[derive(Debug)]
+pub struct RemoteNode {
+    info: &'static NodeInfo,
+    host: Arc<NodeHost>,
+}
+
+impl RemoteNode {
+    pub fn new(spec: &RemoteNodeSpec, host: Arc<NodeHost>) -> Result<Self> {
+        let info = static_node_info(&spec.name, &spec.description, &spec.inputs, &spec.outputs)?;
+        Ok(Self { info, host })
+    }
+}
+
+impl Node for RemoteNode {
+    fn info(&self) -> &'static NodeInfo {
+        self.info
+    }
+
+    fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+        let mut inputs = Map::new();
+        for port in &self.info.input_ports {
+            if ctx.has_input(&port.name) {
+                inputs.insert(port.name.clone(), ctx.input_label(&port.name)?.to_json());
+            } else if port.required {
+                return Err(NodeError::invalid_input(
+                    &port.name,
+                    format!("Missing required input '{}'", port.name),
+                ));
+            }
+        }
+
+        let params = ExecuteParams {
+            node: self.info.name,
+            inputs,
+        };
+        let params = serde_json::to_value(params).context("Failed to encode execute params")?;
+        let result = self.host.request("execute", Some(params))?;
+        let result: ExecuteResult =
+            serde_json::from_value(result).context("Invalid execute result from node host")?;
+
+        for port in &self.info.output_ports {
+            if let Some(value) = result.outputs.get(&port.name) {
+                let label = label_from_json(port.port_type.semantic_label(), value)?;
+                ctx.output_boxed(&port.name, label)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 查询宿主的节点目录，并把其中的节点注册到全局节点工厂
+pub fn register_host_nodes(host: Arc<NodeHost>) -> Result<Vec<&'static str>> {
+    let catalog = host.catalog()?;
+
+    // 先构造全部节点再整体注册，端口声明有误或名称冲突时不留下部分注册
+    let nodes = catalog
+        .nodes
+        .iter()
+        .map(|spec| RemoteNode::new(spec, host.clone()).map(Arc::new))
+        .collect::<Result<Vec<_>>>()?;
+
+    let names = nodes.iter().map(|node| node.info().name).collect();
+    register_nodes(
+        nodes
+            .into_iter()
+            .map(|node| {
+                NodeRegistration::dynamic(node.info().name, move || NodeImpl::Sync(node.clone()))
+            })
+            .collect(),
+    )?;
+    Ok(names)
+}
+
+/// 按宿主清单启动宿主并注册其节点
+pub fn load_host(manifest_path: &Path) -> Result<Vec<&'static str>> {
+    let config = HostConfig::from_file(manifest_path)?;
+    let host = NodeHost::spawn(config)
+        .with_context(|| format!("Failed to start node host {}", manifest_path.display()))?;
+    register_host_nodes(Arc::new(host))
+}
+
+/// 启动目录下所有 `*.host.json` 描述的宿主，目录不存在时返回空列表
+pub fn load_hosts_from_dir(dir: &Path) -> Result<Vec<&'static str>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read plugin directory {}", dir.display()))?
+    {
+        let path = entry?.path();
+        let is_manifest = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(HOST_MANIFEST_SUFFIX));
+        if is_manifest {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut loaded = Vec::new();
+    for path in paths {
+        let node_types = load_host(&path)?;
+        log::info!(
+            "Started node host {} ({} nodes)",
+            path.display(),
+            node_types.len()
+        );
+        loaded.extend(node_types);
+    }
+    Ok(loaded)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use anima_weave_core::{NodeDataInputs, PortRef, semantic_label};
+    use anima_weave_node::create_node_by_type;
+
+    semantic_label! {
+        RemoteTextLabel(value: String) {}
+    }
+
+    /// 用 sh 实现的最小宿主：Echo 回显输入，Crash 直接退出
+    const HOST_SCRIPT: &str = r#"
+echo "host starting"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"method":"catalog"'*)
+      printf '{"id":%s,"result":{"nodes":[%s,%s]}}\n' "$id" \
+        '{"name":"RemoteEcho","inputs":[{"name":"text","label":"RemoteTextLabel"}],"outputs":[{"name":"text","label":"RemoteTextLabel"}]}' \
+        '{"name":"RemoteCrash","outputs":[{"name":"text","label":"RemoteTextLabel"}]}' ;;
+    *'"node":"RemoteCrash"'*)
+      exit 1 ;;
+    *)
+      text=$(printf '%s' "$line" | sed -n 's/.*"value":"\([^"]*\)".*/\1/p')
+      printf '{"id":%s,"result":{"outputs":{"text":{"value":"echo %s"}}}}\n' "$id" "$text" ;;
+  esac
+done
+"#;
+
+    fn run(node_type: &str, text: Option<&str>) -> Result<String> {
+        let node = create_node_by_type(node_type).unwrap();
+        let NodeImpl::Sync(node) = node else {
+            panic!("remote nodes are sync");
+        };
+
+        let mut inputs = NodeDataInputs::new();
+        if let Some(text) = text {
+            inputs.insert(
+                PortRef {
+                    node_name: "remote".to_string(),
+                    port_name: "text".to_string(),
+                },
+                Box::new(RemoteTextLabel {
+                    value: text.to_string(),
+                }),
+            );
+        }
+        let mut ctx = NodeContext::new("remote", node.info(), inputs);
+        node.execute(&mut ctx)?;
+        let outputs = ctx.into_outputs();
+        let label = outputs
+            .values()
+            .next()
+            .and_then(|label| label.as_any().downcast_ref::<RemoteTextLabel>())
+            .unwrap();
+        Ok(label.value.clone())
+    }
+
+    #[test]
+    fn test_catalog_execute_and_restart() {
+        let host = Arc::new(
+            NodeHost::spawn(HostConfig::new("sh").with_args(["-c", HOST_SCRIPT])).unwrap(),
+        );
+
+        let registered = register_host_nodes(host.clone()).unwrap();
+        assert_eq!(registered, vec!["RemoteEcho", "RemoteCrash"]);
+        assert_eq!(
+            create_node_by_type("RemoteEcho")
+                .unwrap()
+                .info()
+                .input_ports[0]
+                .name,
+            "text"
+        );
+
+        assert_eq!(run("RemoteEcho", Some("hi")).unwrap(), "echo hi");
+
+        // 宿主崩溃时当前请求失败，下一个请求会重启宿主
+        let err = NodeError::from(run("RemoteCrash", None).unwrap_err());
+        assert!(err.to_string().contains("exited"), "{}", err);
+        assert!(err.is_retryable());
+        assert_eq!(run("RemoteEcho", Some("again")).unwrap(), "echo again");
+        assert_eq!(host.restart_count(), 1);
+    }
+
+    #[test]
+    fn test_request_timeout() {
+        let mut config = HostConfig::new("sh").with_args(["-c", "cat > /dev/null"]);
+        config.timeout_ms = 50;
+        let host = NodeHost::spawn(config).unwrap();
+
+        let err = host.catalog().unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{}", err);
+    }
+
+    #[test]
+    fn test_request_after_timeout_restarts_host() {
+        // 第一次启动的宿主不响应，之后启动的宿主正常工作
+        let marker =
+            std::env::temp_dir().join(format!("anima-weave-hung-host-{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+        let script = format!(
+            "if [ -e '{marker}' ]; then {HOST_SCRIPT}\nelse touch '{marker}'; cat > /dev/null; fi",
+            marker = marker.display()
+        );
+        let mut config = HostConfig::new("sh").with_args(["-c", script.as_str()]);
+        config.timeout_ms = 200;
+        let host = NodeHost::spawn(config).unwrap();
+
+        let err = host.catalog().unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{}", err);
+        assert_eq!(host.catalog().unwrap().nodes.len(), 2);
+        assert_eq!(host.restart_count(), 1);
+        let _ = std::fs::remove_file(&marker);
+    }
+}
@@ -0,0 +1,69 @@
+//! 节点宿主协议
+//!
+//! 运行时与宿主进程之间通过 stdin/stdout 交换 JSON lines，每行一条消息：
+//!
+//! ```text
+//! → {"id":1,"method":"catalog"}
+//! ← {"id":1,"result":{"nodes":[{"name":"Upper","inputs":[...],"outputs":[...]}]}}
+//! → {"id":2,"method":"execute","params":{"node":"Upper","inputs":{"text":{"value":"hi"}}}}
+//! ← {"id":2,"result":{"outputs":{"text":{"value":"HI"}}}}
+//! ← {"id":3,"error":"something went wrong"}
+//! ```
+//!
+//! 标签以 `SemanticLabel::to_json` 的形式传递。宿主可以乱序响应，
+//! 不是 JSON 的输出行会被忽略（仅记录日志）；stdin 关闭时宿主应退出。
+
+use anima_weave_node::PortSpec;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// 运行时发往宿主的请求
+#[derive(Debug, Clone, Serialize)]
+pub struct Request {
+    pub id: u64,
+    pub method: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+/// 宿主返回的响应，`result` 与 `error` 二选一
+#[derive(Debug, Clone, Deserialize)]
+pub struct Response {
+    pub id: u64,
+    #[serde(default)]
+    pub result: Option<Value>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// `catalog` 的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Catalog {
+    pub nodes: Vec<RemoteNodeSpec>,
+}
+
+/// 宿主提供的节点声明
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteNodeSpec {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub inputs: Vec<PortSpec>,
+    #[serde(default)]
+    pub outputs: Vec<PortSpec>,
+}
+
+/// `execute` 的参数
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecuteParams<'a> {
+    pub node: &'a str,
+    pub inputs: Map<String, Value>,
+}
+
+/// `execute` 的结果
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExecuteResult {
+    #[serde(default)]
+    pub outputs: Map<String, Value>,
+}
@@ -1,45 +1,499 @@
-use super::status_tracker::{NodeStatusEvent, SimpleStatusTracker};
-use anima_weave_core::{Node, NodeName};
+use super::coordinator::{ExecutionCoordinator, ExecutionPermit};
+use super::run::{EdgeError, RunContext, RunId};
+use super::status_tracker::{NodeStatusEvent, SimpleStatusTracker, SinkOutputMessage};
+use anima_weave_core::label::TransformError;
+use anima_weave_core::{
+    ActivationMode, ArrayLabel, ERROR_PORT, ErrorLabel, FailurePolicy, FanIn, Node, NodeContext,
+    NodeError, NodeImpl, NodeName, NodeState, RecordLabel, RetryPolicy, SignalLabel,
+};
 use kameo::Actor;
 use kameo::Reply;
 use kameo::actor::{ActorRef, WeakActorRef};
 use kameo::error::ActorStopReason;
+use kameo::error::SendError;
 use kameo::message::{Context, Message};
-use std::collections::HashMap;
+use kameo::reply::{DelegatedReply, ReplySender};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
+use tokio::task::{JoinError, JoinHandle};
 use uuid::Uuid;
 
 use anima_weave_core::{
-    NodeDataInputs as NodeData, NodeDataOutputs, PortName, PortRef, SemanticLabel,
+    NodeDataInputs as NodeData, NodeDataOutputs, PortName, PortRef, PortType, SemanticLabel,
 };
 
 // 简化版的ExecutionId定义
 pub type ExecutionId = String;
 
+/// 下游连接映射：输出端口名 -> 下游连接列表
+pub type DownstreamConnections = HashMap<PortName, Vec<DownstreamEdge>>;
+
+/// 一条下游连接
+#[derive(Debug, Clone)]
+pub struct DownstreamEdge {
+    pub actor: ActorRef<SimpleNodeActor>,
+    pub to_port: PortRef,
+    /// 输入端口的语义标签与输出端口不同时，投递前要转换到的标签类型
+    pub convert_to: Option<&'static str>,
+}
+
+impl DownstreamEdge {
+    pub fn new(actor: ActorRef<SimpleNodeActor>, to_port: PortRef) -> Self {
+        Self {
+            actor,
+            to_port,
+            convert_to: None,
+        }
+    }
+
+    pub fn with_conversion(mut self, target_type: &'static str) -> Self {
+        self.convert_to = Some(target_type);
+        self
+    }
+
+    /// 按连接的转换要求准备要投递的标签
+    fn convert(&self, data: &dyn SemanticLabel) -> Result<Box<dyn SemanticLabel>, TransformError> {
+        match self.convert_to {
+            Some(target_type) if data.get_semantic_label_type() != target_type => {
+                data.try_convert_to(target_type)
+            }
+            _ => Ok(data.clone_box()),
+        }
+    }
+}
+
 // 数据类型定义：PortRef -> Box<dyn SemanticLabel>
 // 已用别名 NodeDataInputs 在 core::types 中导出，此处仅备注
 
-/// 简化版NodeActor - 只关注数据驱动的执行
-/// 移除了控制端口、复杂的激活模式等逻辑
+/// 一个控制输入端口的来源及已收到的信号
+///
+/// 端口值确定后不再等待其余来源（例如 OR 的第一个 `+`），这些来源之后到达的信号
+/// 属于已经消费的一轮，到达时丢弃，不会带到下一轮
+#[derive(Debug, Clone)]
+struct ControlInput {
+    activation: ActivationMode,
+    /// 来源端口 -> 信号，None 表示该来源尚无信号
+    signals: HashMap<PortRef, Option<bool>>,
+    /// 来源端口 -> 待丢弃的迟到信号数
+    discards: HashMap<PortRef, usize>,
+}
+
+impl ControlInput {
+    /// 端口值：Some(true) 为 `+`，Some(false) 为 `−`，None 为 `⊥`
+    fn value(&self) -> Option<bool> {
+        let signals: Vec<Option<bool>> = self.signals.values().copied().collect();
+        self.activation.port_value(&signals)
+    }
+
+    /// 记录来源的信号，属于已消费轮次的迟到信号被丢弃
+    fn receive(&mut self, source: PortRef, active: bool) {
+        if let Some(discards) = self.discards.get_mut(&source).filter(|n| **n > 0) {
+            *discards -= 1;
+            return;
+        }
+        self.signals.insert(source, Some(active));
+    }
+
+    /// 消费一轮信号，尚未报告的来源之后到达的信号记为待丢弃
+    fn reset(&mut self) {
+        for (source, signal) in &mut self.signals {
+            if signal.take().is_none() {
+                *self.discards.entry(source.clone()).or_default() += 1;
+            }
+        }
+    }
+}
+
+/// 数据输入端口上的一个 token
+#[derive(Debug, Clone)]
+enum InputToken {
+    Data(Box<dyn SemanticLabel>),
+    /// 上游被跳过、失败或没有产生输出，附带原因
+    Skipped(String),
+}
+
+/// 扇入端口：各来源的 token 分开排队，按扇入模式合成端口的 token
+///
+/// 每个来源的队列头组成一轮。Collect / MergeByKey 等待所有来源，被跳过的来源
+/// 不计入集合，全部来源都被跳过时端口视为被跳过；FirstArrival 取本轮最先到达的数据，
+/// 其余来源本轮的 token 被丢弃（还没到达的在到达时丢弃）
+#[derive(Debug, Clone)]
+struct FanInInput {
+    mode: FanIn,
+    /// 来源端口，按连接顺序
+    sources: Vec<PortRef>,
+    /// 来源 -> (到达序号, token)
+    queues: HashMap<PortRef, VecDeque<(u64, InputToken)>>,
+    /// 来源 -> 到达时要丢弃的 token 数
+    discards: HashMap<PortRef, usize>,
+    next_seq: u64,
+}
+
+impl FanInInput {
+    fn new(mode: FanIn, sources: Vec<PortRef>) -> Self {
+        Self {
+            mode,
+            sources,
+            queues: HashMap::new(),
+            discards: HashMap::new(),
+            next_seq: 0,
+        }
+    }
+
+    fn front(&self, source: &PortRef) -> Option<&(u64, InputToken)> {
+        self.queues.get(source).and_then(VecDeque::front)
+    }
+
+    /// 本轮的输入是否已经可以合成
+    fn is_ready(&self) -> bool {
+        let mut fronts = self.sources.iter().map(|source| self.front(source));
+        match self.mode {
+            FanIn::FirstArrival => {
+                let fronts: Vec<_> = fronts.collect();
+                fronts.iter().all(Option::is_some)
+                    || fronts
+                        .iter()
+                        .any(|front| matches!(front, Some((_, InputToken::Data(_)))))
+            }
+            FanIn::Collect | FanIn::MergeByKey => fronts.all(|front| front.is_some()),
+        }
+    }
+
+    fn pending_tokens(&self) -> usize {
+        self.queues.values().map(VecDeque::len).sum::<usize>()
+            + self.discards.values().sum::<usize>()
+    }
+
+    /// 放入来源的一个 token，容量语义同 [`InputSlot::push`]
+    fn push(
+        &mut self,
+        source: PortRef,
+        token: InputToken,
+        capacity: Option<usize>,
+    ) -> Result<(), InputToken> {
+        if let Some(discards) = self.discards.get_mut(&source).filter(|n| **n > 0) {
+            *discards -= 1;
+            return Ok(());
+        }
+        let queue = self.queues.entry(source).or_default();
+        match capacity {
+            None => queue.clear(),
+            Some(capacity) if queue.len() >= capacity => return Err(token),
+            Some(_) => {}
+        }
+        queue.push_back((self.next_seq, token));
+        self.next_seq += 1;
+        Ok(())
+    }
+
+    /// 消费一轮输入，合成端口的 token
+    fn take(&mut self) -> Option<InputToken> {
+        if !self.is_ready() {
+            return None;
+        }
+        if self.mode == FanIn::FirstArrival {
+            return Some(self.take_first_arrival());
+        }
+
+        let mut items = Vec::new();
+        let mut skip_reason = None;
+        for source in &self.sources {
+            match self.queues.get_mut(source).and_then(VecDeque::pop_front) {
+                Some((_, InputToken::Data(data))) => items.push((source, data)),
+                Some((_, InputToken::Skipped(reason))) => {
+                    skip_reason.get_or_insert(reason);
+                }
+                None => {}
+            }
+        }
+        if items.is_empty() {
+            return Some(InputToken::Skipped(skip_reason.unwrap_or_default()));
+        }
+        let label: Box<dyn SemanticLabel> = match self.mode {
+            FanIn::MergeByKey => Box::new(RecordLabel::new(
+                items
+                    .into_iter()
+                    .map(|(source, data)| (source.node_name.clone(), data))
+                    .collect::<BTreeMap<_, _>>(),
+            )),
+            _ => Box::new(ArrayLabel::new(
+                items.into_iter().map(|(_, data)| data).collect(),
+            )),
+        };
+        Some(InputToken::Data(label))
+    }
+
+    fn take_first_arrival(&mut self) -> InputToken {
+        let winner = self
+            .sources
+            .iter()
+            .filter_map(|source| match self.front(source) {
+                Some((seq, InputToken::Data(_))) => Some((*seq, source.clone())),
+                _ => None,
+            })
+            .min_by_key(|(seq, _)| *seq)
+            .map(|(_, source)| source);
+
+        let mut token = None;
+        for source in &self.sources {
+            match self.queues.get_mut(source).and_then(VecDeque::pop_front) {
+                // 没有数据时全部来源都被跳过，取第一个来源的原因
+                Some((_, front)) if winner.as_ref() == Some(source) || winner.is_none() => {
+                    token.get_or_insert(front);
+                }
+                Some(_) => {}
+                None => *self.discards.entry(source.clone()).or_default() += 1,
+            }
+        }
+        token.unwrap_or_else(|| InputToken::Skipped(String::new()))
+    }
+}
+
+/// 队列已满时暂存的 token，放入队列后才回复发送方
+#[derive(Debug)]
+struct ParkedToken {
+    source: PortRef,
+    token: InputToken,
+    reply: Option<ReplySender<()>>,
+}
+
+/// 一次运行的输入缓存
+#[derive(Debug, Default)]
+struct InputSlot {
+    /// 数据端口 -> 待消费的 token，先到先用
+    queues: HashMap<PortRef, VecDeque<InputToken>>,
+    /// 数据端口 -> 队列已满时暂存的 token，按到达顺序
+    parked: HashMap<PortRef, VecDeque<ParkedToken>>,
+    /// 控制输入端口及已收到的信号
+    control: HashMap<PortName, ControlInput>,
+    /// 扇入端口的各来源队列
+    fan_in: HashMap<PortRef, FanInInput>,
+    /// 可选端口的等待计时，见 [`SimpleNodeActor::schedule_optional_wait`]
+    wait_timer: Option<u64>,
+    /// 可选端口的等待时间已到，不再等待它们
+    wait_elapsed: bool,
+}
+
+impl InputSlot {
+    /// 控制状态：所有控制输入端口都为 `+` 时为 Some(true)，任一为 `−` 时为 Some(false)，
+    /// 还有端口为 `⊥` 时为 None。没有控制输入时总是激活
+    fn control_state(&self) -> Option<bool> {
+        let mut active = true;
+        for input in self.control.values() {
+            active &= input.value()?;
+        }
+        Some(active)
+    }
+
+    /// 数据端口有数据，或者已知上游被跳过
+    fn has_input(&self, port: &PortRef) -> bool {
+        match self.fan_in.get(port) {
+            Some(fan_in) => fan_in.is_ready(),
+            None => self.queues.get(port).is_some_and(|queue| !queue.is_empty()),
+        }
+    }
+
+    fn pending_tokens(&self) -> usize {
+        self.queues.values().map(VecDeque::len).sum::<usize>()
+            + self.parked.values().map(VecDeque::len).sum::<usize>()
+            + self
+                .control
+                .values()
+                .flat_map(|control| control.discards.values())
+                .sum::<usize>()
+            + self
+                .fan_in
+                .values()
+                .map(FanInInput::pending_tokens)
+                .sum::<usize>()
+    }
+
+    /// 没有待消费的 token，也没有收到控制信号
+    fn is_empty(&self) -> bool {
+        self.pending_tokens() == 0
+            && self
+                .control
+                .values()
+                .all(|control| control.signals.values().all(Option::is_none))
+    }
+
+    /// 放入来自 `source` 的一个 token
+    ///
+    /// 没有容量限制时（单次运行模式）端口只保留最新的 token；
+    /// 有容量限制时（流式模式）排在队尾，队列已满时拒绝并交还 token。
+    /// 扇入端口按来源分别排队
+    fn push(
+        &mut self,
+        source: PortRef,
+        port: PortRef,
+        token: InputToken,
+        capacity: Option<usize>,
+    ) -> Result<(), InputToken> {
+        if let Some(fan_in) = self.fan_in.get_mut(&port) {
+            return fan_in.push(source, token, capacity);
+        }
+        let queue = self.queues.entry(port).or_default();
+        match capacity {
+            None => queue.clear(),
+            Some(capacity) if queue.len() >= capacity => return Err(token),
+            Some(_) => {}
+        }
+        queue.push_back(token);
+        Ok(())
+    }
+
+    /// 把暂存的 token 按到达顺序放入腾出位置的队列并回复发送方，返回放入的个数
+    fn unpark(&mut self, capacity: Option<usize>) -> usize {
+        let mut unparked = 0;
+        let ports: Vec<PortRef> = self.parked.keys().cloned().collect();
+        for port in ports {
+            let Some(mut parked) = self.parked.remove(&port) else {
+                continue;
+            };
+            let mut still_parked = VecDeque::new();
+            while let Some(ParkedToken {
+                source,
+                token,
+                reply,
+            }) = parked.pop_front()
+            {
+                match self.push(source.clone(), port.clone(), token, capacity) {
+                    Ok(()) => {
+                        if let Some(reply) = reply {
+                            reply.send(());
+                        }
+                        unparked += 1;
+                    }
+                    Err(token) => still_parked.push_back(ParkedToken {
+                        source,
+                        token,
+                        reply,
+                    }),
+                }
+            }
+            if !still_parked.is_empty() {
+                self.parked.insert(port, still_parked);
+            }
+        }
+        unparked
+    }
+
+    /// 消费一次执行的输入：每个有 token 的数据端口取出一个，并清空控制信号
+    fn take(&mut self) -> Vec<(PortRef, InputToken)> {
+        self.wait_timer = None;
+        self.wait_elapsed = false;
+        self.control.values_mut().for_each(ControlInput::reset);
+        let fan_in = self
+            .fan_in
+            .iter_mut()
+            .filter_map(|(port, fan_in)| fan_in.take().map(|token| (port.clone(), token)));
+        self.queues
+            .iter_mut()
+            .filter_map(|(port, queue)| queue.pop_front().map(|token| (port.clone(), token)))
+            .chain(fan_in)
+            .collect()
+    }
+
+    /// 尚未满足的输入：缺数据的端口和尚无信号的控制端口
+    fn waiting_for(&self, data_ports: &[PortRef]) -> Vec<PortName> {
+        let mut ports: Vec<PortName> = data_ports
+            .iter()
+            .filter(|port| !self.has_input(port))
+            .map(|port| port.port_name.clone())
+            .chain(
+                self.control
+                    .iter()
+                    .filter(|(_, control)| control.value().is_none())
+                    .map(|(port_name, _)| port_name.clone()),
+            )
+            .collect();
+        ports.sort();
+        ports
+    }
+}
+
+/// 节点 Actor
+///
+/// 执行条件 `NodeReady ⟺ DataReady ∧ ControlActive`：
+/// 已连接的数据端口都有数据，且每个已连接的控制输入端口按其激活模式得到 `+`。
+/// 任一控制输入为 `−`、或者某个数据输入的上游被跳过时，节点被跳过，
+/// 并向所有下游连接发送跳过消息（控制输入收到的是非激活信号）。
+/// 控制输入为 `−` 时同样等已连接的数据端口都有输入，跳过时一并消费这一轮的输入。
+/// 执行成功后没有写入的输出端口同样向下游发送跳过消息。
+///
+/// 执行失败时按失败策略处理：取消运行、跳过下游，或者把错误发到 `error` 端口。
+///
+/// 输入按运行分开缓存：每条输入消息携带 [`RunContext`]，不同运行的输入互不覆盖，
+/// 一个节点可以交替处理同时进行的多次运行。
+///
+/// 默认每个数据端口只保留最新的输入。流式模式下（[`Self::with_queue_capacity`]）
+/// 每个数据端口是有界 FIFO 队列，每次执行从每个端口消费一个 token，
+/// 控制信号在每次执行后清空。队列已满时不丢弃 token，发送方等待队列腾出位置。
+///
+/// 声明了扇入模式（[`FanIn`]）的数据端口可以有多个来源，各来源分别排队，
+/// 按扇入模式合成端口的输入。
+///
+/// 可选输入端口的上游被跳过时视为没有输入，节点照常执行；没有输入的可选端口
+/// 使用端口声明的默认值。设置了等待时间（`PortDef::wait`）的已连接可选端口
+/// 只在其他输入都就绪后等待这么久，超时后节点不等它执行。
+///
+/// 设置了执行协调器时，节点执行前按 `NodeInfo::mode` 获取执行许可。
+///
+/// 可重试的错误按重试策略退避后重试，每次尝试都受超时限制；
+/// 重试用尽后才按失败策略处理。
 pub struct SimpleNodeActor {
     /// 节点名称
     node_name: NodeName,
 
     /// 节点实现
-    node_impl: Box<dyn Node>,
+    node_impl: NodeImpl,
+
+    /// 节点实例的持久状态，跨执行保留
+    state: NodeState,
 
-    /// 数据缓存 - 简化版，只存储数据输入
-    pending_inputs: NodeData,
+    /// 各次运行的输入缓存
+    slots: HashMap<RunId, InputSlot>,
+
+    /// 流式模式下每个数据端口队列的容量，None 为单次运行模式
+    queue_capacity: Option<usize>,
 
     /// 必需的数据端口名称列表
     connected_input_ports: Vec<PortRef>,
 
+    /// 已连接的控制输入端口，作为每次运行控制信号的初始状态
+    control_inputs: HashMap<PortName, ControlInput>,
+
+    /// 扇入端口及其来源，作为每次运行扇入队列的初始状态
+    fan_in_inputs: HashMap<PortRef, FanInInput>,
+
+    /// 已连接、设置了等待时间的可选输入端口
+    optional_waits: HashMap<PortRef, Duration>,
+
+    /// 最近一次可选端口等待计时的编号
+    last_wait_timer: u64,
+
+    /// 自身引用，在 `on_start` 中设置，用于可选端口的等待计时
+    actor_ref: Option<WeakActorRef<SimpleNodeActor>>,
+
     /// 下游连接映射：输出端口名 -> 下游节点列表
-    downstream_connections: HashMap<PortName, Vec<(ActorRef<SimpleNodeActor>, PortRef)>>,
+    downstream_connections: DownstreamConnections,
 
     /// 状态追踪器引用 (可选)
     status_tracker: Option<ActorRef<SimpleStatusTracker>>,
 
+    /// 图范围的执行协调器 (可选)
+    coordinator: Option<ExecutionCoordinator>,
+
+    /// 执行失败时的处理策略
+    failure_policy: FailurePolicy,
+
+    /// 可重试错误的重试策略
+    retry_policy: RetryPolicy,
+
+    /// 单次尝试的超时时间 (可选)
+    timeout: Option<Duration>,
+
     /// 当前是否正在执行
     is_executing: bool,
 
@@ -47,26 +501,50 @@ pub struct SimpleNodeActor {
     execution_count: u64,
     success_count: u64,
     failure_count: u64,
+    skip_count: u64,
 }
 
 impl SimpleNodeActor {
     pub fn new(
         node_name: NodeName,
-        node_impl: Box<dyn Node>,
+        node_impl: NodeImpl,
         connected_input_ports: Vec<PortRef>,
-        downstream_connections: HashMap<PortName, Vec<(ActorRef<SimpleNodeActor>, PortRef)>>,
+        downstream_connections: DownstreamConnections,
     ) -> Self {
+        let info = node_impl.info();
+        let optional_waits = connected_input_ports
+            .iter()
+            .filter_map(|port| {
+                let def = info
+                    .input_ports
+                    .iter()
+                    .find(|def| def.name == port.port_name && !def.required)?;
+                def.wait.map(|wait| (port.clone(), wait))
+            })
+            .collect();
         Self {
             node_name,
             node_impl,
-            pending_inputs: HashMap::new(),
+            state: NodeState::new(),
+            slots: HashMap::new(),
+            queue_capacity: None,
             connected_input_ports,
+            control_inputs: HashMap::new(),
+            fan_in_inputs: HashMap::new(),
+            optional_waits,
+            last_wait_timer: 0,
+            actor_ref: None,
             downstream_connections,
             status_tracker: None,
+            coordinator: None,
+            failure_policy: FailurePolicy::default(),
+            retry_policy: RetryPolicy::none(),
+            timeout: None,
             is_executing: false,
             execution_count: 0,
             success_count: 0,
             failure_count: 0,
+            skip_count: 0,
         }
     }
 
@@ -76,54 +554,588 @@ impl SimpleNodeActor {
         self
     }
 
-    /// 检查是否可以执行
-    fn can_execute(&self) -> bool {
+    /// 设置执行协调器，与同一图的其他节点共享
+    pub fn with_coordinator(mut self, coordinator: ExecutionCoordinator) -> Self {
+        self.coordinator = Some(coordinator);
+        self
+    }
+
+    /// 设置执行失败时的处理策略
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// 启用流式模式：每个数据输入端口最多缓存 `capacity` 个 token
+    ///
+    /// 队列已满时新到达的 token 暂存，发送方等到它进入队列才继续；
+    /// 本节点向下游发送时同样等待，反压沿连接一直传到输入
+    pub fn with_queue_capacity(mut self, capacity: usize) -> Self {
+        self.queue_capacity = Some(capacity.max(1));
+        self
+    }
+
+    /// 设置可重试错误的重试策略
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// 设置单次尝试的超时时间
+    ///
+    /// 异步节点超时的尝试被取消，按可重试错误处理。同步节点在阻塞线程池中执行，
+    /// 超时后无法中断：执行以不可重试的错误失败，避免重试与仍在运行的尝试重叠，
+    /// 执行许可留给被放弃的尝试，它结束后才释放
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 运行的输入缓存，第一次收到该运行的输入时创建
+    fn slot_mut(&mut self, run_id: &RunId) -> &mut InputSlot {
+        let (control_inputs, fan_in_inputs) = (&self.control_inputs, &self.fan_in_inputs);
+        self.slots
+            .entry(run_id.clone())
+            .or_insert_with(|| InputSlot {
+                control: control_inputs.clone(),
+                fan_in: fan_in_inputs.clone(),
+                ..InputSlot::default()
+            })
+    }
+
+    /// 把来自 `source` 的 token 放入运行的端口队列
+    ///
+    /// 流式模式下 token 进入队列就回复发送方；队列已满时 token 连同回复暂存，
+    /// 执行腾出位置后才放入队列并回复，发送方在此之前一直等待。
+    /// 返回消息处理结束时还要回复的发送方
+    async fn push_token(
+        &mut self,
+        run: &RunContext,
+        source: PortRef,
+        port: PortRef,
+        token: InputToken,
+        reply: Option<ReplySender<()>>,
+    ) -> Option<ReplySender<()>> {
+        let capacity = self.queue_capacity;
+        let slot = self.slot_mut(&run.id);
+        let token = match slot.push(source.clone(), port.clone(), token, capacity) {
+            Ok(()) => {
+                // 扇入端口丢弃了迟到的 token 后，运行可能已经没有残留输入
+                if slot.is_empty() {
+                    self.slots.remove(&run.id);
+                }
+                if capacity.is_none() {
+                    return reply;
+                }
+                if let Some(reply) = reply {
+                    reply.send(());
+                }
+                return None;
+            }
+            Err(token) => token,
+        };
+
+        slot.parked
+            .entry(port.clone())
+            .or_default()
+            .push_back(ParkedToken {
+                source,
+                token,
+                reply,
+            });
+        log::debug!(
+            "Node {} queue on {} is full, sender waits",
+            self.node_name,
+            port.port_name
+        );
+        if let Some(ref tracker) = self.status_tracker {
+            let full_event = NodeStatusEvent::InputQueueFull {
+                run_id: run.id.clone(),
+                node_name: self.node_name.clone(),
+                port_name: port.port_name,
+                capacity: capacity.unwrap_or_default(),
+            };
+            let _ = tracker.tell(full_event).await;
+        }
+        None
+    }
+
+    /// 记录控制输入端口 `port` 收到的来自 `source` 的信号
+    fn receive_signal(&mut self, run: &RunContext, port: &PortRef, source: PortRef, active: bool) {
+        let slot = self.slot_mut(&run.id);
+        if let Some(control) = slot.control.get_mut(&port.port_name) {
+            control.receive(source, active);
+        }
+        // 丢弃了迟到的信号后，运行可能已经没有残留输入
+        if slot.is_empty() {
+            self.slots.remove(&run.id);
+        }
+    }
+
+    /// 运行尚未满足的输入端口
+    fn waiting_for(&self, run_id: &RunId) -> Vec<PortName> {
+        match self.slots.get(run_id) {
+            Some(slot) => slot.waiting_for(&self.connected_input_ports),
+            None => InputSlot {
+                control: self.control_inputs.clone(),
+                fan_in: self.fan_in_inputs.clone(),
+                ..InputSlot::default()
+            }
+            .waiting_for(&self.connected_input_ports),
+        }
+    }
+
+    /// 使用给定的节点状态（默认为空状态）
+    pub fn with_state(mut self, state: NodeState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// 设置控制输入端口的来源：端口名 -> 上游控制输出端口
+    ///
+    /// 激活模式取自节点声明的端口类型
+    pub fn with_control_sources(mut self, sources: HashMap<PortName, Vec<PortRef>>) -> Self {
+        let info = self.node_impl.info();
+        self.control_inputs = sources
+            .into_iter()
+            .map(|(port_name, sources)| {
+                let activation = info
+                    .input_ports
+                    .iter()
+                    .find(|port| port.name == port_name)
+                    .and_then(|port| match port.port_type {
+                        PortType::Control { activation } => Some(activation),
+                        PortType::Data { .. } => None,
+                    })
+                    .unwrap_or_default();
+                let signals = sources.into_iter().map(|source| (source, None)).collect();
+                (
+                    port_name,
+                    ControlInput {
+                        activation,
+                        signals,
+                        discards: HashMap::new(),
+                    },
+                )
+            })
+            .collect();
+        self
+    }
+
+    /// 设置扇入端口的来源：端口名 -> 上游输出端口（按连接顺序，图输入为端口自身）
+    ///
+    /// 扇入模式取自节点声明的端口，没有声明扇入模式的端口被忽略
+    pub fn with_fan_in_sources(mut self, sources: HashMap<PortName, Vec<PortRef>>) -> Self {
+        let info = self.node_impl.info();
+        self.fan_in_inputs = sources
+            .into_iter()
+            .filter_map(|(port_name, sources)| {
+                let mode = info
+                    .input_ports
+                    .iter()
+                    .find(|port| port.name == port_name)
+                    .and_then(|port| port.fan_in)?;
+                let port = PortRef {
+                    node_name: self.node_name.clone(),
+                    port_name,
+                };
+                Some((port, FanInInput::new(mode, sources)))
+            })
+            .collect();
+        self
+    }
+
+    /// 检查运行的输入是否允许执行
+    fn can_execute(&self, run_id: &RunId) -> bool {
         // 1. 不能在执行中再次执行
         if self.is_executing {
             return false;
         }
 
-        // 2. 检查所有必需的数据端口是否都有数据
+        // 2. 控制输入都已确定
+        let Some(slot) = self.slots.get(run_id) else {
+            // 没有收到过这次运行的输入：只有没有输入端口的源节点可以执行
+            return self.connected_input_ports.is_empty() && self.control_inputs.is_empty();
+        };
+        if slot.control_state().is_none() {
+            return false;
+        }
+
+        // 3. 检查所有必需的数据端口是否都有数据（或者已知上游被跳过），
+        //    等待时间已到的可选端口不再等待。控制输入非激活时同样等待数据，
+        //    跳过时一并消费这一轮的数据，迟到的数据不会与下一轮配对
+        self.connected_input_ports.iter().all(|port| {
+            slot.has_input(port) || (slot.wait_elapsed && self.optional_waits.contains_key(port))
+        })
+    }
+
+    /// 端口是可选输入端口
+    fn is_optional(&self, port: &PortRef) -> bool {
+        self.node_impl
+            .info()
+            .input_ports
+            .iter()
+            .any(|def| def.name == port.port_name && !def.required)
+    }
+
+    /// 运行只差设置了等待时间的可选端口时开始计时，时间到后不再等待它们
+    ///
+    /// 等待时间取缺少的端口中最长的一个。计时消息计入运行的在途消息
+    fn schedule_optional_wait(&mut self, run: &RunContext) {
+        let Some(actor_ref) = self.actor_ref.clone() else {
+            return;
+        };
+        let Some(slot) = self.slots.get(&run.id) else {
+            return;
+        };
+        if slot.wait_timer.is_some() || slot.wait_elapsed || slot.control_state() != Some(true) {
+            return;
+        }
+        let mut wait = None;
         for port in &self.connected_input_ports {
-            if !self.pending_inputs.contains_key(port) {
-                return false;
+            if slot.has_input(port) {
+                continue;
+            }
+            match self.optional_waits.get(port) {
+                Some(port_wait) => wait = wait.max(Some(*port_wait)),
+                None => return,
+            }
+        }
+        let Some(wait) = wait else {
+            return;
+        };
+
+        self.last_wait_timer += 1;
+        let timer = self.last_wait_timer;
+        if let Some(slot) = self.slots.get_mut(&run.id) {
+            slot.wait_timer = Some(timer);
+        }
+        let run = run.clone();
+        run.begin();
+        tokio::spawn(async move {
+            tokio::time::sleep(wait).await;
+            let delivered = match actor_ref.upgrade() {
+                Some(actor_ref) => actor_ref
+                    .tell(OptionalWaitElapsed {
+                        timer,
+                        run: run.clone(),
+                    })
+                    .await
+                    .is_ok(),
+                None => false,
+            };
+            if !delivered {
+                run.end();
             }
+        });
+    }
+
+    /// 跳过本次执行，向所有下游传播跳过
+    async fn skip(&mut self, run: &RunContext, reason: String) {
+        let execution_id = Uuid::new_v4().to_string();
+        self.skip_count += 1;
+
+        log::info!("Node {} skipped: {}", self.node_name, reason);
+        if let Some(ref tracker) = self.status_tracker {
+            let skip_event = NodeStatusEvent::ExecutionSkipped {
+                run_id: run.id.clone(),
+                node_name: self.node_name.clone(),
+                execution_id: execution_id.clone(),
+                reason,
+            };
+            let _ = tracker.tell(skip_event).await;
         }
 
-        true
+        let reason = format!("upstream {} skipped", self.node_name);
+        self.send_outputs_to_downstream(run, NodeDataOutputs::new(), execution_id, &reason)
+            .await;
     }
 
-    /// 执行节点逻辑
-    async fn execute(&mut self) {
-        if !self.can_execute() {
-            return;
+    /// 为节点没有写入的控制输出补上信号
+    fn fill_control_outputs(&self, outputs: &mut NodeDataOutputs, signal: SignalLabel) {
+        for port in &self.node_impl.info().output_ports {
+            if port.is_control() {
+                outputs
+                    .entry(PortRef {
+                        node_name: self.node_name.clone(),
+                        port_name: port.name.clone(),
+                    })
+                    .or_insert_with(|| Box::new(signal.clone()));
+            }
+        }
+    }
+
+    /// 按节点的执行模式获取执行许可，需要等待时先上报等待事件
+    async fn acquire_permit(
+        &self,
+        run: &RunContext,
+        execution_id: &ExecutionId,
+    ) -> Option<ExecutionPermit> {
+        let coordinator = self.coordinator.as_ref()?;
+        let mode = self.node_impl.info().mode;
+        if let Some(permit) = coordinator.try_acquire(mode) {
+            return Some(permit);
+        }
+
+        log::debug!(
+            "Node {} ({}) is waiting for exclusivity",
+            self.node_name,
+            mode
+        );
+        if let Some(ref tracker) = self.status_tracker {
+            let wait_event = NodeStatusEvent::WaitingForExclusivity {
+                run_id: run.id.clone(),
+                node_name: self.node_name.clone(),
+                execution_id: execution_id.clone(),
+                mode,
+            };
+            let _ = tracker.tell(wait_event).await;
+        }
+        Some(coordinator.acquire(mode).await)
+    }
+
+    /// 执行一次尝试，超时的尝试上报超时事件
+    ///
+    /// 异步节点超时返回可重试错误；同步节点超时返回不可重试错误，
+    /// 并把 `permit` 交给仍在运行的阻塞任务，见 [`Self::with_timeout`]
+    async fn invoke_attempt(
+        &self,
+        run: &RunContext,
+        inputs: NodeData,
+        execution_id: &ExecutionId,
+        attempt: u32,
+        permit: &mut Option<ExecutionPermit>,
+    ) -> Result<NodeDataOutputs, NodeError> {
+        let ctx = NodeContext::new(self.node_name.clone(), self.node_impl.info(), inputs)
+            .with_state(self.state.clone())
+            .with_failure_policy(self.failure_policy)
+            .with_cancellation(run.cancellation().clone());
+        let Some(timeout) = self.timeout else {
+            return invoke_node(&self.node_impl, ctx).await;
+        };
+        let abandoned = match &self.node_impl {
+            NodeImpl::Sync(node) => {
+                let mut task = spawn_sync(node.clone(), ctx);
+                match tokio::time::timeout(timeout, &mut task).await {
+                    Ok(joined) => return join_sync(joined),
+                    Err(_) => {
+                        let permit = permit.take();
+                        tokio::spawn(async move {
+                            let _ = task.await;
+                            drop(permit);
+                        });
+                        true
+                    }
+                }
+            }
+            NodeImpl::Async(_) => {
+                match tokio::time::timeout(timeout, invoke_node(&self.node_impl, ctx)).await {
+                    Ok(result) => return result,
+                    Err(_) => false,
+                }
+            }
+        };
+
+        log::warn!(
+            "Node {} timed out after {:?}, attempt #{}",
+            self.node_name,
+            timeout,
+            attempt
+        );
+        if let Some(ref tracker) = self.status_tracker {
+            let timeout_event = NodeStatusEvent::AttemptTimedOut {
+                run_id: run.id.clone(),
+                node_name: self.node_name.clone(),
+                execution_id: execution_id.clone(),
+                attempt,
+                timeout,
+            };
+            let _ = tracker.tell(timeout_event).await;
+        }
+        let message = format!("Node {} timed out after {:?}", self.node_name, timeout);
+        if abandoned {
+            Err(NodeError::fatal(format!(
+                "{}; the blocking attempt is still running and is not retried",
+                message
+            )))
+        } else {
+            Err(NodeError::transient(message))
+        }
+    }
+
+    /// 执行节点，可重试的错误在退避后重试，直到成功、遇到不可重试的错误、
+    /// 次数用尽或者运行被取消。退避期间释放执行许可
+    async fn invoke_with_retry(
+        &self,
+        run: &RunContext,
+        inputs: NodeData,
+        execution_id: &ExecutionId,
+        mut permit: Option<ExecutionPermit>,
+    ) -> Result<NodeDataOutputs, NodeError> {
+        let mut attempt = 1;
+        loop {
+            let attempt_inputs = inputs
+                .iter()
+                .map(|(port, data)| (port.clone(), data.clone_box()))
+                .collect();
+            let error = match self
+                .invoke_attempt(run, attempt_inputs, execution_id, attempt, &mut permit)
+                .await
+            {
+                Ok(outputs) => return Ok(outputs),
+                Err(error) => error,
+            };
+            if !error.is_retryable()
+                || !self.retry_policy.should_retry(attempt)
+                || run.is_cancelled()
+            {
+                return Err(error);
+            }
+
+            let backoff = self.retry_policy.backoff(attempt, jitter_sample());
+            log::warn!(
+                "Node {} attempt #{} failed, retrying in {:?}: {}",
+                self.node_name,
+                attempt,
+                backoff,
+                error
+            );
+            if let Some(ref tracker) = self.status_tracker {
+                let retry_event = NodeStatusEvent::RetryScheduled {
+                    run_id: run.id.clone(),
+                    node_name: self.node_name.clone(),
+                    execution_id: execution_id.clone(),
+                    attempt,
+                    error,
+                    backoff,
+                };
+                let _ = tracker.tell(retry_event).await;
+            }
+
+            drop(permit.take());
+            tokio::time::sleep(backoff).await;
+            permit = self.acquire_permit(run, execution_id).await;
+            attempt += 1;
+        }
+    }
+
+    /// 运行的输入已满足时执行节点逻辑
+    async fn execute(&mut self, run: &RunContext) {
+        // 暂存的 token 放入队列后节点可能再次就绪
+        while self.execute_once(run).await {}
+    }
+
+    /// 就绪时执行一次，返回是否有暂存的 token 放入了队列
+    async fn execute_once(&mut self, run: &RunContext) -> bool {
+        if !self.can_execute(&run.id) {
+            self.schedule_optional_wait(run);
+            return false;
+        }
+        // 消费本次执行的输入，流式模式下队列中剩余的 token 留给之后的执行
+        let capacity = self.queue_capacity;
+        let (control_state, tokens, unparked) = match self.slots.get_mut(&run.id) {
+            Some(slot) => {
+                let control_state = slot.control_state();
+                let tokens = slot.take();
+                let unparked = slot.unpark(capacity) > 0;
+                if slot.pending_tokens() == 0 {
+                    self.slots.remove(&run.id);
+                }
+                (control_state, tokens, unparked)
+            }
+            None => (Some(true), Vec::new(), false),
+        };
+        if run.is_cancelled() {
+            log::debug!(
+                "Node {} discarded inputs of run {}: run cancelled",
+                self.node_name,
+                run.id
+            );
+            return unparked;
+        }
+        if control_state == Some(false) {
+            self.skip(run, "control input inactive".to_string()).await;
+            return unparked;
+        }
+        let mut inputs = NodeData::new();
+        let mut skip_reason = None;
+        for (port, token) in tokens {
+            match token {
+                InputToken::Data(data) => {
+                    inputs.insert(port, data);
+                }
+                // 可选端口的上游被跳过时视为没有输入
+                InputToken::Skipped(_) if self.is_optional(&port) => {}
+                InputToken::Skipped(reason) => skip_reason = Some(reason),
+            }
+        }
+        if let Some(reason) = skip_reason {
+            self.skip(run, reason).await;
+            return unparked;
         }
 
         // 生成执行ID
         let execution_id = Uuid::new_v4().to_string();
-        let start_time = SystemTime::now();
 
         // 标记开始执行
         self.is_executing = true;
         self.execution_count += 1;
 
+        // 获取执行许可，Sequential 节点在此等待其他节点结束
+        let permit = self.acquire_permit(run, &execution_id).await;
+        if run.is_cancelled() {
+            log::debug!(
+                "Node {} discarded inputs of run {}: run cancelled",
+                self.node_name,
+                run.id
+            );
+            self.is_executing = false;
+            return unparked;
+        }
+        let start_time = SystemTime::now();
+
         // 向状态追踪器汇报执行开始
         if let Some(ref tracker) = self.status_tracker {
             let start_event = NodeStatusEvent::ExecutionStarted {
+                run_id: run.id.clone(),
                 node_name: self.node_name.clone(),
                 execution_id: execution_id.clone(),
             };
             let _ = tracker.tell(start_event).await;
         }
 
-        // 收集所有输入数据并清空缓存
-        let inputs: NodeData = self.pending_inputs.drain().collect();
+        // 没有输入的可选端口使用默认值
+        for port in &self.node_impl.info().input_ports {
+            if let (false, Some(default)) = (port.required, &port.default) {
+                inputs
+                    .entry(PortRef {
+                        node_name: self.node_name.clone(),
+                        port_name: port.name.clone(),
+                    })
+                    .or_insert_with(|| default.clone());
+            }
+        }
+
+        // 控制输入以激活信号的形式传给节点
+        for port_name in self.control_inputs.keys() {
+            inputs.insert(
+                PortRef {
+                    node_name: self.node_name.clone(),
+                    port_name: port_name.clone(),
+                },
+                Box::new(SignalLabel::ACTIVE),
+            );
+        }
 
-        // 执行节点逻辑
+        // 执行节点逻辑，可重试的错误按重试策略重试
+        let result = self
+            .invoke_with_retry(run, inputs, &execution_id, permit)
+            .await;
         let duration: Duration = start_time.elapsed().unwrap_or(Duration::from_millis(0));
-        match self.node_impl.execute(inputs) {
-            Ok(outputs) => {
+        match result {
+            Ok(mut outputs) => {
                 self.success_count += 1;
+                self.fill_control_outputs(&mut outputs, SignalLabel::ACTIVE);
 
                 log::info!(
                     "Node {} executed successfully, execution #{}, outputs: {}",
@@ -132,35 +1144,63 @@ impl SimpleNodeActor {
                     outputs.len()
                 );
 
+                // 先将输出发送给下游节点和状态追踪器，再汇报执行成功，
+                // 保证运行完成时汇点输出已经到达
+                let reason = format!("upstream {} produced no output", self.node_name);
+                self.send_outputs_to_downstream(run, outputs, execution_id.clone(), &reason)
+                    .await;
+
                 // 向状态追踪器汇报执行成功
                 if let Some(ref tracker) = self.status_tracker {
                     let complete_event = NodeStatusEvent::ExecutionCompleted {
+                        run_id: run.id.clone(),
                         node_name: self.node_name.clone(),
-                        execution_id: execution_id.clone(),
+                        execution_id,
                         duration,
                     };
                     let _ = tracker.tell(complete_event).await;
                 }
-
-                // 将输出发送给下游节点
-                self.send_outputs_to_downstream(outputs, execution_id).await;
             }
             Err(error) => {
                 self.failure_count += 1;
 
                 log::error!(
-                    "Node {} execution failed, execution #{}: {}",
+                    "Node {} execution failed ({}), execution #{}: {}",
                     self.node_name,
+                    error.kind(),
                     self.execution_count,
                     error
                 );
 
+                // 按失败策略处理下游
+                let mut outputs = NodeDataOutputs::new();
+                match self.failure_policy {
+                    FailurePolicy::FailFast => {
+                        log::warn!("Node {} failed, cancelling run {}", self.node_name, run.id);
+                        run.cancellation().cancel();
+                    }
+                    FailurePolicy::SkipDownstream => {}
+                    FailurePolicy::RouteError => {
+                        outputs.insert(
+                            PortRef {
+                                node_name: self.node_name.clone(),
+                                port_name: ERROR_PORT.to_string(),
+                            },
+                            Box::new(ErrorLabel::new(self.node_name.clone(), error.clone())),
+                        );
+                    }
+                }
+                let reason = format!("upstream {} failed", self.node_name);
+                self.send_outputs_to_downstream(run, outputs, execution_id.clone(), &reason)
+                    .await;
+
                 // 向状态追踪器汇报执行失败
                 if let Some(ref tracker) = self.status_tracker {
                     let fail_event = NodeStatusEvent::ExecutionFailed {
+                        run_id: run.id.clone(),
                         node_name: self.node_name.clone(),
                         execution_id: execution_id.clone(),
-                        error: error.to_string(),
+                        error,
                         duration,
                     };
                     let _ = tracker.tell(fail_event).await;
@@ -170,34 +1210,127 @@ impl SimpleNodeActor {
 
         // 标记执行结束
         self.is_executing = false;
+        unparked
     }
 
     /// 将输出发送给下游节点
+    ///
+    /// 输出按连接预先确定的目标类型转换后投递，转换失败时向状态追踪器报告连接错误，
+    /// 并向该下游发送跳过消息。有下游连接但没有输出的端口向下游发送跳过消息，
+    /// 原因为 `skip_reason`；没有下游连接的数据输出作为汇点输出交给状态追踪器
     async fn send_outputs_to_downstream(
         &self,
+        run: &RunContext,
         outputs: NodeDataOutputs,
         execution_id: ExecutionId,
+        skip_reason: &str,
     ) {
-        for (output_port, data) in outputs {
-            if let Some(downstream_list) = self.downstream_connections.get(&output_port.port_name) {
-                for (downstream_actor, input_port) in downstream_list {
-                    let message = DataInputMessage {
-                        from_port: output_port.clone(),
-                        to_port: input_port.clone(),
-                        data: data.as_ref().clone_box(),
-                        execution_id: execution_id.clone(),
-                    };
-                    let _ = downstream_actor.tell(message).await;
+        let mut outputs: HashMap<PortName, Box<dyn SemanticLabel>> = outputs
+            .into_iter()
+            .map(|(port, data)| (port.port_name, data))
+            .collect();
+
+        for (port_name, downstream_list) in &self.downstream_connections {
+            let from_port = PortRef {
+                node_name: self.node_name.clone(),
+                port_name: port_name.clone(),
+            };
+            let data = outputs.remove(port_name);
+            for edge in downstream_list {
+                let payload = match &data {
+                    Some(data) => match edge.convert(data.as_ref()) {
+                        Ok(data) => Ok(data),
+                        Err(error) => {
+                            let error = EdgeError {
+                                from_port: from_port.clone(),
+                                to_port: edge.to_port.clone(),
+                                from_label: data.get_semantic_label_type(),
+                                to_label: edge.convert_to.unwrap_or_default(),
+                                message: error.to_string(),
+                            };
+                            let reason = error.to_string();
+                            self.report_edge_error(run, &execution_id, error).await;
+                            Err(reason)
+                        }
+                    },
+                    None => Err(skip_reason.to_string()),
+                };
+
+                // 在本条消息处理结束前计入下游消息，计数不会提前归零
+                run.begin();
+                let wait = self.queue_capacity.is_some();
+                let delivered = match payload {
+                    Ok(data) => {
+                        let message = DataInputMessage {
+                            from_port: from_port.clone(),
+                            to_port: edge.to_port.clone(),
+                            data,
+                            execution_id: execution_id.clone(),
+                            run: run.clone(),
+                        };
+                        deliver_input(&edge.actor, message, wait).await.is_ok()
+                    }
+                    Err(reason) => {
+                        let message = SkippedInputMessage {
+                            from_port: from_port.clone(),
+                            to_port: edge.to_port.clone(),
+                            execution_id: execution_id.clone(),
+                            reason,
+                            run: run.clone(),
+                        };
+                        deliver_input(&edge.actor, message, wait).await.is_ok()
+                    }
+                };
+                if !delivered {
+                    run.end();
                 }
             }
         }
+
+        // 汇点输出
+        if let Some(ref tracker) = self.status_tracker {
+            for (port_name, data) in outputs {
+                if data.as_any().is::<SignalLabel>() {
+                    continue;
+                }
+                let sink_output = SinkOutputMessage {
+                    run_id: run.id.clone(),
+                    port: PortRef {
+                        node_name: self.node_name.clone(),
+                        port_name,
+                    },
+                    data,
+                };
+                let _ = tracker.tell(sink_output).await;
+            }
+        }
+    }
+
+    /// 向状态追踪器报告连接上的转换失败
+    async fn report_edge_error(
+        &self,
+        run: &RunContext,
+        execution_id: &ExecutionId,
+        error: EdgeError,
+    ) {
+        log::error!(
+            "Node {} failed to deliver output: {}",
+            self.node_name,
+            error
+        );
+        if let Some(ref tracker) = self.status_tracker {
+            let edge_event = NodeStatusEvent::EdgeConversionFailed {
+                run_id: run.id.clone(),
+                node_name: self.node_name.clone(),
+                execution_id: execution_id.clone(),
+                error,
+            };
+            let _ = tracker.tell(edge_event).await;
+        }
     }
 
     /// 配置下游连接
-    pub fn set_downstream_connections(
-        &mut self,
-        connections: HashMap<PortName, Vec<(ActorRef<SimpleNodeActor>, PortRef)>>,
-    ) {
+    pub fn set_downstream_connections(&mut self, connections: DownstreamConnections) {
         self.downstream_connections = connections;
     }
 
@@ -207,12 +1340,119 @@ impl SimpleNodeActor {
     }
 }
 
+/// 向节点投递一条输入消息
+///
+/// `wait` 时（流式模式）用 `ask` 等到接收方把 token 放入队列，队列已满时一直等待，
+/// 反压由此传到发送方；否则只等待邮箱有空位
+pub(crate) async fn deliver_input<M>(
+    actor: &ActorRef<SimpleNodeActor>,
+    message: M,
+    wait: bool,
+) -> Result<(), SendError<M>>
+where
+    SimpleNodeActor: Message<M, Reply = DelegatedReply<()>>,
+    M: Send + 'static,
+{
+    if !wait {
+        return actor.tell(message).await;
+    }
+    match actor.ask(message).await {
+        // 接收方收到了消息但没有回复，例如运行结束时丢弃了暂存的 token
+        Err(SendError::ActorStopped) => Ok(()),
+        result => result,
+    }
+}
+
+/// 退避抖动用的 `[0, 1)` 随机数
+fn jitter_sample() -> f64 {
+    (Uuid::new_v4().as_u128() >> 75) as f64 / (1u64 << 53) as f64
+}
+
+/// 调用节点实现
+///
+/// 同步节点被移到阻塞线程池执行，避免 CPU 密集型节点占用 runtime 工作线程；
+/// 异步节点直接 await
+pub(crate) async fn invoke_node(
+    node_impl: &NodeImpl,
+    mut ctx: NodeContext,
+) -> Result<NodeDataOutputs, NodeError> {
+    match node_impl {
+        NodeImpl::Sync(node) => join_sync(spawn_sync(node.clone(), ctx).await),
+        NodeImpl::Async(node) => {
+            node.execute(&mut ctx).await?;
+            Ok(ctx.into_outputs())
+        }
+    }
+}
+
+/// 在阻塞线程池中执行同步节点
+fn spawn_sync(
+    node: Arc<dyn Node>,
+    mut ctx: NodeContext,
+) -> JoinHandle<Result<NodeDataOutputs, NodeError>> {
+    tokio::task::spawn_blocking(move || node.execute(&mut ctx).map(|_| ctx.into_outputs()))
+}
+
+fn join_sync(
+    joined: Result<Result<NodeDataOutputs, NodeError>, JoinError>,
+) -> Result<NodeDataOutputs, NodeError> {
+    joined.unwrap_or_else(|e| Err(NodeError::fatal(format!("Node task panicked: {}", e))))
+}
+
+/// 节点生命周期钩子
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum LifecycleHook {
+    Start,
+    Stop,
+}
+
+/// 调用节点的生命周期钩子，同步节点同样在阻塞线程池中执行
+pub(crate) async fn invoke_hook(
+    node_impl: &NodeImpl,
+    state: &NodeState,
+    hook: LifecycleHook,
+) -> Result<(), NodeError> {
+    match node_impl {
+        NodeImpl::Sync(node) => {
+            let node = node.clone();
+            let state = state.clone();
+            tokio::task::spawn_blocking(move || match hook {
+                LifecycleHook::Start => node.on_start(&state),
+                LifecycleHook::Stop => node.on_stop(&state),
+            })
+            .await
+            .unwrap_or_else(|e| {
+                Err(NodeError::fatal(format!(
+                    "Node {:?} hook panicked: {}",
+                    hook, e
+                )))
+            })
+        }
+        NodeImpl::Async(node) => match hook {
+            LifecycleHook::Start => node.on_start(state).await,
+            LifecycleHook::Stop => node.on_stop(state).await,
+        },
+    }
+}
+
 impl Actor for SimpleNodeActor {
     type Args = Self;
     type Error = String;
 
-    async fn on_start(actor: Self::Args, _actor_ref: ActorRef<Self>) -> Result<Self, Self::Error> {
+    async fn on_start(
+        mut actor: Self::Args,
+        actor_ref: ActorRef<Self>,
+    ) -> Result<Self, Self::Error> {
         log::info!("Starting SimpleNodeActor: {}", actor.node_name);
+        actor.actor_ref = Some(actor_ref.downgrade());
+        if let Err(error) = invoke_hook(&actor.node_impl, &actor.state, LifecycleHook::Start).await
+        {
+            log::error!("Node {} failed to start: {}", actor.node_name, error);
+            return Err(format!(
+                "Node {} failed to start: {}",
+                actor.node_name, error
+            ));
+        }
         Ok(actor)
     }
 
@@ -222,36 +1462,139 @@ impl Actor for SimpleNodeActor {
         _reason: ActorStopReason,
     ) -> Result<(), Self::Error> {
         log::info!("Stopping SimpleNodeActor: {}", self.node_name);
-        Ok(())
+        invoke_hook(&self.node_impl, &self.state, LifecycleHook::Stop)
+            .await
+            .map_err(|error| {
+                log::error!("Node {} failed to stop cleanly: {}", self.node_name, error);
+                format!("Node {} failed to stop cleanly: {}", self.node_name, error)
+            })
     }
 }
 
-/// 数据输入消息 - 简化版，只传递数据
+/// 数据输入消息，属于某一次运行：携带的 `RunContext` 决定数据进入哪一次运行的输入槽，
+/// 不同运行的输入各自配对
+///
+/// 处理结束时回复。流式模式下 token 进入端口队列就回复，队列已满时等到
+/// token 进入队列才回复，用 `ask` 发送的一方因此受到反压
 #[derive(Debug)]
 pub struct DataInputMessage {
     pub from_port: PortRef,
     pub to_port: PortRef,
     pub data: Box<dyn SemanticLabel>,
     pub execution_id: ExecutionId,
+    /// 输入所属的运行，节点按运行配对输入
+    pub run: RunContext,
 }
 
 impl Message<DataInputMessage> for SimpleNodeActor {
-    type Reply = Result<(), String>;
+    type Reply = DelegatedReply<()>;
 
     async fn handle(
         &mut self,
         message: DataInputMessage,
-        _ctx: &mut Context<Self, Self::Reply>,
+        ctx: &mut Context<Self, Self::Reply>,
     ) -> Self::Reply {
-        // 存储输入数据
-        self.pending_inputs.insert(message.to_port, message.data);
+        let (delegated, mut reply) = ctx.reply_sender();
+        let run = message.run;
+        if self.control_inputs.contains_key(&message.to_port.port_name) {
+            // 控制输入：按来源记录信号，非 SignalLabel 视为激活信号
+            let active = message
+                .data
+                .as_any()
+                .downcast_ref::<SignalLabel>()
+                .is_none_or(|signal| signal.active);
+            self.receive_signal(&run, &message.to_port, message.from_port, active);
+        } else {
+            // 存储输入数据
+            let token = InputToken::Data(message.data);
+            reply = self
+                .push_token(&run, message.from_port, message.to_port, token, reply)
+                .await;
+        }
 
         // 检查是否可以执行
-        if self.can_execute() {
-            self.execute().await;
+        self.execute(&run).await;
+
+        run.end();
+        if let Some(reply) = reply {
+            reply.send(());
+        }
+        delegated
+    }
+}
+
+/// 上游被跳过、失败或没有在该端口产生输出，这个输入不会再到达
+#[derive(Debug, Clone)]
+pub struct SkippedInputMessage {
+    pub from_port: PortRef,
+    pub to_port: PortRef,
+    pub execution_id: ExecutionId,
+    pub reason: String,
+    pub run: RunContext,
+}
+
+impl Message<SkippedInputMessage> for SimpleNodeActor {
+    type Reply = DelegatedReply<()>;
+
+    async fn handle(
+        &mut self,
+        message: SkippedInputMessage,
+        ctx: &mut Context<Self, Self::Reply>,
+    ) -> Self::Reply {
+        let (delegated, mut reply) = ctx.reply_sender();
+        let run = message.run;
+        if self.control_inputs.contains_key(&message.to_port.port_name) {
+            // 控制输入：视为非激活信号
+            self.receive_signal(&run, &message.to_port, message.from_port, false);
+        } else {
+            let token = InputToken::Skipped(message.reason);
+            reply = self
+                .push_token(&run, message.from_port, message.to_port, token, reply)
+                .await;
         }
 
-        Ok(())
+        self.execute(&run).await;
+
+        run.end();
+        if let Some(reply) = reply {
+            reply.send(());
+        }
+        delegated
+    }
+}
+
+/// 可选端口的等待时间到了，由节点自己在计时结束后发送
+#[derive(Debug)]
+struct OptionalWaitElapsed {
+    timer: u64,
+    run: RunContext,
+}
+
+impl Message<OptionalWaitElapsed> for SimpleNodeActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        message: OptionalWaitElapsed,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> Self::Reply {
+        let run = message.run;
+        // 计时开始后运行的输入已经被消费时，这个计时已经过期
+        let current = self
+            .slots
+            .get_mut(&run.id)
+            .filter(|slot| slot.wait_timer == Some(message.timer));
+        if let Some(slot) = current {
+            slot.wait_elapsed = true;
+            log::debug!(
+                "Node {} stopped waiting for optional inputs of run {}",
+                self.node_name,
+                run.id
+            );
+            self.execute(&run).await;
+        }
+
+        run.end();
     }
 }
 
@@ -262,6 +1605,7 @@ impl Clone for DataInputMessage {
             to_port: self.to_port.clone(),
             data: self.data.clone_box(),
             execution_id: self.execution_id.clone(),
+            run: self.run.clone(),
         }
     }
 }
@@ -270,13 +1614,14 @@ impl Clone for DataInputMessage {
 #[derive(Debug)]
 pub struct TriggerExecutionMessage {
     pub execution_id: ExecutionId,
+    pub run: RunContext,
 }
 
 /// 获取节点状态查询
 #[derive(Debug)]
 /// 设置下游连接的消息
 pub struct SetDownstreamConnectionsMessage {
-    pub connections: HashMap<PortName, Vec<(ActorRef<SimpleNodeActor>, PortRef)>>,
+    pub connections: DownstreamConnections,
 }
 
 impl Message<SetDownstreamConnectionsMessage> for SimpleNodeActor {
@@ -304,6 +1649,29 @@ pub struct NodeStatus {
     pub execution_count: u64,
     pub success_count: u64,
     pub failure_count: u64,
+    pub skip_count: u64,
+    /// 已收到输入、尚未执行的运行数
+    pub pending_runs: usize,
+}
+
+/// 运行结束：丢弃该运行残留的输入，回复节点在这次运行中还在等待的端口
+#[derive(Debug)]
+pub struct FinishRunMessage {
+    pub run_id: RunId,
+}
+
+impl Message<FinishRunMessage> for SimpleNodeActor {
+    type Reply = Vec<PortName>;
+
+    async fn handle(
+        &mut self,
+        message: FinishRunMessage,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> Self::Reply {
+        let waiting_for = self.waiting_for(&message.run_id);
+        self.slots.remove(&message.run_id);
+        waiting_for
+    }
 }
 
 impl Message<TriggerExecutionMessage> for SimpleNodeActor {
@@ -311,26 +1679,27 @@ impl Message<TriggerExecutionMessage> for SimpleNodeActor {
 
     async fn handle(
         &mut self,
-        _message: TriggerExecutionMessage,
+        message: TriggerExecutionMessage,
         _ctx: &mut Context<Self, Self::Reply>,
     ) -> Self::Reply {
         log::info!(
             "TriggerExecutionMessage received for node: {}",
             self.node_name
         );
-        // 对于没有输入端口的节点，直接执行
-        if self.connected_input_ports.is_empty() {
+        // 对于没有输入连接的节点，直接执行
+        if self.connected_input_ports.is_empty() && self.control_inputs.is_empty() {
             log::info!(
                 "Node {} has no input ports, executing directly",
                 self.node_name
             );
-            self.execute().await;
+            self.execute(&message.run).await;
         } else {
             log::warn!(
                 "Node {} has input ports, cannot trigger directly",
                 self.node_name
             );
         }
+        message.run.end();
         Ok(())
     }
 }
@@ -346,12 +1715,1084 @@ impl Message<GetNodeStatusQuery> for SimpleNodeActor {
         NodeStatus {
             node_name: self.node_name.clone(),
             is_executing: self.is_executing,
-            pending_input_count: self.pending_inputs.len(),
+            pending_input_count: self.slots.values().map(InputSlot::pending_tokens).sum(),
             required_port_count: self.connected_input_ports.len(),
             downstream_connection_count: self.downstream_connections.len(),
             execution_count: self.execution_count,
             success_count: self.success_count,
             failure_count: self.failure_count,
+            skip_count: self.skip_count,
+            pending_runs: self.slots.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quiescence::InFlightCounter;
+    use crate::status_tracker::{
+        FinishRunQuery, GetNodeStatsQuery, NodeExecutionStats, StartRunCommand,
+    };
+    use anima_weave_core::{
+        AsyncNode, ExecutionMode, Node, NodeErrorKind, NodeFuture, NodeInfo, PortDef,
+    };
+    use anima_weave_vessels::{AddNode, NumberLabel, StringLabel};
+    use once_cell::sync::Lazy;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static SLEEPY_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "SleepyNode",
+        description: "测试用异步节点",
+        input_ports: vec![],
+        output_ports: vec![PortDef::output_data::<NumberLabel>("value")],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    #[derive(Debug)]
+    struct SleepyNode;
+
+    impl AsyncNode for SleepyNode {
+        fn info(&self) -> &'static NodeInfo {
+            &SLEEPY_NODE_INFO
+        }
+
+        fn execute<'a>(&'a self, ctx: &'a mut NodeContext) -> NodeFuture<'a> {
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                ctx.output("value", NumberLabel { value: 1.0 })
+            })
+        }
+    }
+
+    fn port(node_name: &str, port_name: &str) -> PortRef {
+        PortRef {
+            node_name: node_name.to_string(),
+            port_name: port_name.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invoke_sync_node_on_blocking_pool() {
+        let node: Arc<dyn Node> = Arc::new(AddNode::new());
+        let mut inputs = NodeData::new();
+        inputs.insert(port("sum", "a"), Box::new(NumberLabel { value: 2.0 }));
+        inputs.insert(port("sum", "b"), Box::new(NumberLabel { value: 3.0 }));
+        let ctx = NodeContext::new("sum", node.info(), inputs);
+
+        let outputs = invoke_node(&NodeImpl::Sync(node), ctx).await.unwrap();
+        let result = outputs[&port("sum", "result")]
+            .as_any()
+            .downcast_ref::<NumberLabel>()
+            .unwrap();
+        assert_eq!(result.value, 5.0);
+    }
+
+    static COUNTER_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "CounterNode",
+        description: "测试用计数节点",
+        input_ports: vec![],
+        output_ports: vec![PortDef::output_data::<NumberLabel>("count")],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    /// 启动时初始化计数，每次执行加一，停止时记录最终计数
+    #[derive(Debug, Default)]
+    struct CounterNode {
+        stopped_at: Arc<std::sync::Mutex<Option<u32>>>,
+    }
+
+    impl Node for CounterNode {
+        fn info(&self) -> &'static NodeInfo {
+            &COUNTER_NODE_INFO
+        }
+
+        fn on_start(&self, state: &NodeState) -> Result<(), NodeError> {
+            state.insert(0u32);
+            Ok(())
+        }
+
+        fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+            let count = ctx
+                .state()
+                .with(|count: &mut u32| {
+                    *count += 1;
+                    *count
+                })
+                .ok_or_else(|| NodeError::fatal("on_start was not called"))?;
+            ctx.output(
+                "count",
+                NumberLabel {
+                    value: count as f64,
+                },
+            )
+        }
+
+        fn on_stop(&self, state: &NodeState) -> Result<(), NodeError> {
+            *self.stopped_at.lock().unwrap() = state.get::<u32>();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_hooks_and_state() {
+        let node = Arc::new(CounterNode::default());
+        let stopped_at = node.stopped_at.clone();
+        let actor_ref = SimpleNodeActor::spawn(SimpleNodeActor::new(
+            "counter".to_string(),
+            NodeImpl::Sync(node),
+            vec![],
+            HashMap::new(),
+        ));
+
+        for _ in 0..3 {
+            actor_ref
+                .ask(TriggerExecutionMessage {
+                    execution_id: "test".to_string(),
+                    run: RunContext::new("test"),
+                })
+                .await
+                .unwrap();
+        }
+        let status: NodeStatus = actor_ref.ask(GetNodeStatusQuery).await.unwrap();
+        assert_eq!(status.success_count, 3);
+
+        actor_ref.stop_gracefully().await.unwrap();
+        actor_ref.wait_for_shutdown().await;
+        assert_eq!(*stopped_at.lock().unwrap(), Some(3));
+    }
+
+    static EXCLUSIVE_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "ExclusiveNode",
+        description: "测试用独占节点",
+        input_ports: vec![],
+        output_ports: vec![],
+        mode: ExecutionMode::Sequential,
+    });
+
+    /// 记录同时运行的节点数的最大值
+    #[derive(Debug, Default, Clone)]
+    struct ExclusiveNode {
+        running: Arc<AtomicUsize>,
+        max_running: Arc<AtomicUsize>,
+    }
+
+    impl AsyncNode for ExclusiveNode {
+        fn info(&self) -> &'static NodeInfo {
+            &EXCLUSIVE_NODE_INFO
+        }
+
+        fn execute<'a>(&'a self, _ctx: &'a mut NodeContext) -> NodeFuture<'a> {
+            Box::pin(async move {
+                let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+                self.max_running.fetch_max(running, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                self.running.fetch_sub(1, Ordering::SeqCst);
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sequential_nodes_do_not_overlap() {
+        let coordinator = ExecutionCoordinator::new();
+        let tracker = SimpleStatusTracker::spawn(SimpleStatusTracker::new());
+        let node = ExclusiveNode::default();
+
+        let actors: Vec<_> = (0..3)
+            .map(|i| {
+                SimpleNodeActor::spawn(
+                    SimpleNodeActor::new(
+                        format!("exclusive{}", i),
+                        NodeImpl::Async(Arc::new(node.clone())),
+                        vec![],
+                        HashMap::new(),
+                    )
+                    .with_status_tracker(tracker.clone())
+                    .with_coordinator(coordinator.clone()),
+                )
+            })
+            .collect();
+
+        // 模拟一个正在运行的并发节点，所有 Sequential 节点都要等待
+        let running = coordinator.acquire(ExecutionMode::Concurrent).await;
+        for actor_ref in &actors {
+            actor_ref
+                .tell(TriggerExecutionMessage {
+                    execution_id: "test".to_string(),
+                    run: RunContext::new("test"),
+                })
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(node.max_running.load(Ordering::SeqCst), 0);
+        drop(running);
+
+        for (i, actor_ref) in actors.iter().enumerate() {
+            let status: NodeStatus = actor_ref.ask(GetNodeStatusQuery).await.unwrap();
+            assert_eq!(status.success_count, 1);
+
+            let stats = tracker
+                .ask(GetNodeStatsQuery {
+                    node_name: format!("exclusive{}", i),
+                })
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(stats.exclusivity_waits, 1);
+        }
+        assert_eq!(node.max_running.load(Ordering::SeqCst), 1);
+    }
+
+    static FLAKY_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "FlakyNode",
+        description: "测试用不稳定节点",
+        input_ports: vec![],
+        output_ports: vec![],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    /// 前 `failures` 次执行返回暂时性错误
+    #[derive(Debug)]
+    struct FlakyNode {
+        failures: usize,
+        attempts: AtomicUsize,
+    }
+
+    impl Node for FlakyNode {
+        fn info(&self) -> &'static NodeInfo {
+            &FLAKY_NODE_INFO
+        }
+
+        fn execute(&self, _ctx: &mut NodeContext) -> Result<(), NodeError> {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(NodeError::transient("rate limited"));
+            }
+            Ok(())
+        }
+    }
+
+    async fn run_once(actor: SimpleNodeActor) -> (NodeStatus, NodeExecutionStats) {
+        let tracker = SimpleStatusTracker::spawn(SimpleStatusTracker::new());
+        let actor_ref = SimpleNodeActor::spawn(actor.with_status_tracker(tracker.clone()));
+        actor_ref
+            .ask(TriggerExecutionMessage {
+                execution_id: "test".to_string(),
+                run: RunContext::new("test"),
+            })
+            .await
+            .unwrap();
+        let status: NodeStatus = actor_ref.ask(GetNodeStatusQuery).await.unwrap();
+        let stats = tracker
+            .ask(GetNodeStatsQuery {
+                node_name: status.node_name.clone(),
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        (status, stats)
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_errors() {
+        let retry = RetryPolicy::new(3).with_backoff(Duration::ZERO, Duration::ZERO);
+        let flaky = |failures| {
+            SimpleNodeActor::new(
+                "flaky".to_string(),
+                NodeImpl::Sync(Arc::new(FlakyNode {
+                    failures,
+                    attempts: AtomicUsize::new(0),
+                })),
+                vec![],
+                HashMap::new(),
+            )
+            .with_retry_policy(retry.clone())
+        };
+
+        let (status, stats) = run_once(flaky(2)).await;
+        assert_eq!(status.success_count, 1);
+        assert_eq!(stats.total_executions, 1);
+        assert_eq!(stats.retries, 2);
+
+        let (status, stats) = run_once(flaky(3)).await;
+        assert_eq!(status.failure_count, 1);
+        assert_eq!(stats.retries, 2);
+        assert_eq!(stats.failures_by_kind[&NodeErrorKind::Transient], 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let sleepy = SimpleNodeActor::new(
+            "sleepy".to_string(),
+            NodeImpl::Async(Arc::new(SleepyNode)),
+            vec![],
+            HashMap::new(),
+        )
+        .with_timeout(Duration::from_millis(1));
+
+        let (status, stats) = run_once(sleepy).await;
+        assert_eq!(status.failure_count, 1);
+        assert_eq!(stats.timeouts, 1);
+        assert_eq!(stats.retries, 0);
+    }
+
+    static BLOCKING_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "BlockingNode",
+        description: "测试用阻塞节点",
+        input_ports: vec![],
+        output_ports: vec![],
+        mode: ExecutionMode::Sequential,
+    });
+
+    /// 阻塞线程 50ms 的同步节点，记录执行次数
+    #[derive(Debug, Default)]
+    struct BlockingNode {
+        attempts: AtomicUsize,
+    }
+
+    impl Node for BlockingNode {
+        fn info(&self) -> &'static NodeInfo {
+            &BLOCKING_NODE_INFO
+        }
+
+        fn execute(&self, _ctx: &mut NodeContext) -> Result<(), NodeError> {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(50));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_sync_node_is_not_retried() {
+        let coordinator = ExecutionCoordinator::new();
+        let node = Arc::new(BlockingNode::default());
+        let blocking = SimpleNodeActor::new(
+            "blocking".to_string(),
+            NodeImpl::Sync(node.clone()),
+            vec![],
+            HashMap::new(),
+        )
+        .with_coordinator(coordinator.clone())
+        .with_timeout(Duration::from_millis(5))
+        .with_retry_policy(RetryPolicy::new(3).with_backoff(Duration::ZERO, Duration::ZERO));
+
+        let (status, stats) = run_once(blocking).await;
+        assert_eq!(status.failure_count, 1);
+        assert_eq!(stats.timeouts, 1);
+        assert_eq!(stats.retries, 0);
+        assert_eq!(node.attempts.load(Ordering::SeqCst), 1);
+
+        // 被放弃的尝试仍持有独占许可，结束后才释放
+        assert!(coordinator.try_acquire(ExecutionMode::Concurrent).is_none());
+        let _permit = tokio::time::timeout(
+            Duration::from_secs(1),
+            coordinator.acquire(ExecutionMode::Concurrent),
+        )
+        .await
+        .unwrap();
+    }
+
+    static AND_GATE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "AndGateNode",
+        description: "测试用控制节点",
+        input_ports: vec![PortDef::control_input("trigger")],
+        output_ports: vec![PortDef::control_output("done")],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    static OR_GATE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "OrGateNode",
+        description: "测试用控制节点",
+        input_ports: vec![PortDef::control_input_with("trigger", ActivationMode::Or)],
+        output_ports: vec![PortDef::control_output("done")],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    /// 只在控制输入激活时执行，控制输出由 runtime 补发
+    #[derive(Debug)]
+    struct GateNode(&'static NodeInfo);
+
+    impl Node for GateNode {
+        fn info(&self) -> &'static NodeInfo {
+            self.0
+        }
+
+        fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+            assert!(ctx.input::<SignalLabel>("trigger")?.active);
+            Ok(())
+        }
+    }
+
+    fn spawn_gate(
+        name: &str,
+        info: &'static NodeInfo,
+        sources: &[&str],
+        downstream: DownstreamConnections,
+    ) -> ActorRef<SimpleNodeActor> {
+        let sources = sources.iter().map(|source| port(source, "done")).collect();
+        SimpleNodeActor::spawn(
+            SimpleNodeActor::new(
+                name.to_string(),
+                NodeImpl::Sync(Arc::new(GateNode(info))),
+                vec![],
+                downstream,
+            )
+            .with_control_sources(HashMap::from([("trigger".to_string(), sources)])),
+        )
+    }
+
+    async fn signal(actor_ref: &ActorRef<SimpleNodeActor>, from: &str, active: bool) {
+        actor_ref
+            .ask(DataInputMessage {
+                from_port: port(from, "done"),
+                to_port: port("gate", "trigger"),
+                data: Box::new(SignalLabel { active }),
+                execution_id: "test".to_string(),
+                run: RunContext::new("test"),
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn status(actor_ref: &ActorRef<SimpleNodeActor>) -> NodeStatus {
+        actor_ref.ask(GetNodeStatusQuery).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_and_activation_and_skip_propagation() {
+        let next = spawn_gate("next", &AND_GATE_INFO, &["gate"], HashMap::new());
+        let gate = spawn_gate(
+            "gate",
+            &AND_GATE_INFO,
+            &["s1", "s2"],
+            HashMap::from([(
+                "done".to_string(),
+                vec![DownstreamEdge::new(next.clone(), port("next", "trigger"))],
+            )]),
+        );
+
+        // AND：全部来源激活后才执行，完成后自动发出激活信号
+        signal(&gate, "s1", true).await;
+        assert_eq!(status(&gate).await.execution_count, 0);
+        signal(&gate, "s2", true).await;
+        assert_eq!(status(&gate).await.success_count, 1);
+        assert_eq!(status(&next).await.success_count, 1);
+
+        // 任一来源非激活：立即跳过，并向下游传播非激活信号
+        signal(&gate, "s1", false).await;
+        assert_eq!(status(&gate).await.skip_count, 1);
+        assert_eq!(status(&next).await.skip_count, 1);
+        assert_eq!(status(&next).await.success_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_or_activation() {
+        let gate = spawn_gate("gate", &OR_GATE_INFO, &["s1", "s2"], HashMap::new());
+
+        signal(&gate, "s1", false).await;
+        assert_eq!(status(&gate).await.execution_count, 0);
+        signal(&gate, "s2", true).await;
+        assert_eq!(status(&gate).await.success_count, 1);
+
+        signal(&gate, "s1", false).await;
+        signal(&gate, "s2", false).await;
+        assert_eq!(status(&gate).await.skip_count, 1);
+
+        // 第一个 `+` 就执行，s2 迟到的信号属于这一轮，不带到下一轮
+        signal(&gate, "s1", true).await;
+        assert_eq!(status(&gate).await.success_count, 2);
+        signal(&gate, "s2", true).await;
+        assert_eq!(status(&gate).await.execution_count, 2);
+        assert_eq!(status(&gate).await.pending_input_count, 0);
+
+        signal(&gate, "s1", false).await;
+        assert_eq!(status(&gate).await.execution_count, 2);
+        signal(&gate, "s2", false).await;
+        assert_eq!(status(&gate).await.success_count, 2);
+        assert_eq!(status(&gate).await.skip_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_inputs_are_paired_per_run() {
+        let tracker = SimpleStatusTracker::spawn(SimpleStatusTracker::new());
+        let sum = SimpleNodeActor::spawn(
+            SimpleNodeActor::new(
+                "sum".to_string(),
+                NodeImpl::Sync(Arc::new(AddNode::new())),
+                vec![port("sum", "a"), port("sum", "b")],
+                HashMap::new(),
+            )
+            .with_status_tracker(tracker.clone()),
+        );
+        let runs = [RunContext::new("r1"), RunContext::new("r2")];
+        for run in &runs {
+            tracker
+                .tell(StartRunCommand {
+                    run_id: run.id.clone(),
+                })
+                .await
+                .unwrap();
+        }
+
+        // 两次运行的输入交错到达
+        for (run, port_name, value) in [
+            (&runs[0], "a", 1.0),
+            (&runs[1], "a", 10.0),
+            (&runs[1], "b", 20.0),
+            (&runs[0], "b", 2.0),
+        ] {
+            sum.ask(DataInputMessage {
+                from_port: port("input", port_name),
+                to_port: port("sum", port_name),
+                data: Box::new(NumberLabel { value }),
+                execution_id: "test".to_string(),
+                run: run.clone(),
+            })
+            .await
+            .unwrap();
+        }
+        assert_eq!(status(&sum).await.success_count, 2);
+        assert_eq!(status(&sum).await.pending_runs, 0);
+
+        for (run, expected) in runs.iter().zip([3.0, 30.0]) {
+            let result = tracker
+                .ask(FinishRunQuery {
+                    run_id: run.id.clone(),
+                })
+                .await
+                .unwrap();
+            let value = result.output("sum", "result").unwrap();
+            assert_eq!(
+                value.as_any().downcast_ref::<NumberLabel>().unwrap().value,
+                expected
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streaming_queues() {
+        let tracker = SimpleStatusTracker::spawn(SimpleStatusTracker::new());
+        let sum = SimpleNodeActor::spawn(
+            SimpleNodeActor::new(
+                "sum".to_string(),
+                NodeImpl::Sync(Arc::new(AddNode::new())),
+                vec![port("sum", "a"), port("sum", "b")],
+                HashMap::new(),
+            )
+            .with_status_tracker(tracker.clone())
+            .with_queue_capacity(2),
+        );
+        let run = RunContext::new("run");
+        tracker
+            .tell(StartRunCommand {
+                run_id: run.id.clone(),
+            })
+            .await
+            .unwrap();
+
+        let send = |port_name: &'static str, value: f64| {
+            let (sum, run) = (sum.clone(), run.clone());
+            async move {
+                sum.ask(DataInputMessage {
+                    from_port: port("input", port_name),
+                    to_port: port("sum", port_name),
+                    data: Box::new(NumberLabel { value }),
+                    execution_id: "test".to_string(),
+                    run,
+                })
+                .await
+                .unwrap()
+            }
+        };
+
+        // 第三个 a 超出队列容量，发送方一直等到第一次执行腾出位置
+        send("a", 1.0).await;
+        send("a", 2.0).await;
+        let third = tokio::spawn(send("a", 3.0));
+        while status(&sum).await.pending_input_count < 3 {
+            tokio::task::yield_now().await;
         }
+        assert!(!third.is_finished());
+
+        send("b", 10.0).await;
+        tokio::time::timeout(Duration::from_secs(1), third)
+            .await
+            .unwrap()
+            .unwrap();
+        send("b", 20.0).await;
+        send("b", 30.0).await;
+        assert_eq!(status(&sum).await.success_count, 3);
+        assert_eq!(status(&sum).await.pending_input_count, 0);
+
+        let result = tracker
+            .ask(FinishRunQuery {
+                run_id: run.id.clone(),
+            })
+            .await
+            .unwrap();
+        let values: Vec<f64> = result
+            .stream("sum", "result")
+            .iter()
+            .map(|label| label.as_any().downcast_ref::<NumberLabel>().unwrap().value)
+            .collect();
+        assert_eq!(values, vec![11.0, 22.0, 33.0]);
+
+        let stats = tracker
+            .ask(GetNodeStatsQuery {
+                node_name: "sum".to_string(),
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.backpressure_waits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_inactive_control_consumes_round_data() {
+        let tracker = SimpleStatusTracker::spawn(SimpleStatusTracker::new());
+        let sum = SimpleNodeActor::spawn(
+            SimpleNodeActor::new(
+                "sum".to_string(),
+                NodeImpl::Sync(Arc::new(AddNode::new())),
+                vec![port("sum", "a"), port("sum", "b")],
+                HashMap::new(),
+            )
+            .with_control_sources(HashMap::from([(
+                "trigger".to_string(),
+                vec![port("cond", "done")],
+            )]))
+            .with_status_tracker(tracker.clone())
+            .with_queue_capacity(4),
+        );
+        let run = RunContext::new("run");
+        tracker
+            .tell(StartRunCommand {
+                run_id: run.id.clone(),
+            })
+            .await
+            .unwrap();
+
+        let send = |from: PortRef, port_name: &str, data: Box<dyn SemanticLabel>| {
+            sum.ask(DataInputMessage {
+                from_port: from,
+                to_port: port("sum", port_name),
+                data,
+                execution_id: "test".to_string(),
+                run: run.clone(),
+            })
+        };
+        let number = |port_name: &str, value: f64| {
+            send(
+                port("input", port_name),
+                port_name,
+                Box::new(NumberLabel { value }),
+            )
+        };
+        let trigger = |active: bool| {
+            send(
+                port("cond", "done"),
+                "trigger",
+                Box::new(SignalLabel { active }),
+            )
+        };
+
+        // 第一轮的控制信号先于数据到达：等数据到齐后一并跳过
+        trigger(false).await.unwrap();
+        assert_eq!(status(&sum).await.skip_count, 0);
+        number("a", 1.0).await.unwrap();
+        number("b", 10.0).await.unwrap();
+        assert_eq!(status(&sum).await.skip_count, 1);
+
+        // 第二轮只使用第二轮的数据
+        trigger(true).await.unwrap();
+        number("a", 2.0).await.unwrap();
+        number("b", 20.0).await.unwrap();
+        assert_eq!(status(&sum).await.success_count, 1);
+        assert_eq!(status(&sum).await.pending_input_count, 0);
+
+        let result = tracker
+            .ask(FinishRunQuery {
+                run_id: run.id.clone(),
+            })
+            .await
+            .unwrap();
+        let values: Vec<f64> = result
+            .stream("sum", "result")
+            .iter()
+            .map(|label| label.as_any().downcast_ref::<NumberLabel>().unwrap().value)
+            .collect();
+        assert_eq!(values, vec![22.0]);
+    }
+
+    static LABEL_TYPE_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "LabelTypeNode",
+        description: "测试用节点，输出收到的标签类型",
+        input_ports: vec![PortDef::required_data::<StringLabel>("text")],
+        output_ports: vec![PortDef::output_data::<StringLabel>("label_type")],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    #[derive(Debug)]
+    struct LabelTypeNode;
+
+    impl Node for LabelTypeNode {
+        fn info(&self) -> &'static NodeInfo {
+            &LABEL_TYPE_NODE_INFO
+        }
+
+        fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+            let value = ctx
+                .raw_input("text")
+                .map(|label| label.get_semantic_label_type().to_string())
+                .unwrap_or_default();
+            ctx.output("label_type", StringLabel { value })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_edge_conversion() {
+        let tracker = SimpleStatusTracker::spawn(SimpleStatusTracker::new());
+        let spawn_label_type = |name: &str| {
+            SimpleNodeActor::spawn(
+                SimpleNodeActor::new(
+                    name.to_string(),
+                    NodeImpl::Sync(Arc::new(LabelTypeNode)),
+                    vec![port(name, "text")],
+                    HashMap::new(),
+                )
+                .with_status_tracker(tracker.clone()),
+            )
+        };
+        let converted = spawn_label_type("converted");
+        let broken = spawn_label_type("broken");
+        let add = SimpleNodeActor::spawn(
+            SimpleNodeActor::new(
+                "add".to_string(),
+                NodeImpl::Sync(Arc::new(AddNode::new())),
+                vec![port("add", "a"), port("add", "b")],
+                HashMap::from([(
+                    "result".to_string(),
+                    vec![
+                        DownstreamEdge::new(converted.clone(), port("converted", "text"))
+                            .with_conversion("StringLabel"),
+                        DownstreamEdge::new(broken.clone(), port("broken", "text"))
+                            .with_conversion("MissingLabel"),
+                    ],
+                )]),
+            )
+            .with_status_tracker(tracker.clone()),
+        );
+
+        let run = RunContext::new("run");
+        tracker
+            .tell(StartRunCommand {
+                run_id: run.id.clone(),
+            })
+            .await
+            .unwrap();
+        for port_name in ["a", "b"] {
+            add.ask(DataInputMessage {
+                from_port: port("input", port_name),
+                to_port: port("add", port_name),
+                data: Box::new(NumberLabel { value: 1.0 }),
+                execution_id: "test".to_string(),
+                run: run.clone(),
+            })
+            .await
+            .unwrap();
+        }
+        assert_eq!(status(&converted).await.success_count, 1);
+        assert_eq!(status(&broken).await.skip_count, 1);
+
+        let result = tracker
+            .ask(FinishRunQuery {
+                run_id: run.id.clone(),
+            })
+            .await
+            .unwrap();
+        let label_type = result.output("converted", "label_type").unwrap();
+        assert_eq!(
+            label_type
+                .as_any()
+                .downcast_ref::<StringLabel>()
+                .unwrap()
+                .value,
+            "StringLabel"
+        );
+        assert!(!result.is_success());
+        assert_eq!(result.edge_errors.len(), 1);
+        let error = &result.edge_errors[0];
+        assert_eq!(error.from_port, port("add", "result"));
+        assert_eq!(error.to_port, port("broken", "text"));
+        assert_eq!(
+            (error.from_label, error.to_label),
+            ("NumberLabel", "MissingLabel")
+        );
+
+        let stats = tracker
+            .ask(GetNodeStatsQuery {
+                node_name: "add".to_string(),
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.conversion_failures, 1);
+    }
+
+    static COLLECT_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "DigitsNode",
+        description: "测试用扇入节点，按顺序把数字拼成一个数",
+        input_ports: vec![
+            PortDef::required_data::<NumberLabel>("values").with_fan_in(FanIn::Collect),
+        ],
+        output_ports: vec![PortDef::output_data::<NumberLabel>("result")],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    static FIRST_ARRIVAL_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "DigitsNode",
+        description: "测试用扇入节点，输出最先到达的数字",
+        input_ports: vec![
+            PortDef::required_data::<NumberLabel>("values").with_fan_in(FanIn::FirstArrival),
+        ],
+        output_ports: vec![PortDef::output_data::<NumberLabel>("result")],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    #[derive(Debug)]
+    struct DigitsNode(&'static NodeInfo);
+
+    impl Node for DigitsNode {
+        fn info(&self) -> &'static NodeInfo {
+            self.0
+        }
+
+        fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+            let label = ctx.input_label("values")?;
+            let value = match label.as_any().downcast_ref::<ArrayLabel>() {
+                Some(array) => array
+                    .items_as::<NumberLabel>()
+                    .map_err(|e| NodeError::invalid_input("values", e.to_string()))?
+                    .iter()
+                    .fold(0.0, |acc, digit| acc * 10.0 + digit.value),
+                None => ctx.input::<NumberLabel>("values")?.value,
+            };
+            ctx.output("result", NumberLabel { value })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fan_in_ports() {
+        let tracker = SimpleStatusTracker::spawn(SimpleStatusTracker::new());
+        let spawn_digits = |name: &str, info: &'static NodeInfo, sources: &[&str]| {
+            let sources = sources
+                .iter()
+                .map(|source| port(source, "result"))
+                .collect();
+            SimpleNodeActor::spawn(
+                SimpleNodeActor::new(
+                    name.to_string(),
+                    NodeImpl::Sync(Arc::new(DigitsNode(info))),
+                    vec![port(name, "values")],
+                    HashMap::new(),
+                )
+                .with_status_tracker(tracker.clone())
+                .with_fan_in_sources(HashMap::from([("values".to_string(), sources)])),
+            )
+        };
+        let collect = spawn_digits("collect", &COLLECT_NODE_INFO, &["s1", "s2", "s3"]);
+        let first = spawn_digits("first", &FIRST_ARRIVAL_NODE_INFO, &["s1", "s2"]);
+        let run = RunContext::new("run");
+        tracker
+            .tell(StartRunCommand {
+                run_id: run.id.clone(),
+            })
+            .await
+            .unwrap();
+        let send = |actor_ref: &ActorRef<SimpleNodeActor>, to: &str, from: &str, value| {
+            let (actor_ref, run) = (actor_ref.clone(), run.clone());
+            let (from_port, to_port) = (port(from, "result"), port(to, "values"));
+            async move {
+                match value {
+                    Some(value) => actor_ref
+                        .ask(DataInputMessage {
+                            from_port,
+                            to_port,
+                            data: Box::new(NumberLabel { value }),
+                            execution_id: "test".to_string(),
+                            run,
+                        })
+                        .await
+                        .unwrap(),
+                    None => actor_ref
+                        .ask(SkippedInputMessage {
+                            from_port,
+                            to_port,
+                            execution_id: "test".to_string(),
+                            reason: "upstream skipped".to_string(),
+                            run,
+                        })
+                        .await
+                        .unwrap(),
+                }
+            }
+        };
+
+        // Collect：等待所有来源，按连接顺序收集，被跳过的来源不计入
+        send(&collect, "collect", "s2", Some(2.0)).await;
+        send(&collect, "collect", "s1", Some(1.0)).await;
+        assert_eq!(status(&collect).await.execution_count, 0);
+        send(&collect, "collect", "s3", None).await;
+        assert_eq!(status(&collect).await.success_count, 1);
+
+        // FirstArrival：没有数据的来源不阻塞，迟到的数据被丢弃
+        send(&first, "first", "s1", None).await;
+        send(&first, "first", "s2", Some(5.0)).await;
+        send(&first, "first", "s2", Some(7.0)).await;
+        send(&first, "first", "s1", Some(9.0)).await;
+        assert_eq!(status(&first).await.success_count, 2);
+        assert_eq!(status(&first).await.pending_runs, 0);
+
+        let result = tracker
+            .ask(FinishRunQuery {
+                run_id: run.id.clone(),
+            })
+            .await
+            .unwrap();
+        let values = |node_name| -> Vec<f64> {
+            result
+                .stream(node_name, "result")
+                .iter()
+                .map(|label| label.as_any().downcast_ref::<NumberLabel>().unwrap().value)
+                .collect()
+        };
+        assert_eq!(values("collect"), vec![12.0]);
+        assert_eq!(values("first"), vec![5.0, 7.0]);
+    }
+
+    static OPTIONAL_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "OptionalSumNode",
+        description: "测试用节点，可选端口带默认值",
+        input_ports: vec![
+            PortDef::required_data::<NumberLabel>("a"),
+            PortDef::optional_data::<NumberLabel>("b")
+                .with_default(NumberLabel { value: 10.0 })
+                .with_wait(Duration::from_millis(20)),
+            PortDef::optional_data::<NumberLabel>("c").with_default(NumberLabel { value: 100.0 }),
+        ],
+        output_ports: vec![PortDef::output_data::<NumberLabel>("result")],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    #[derive(Debug)]
+    struct OptionalSumNode;
+
+    impl Node for OptionalSumNode {
+        fn info(&self) -> &'static NodeInfo {
+            &OPTIONAL_NODE_INFO
+        }
+
+        fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+            let mut value = 0.0;
+            for port_name in ["a", "b", "c"] {
+                value += ctx.input::<NumberLabel>(port_name)?.value;
+            }
+            ctx.output("result", NumberLabel { value })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_optional_ports() {
+        let tracker = SimpleStatusTracker::spawn(SimpleStatusTracker::new());
+        // b 已连接，c 未连接
+        let sum = SimpleNodeActor::spawn(
+            SimpleNodeActor::new(
+                "sum".to_string(),
+                NodeImpl::Sync(Arc::new(OptionalSumNode)),
+                vec![port("sum", "a"), port("sum", "b")],
+                HashMap::new(),
+            )
+            .with_status_tracker(tracker.clone()),
+        );
+        let in_flight = InFlightCounter::new();
+        let runs: Vec<_> = (0..3)
+            .map(|i| RunContext::tracked(format!("run{}", i), in_flight.clone()))
+            .collect();
+        for run in &runs {
+            tracker
+                .tell(StartRunCommand {
+                    run_id: run.id.clone(),
+                })
+                .await
+                .unwrap();
+        }
+        let send = |run: &RunContext, port_name: &str, value: Option<f64>| {
+            let (sum, run) = (sum.clone(), run.clone());
+            let (from_port, to_port) = (port("input", port_name), port("sum", port_name));
+            async move {
+                run.begin();
+                match value {
+                    Some(value) => sum
+                        .ask(DataInputMessage {
+                            from_port,
+                            to_port,
+                            data: Box::new(NumberLabel { value }),
+                            execution_id: "test".to_string(),
+                            run,
+                        })
+                        .await
+                        .unwrap(),
+                    None => sum
+                        .ask(SkippedInputMessage {
+                            from_port,
+                            to_port,
+                            execution_id: "test".to_string(),
+                            reason: "upstream skipped".to_string(),
+                            run,
+                        })
+                        .await
+                        .unwrap(),
+                }
+            }
+        };
+
+        // 两个端口都到达；上游被跳过的可选端口使用默认值
+        send(&runs[0], "a", Some(1.0)).await;
+        send(&runs[0], "b", Some(2.0)).await;
+        send(&runs[1], "b", None).await;
+        send(&runs[1], "a", Some(1.0)).await;
+        assert_eq!(status(&sum).await.success_count, 2);
+
+        // b 一直没有到达：等待计时结束后执行，计时期间运行不会静止
+        send(&runs[2], "a", Some(1.0)).await;
+        assert_eq!(status(&sum).await.success_count, 2);
+        in_flight.wait_idle().await;
+        assert_eq!(status(&sum).await.success_count, 3);
+
+        for (run, expected) in runs.iter().zip([103.0, 111.0, 111.0]) {
+            let result = tracker
+                .ask(FinishRunQuery {
+                    run_id: run.id.clone(),
+                })
+                .await
+                .unwrap();
+            let value = result.output("sum", "result").unwrap();
+            assert_eq!(
+                value.as_any().downcast_ref::<NumberLabel>().unwrap().value,
+                expected
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invoke_async_node() {
+        let node = NodeImpl::Async(Arc::new(SleepyNode));
+        assert!(node.is_async());
+
+        let ctx = NodeContext::new("sleepy", node.info(), NodeData::new());
+        let outputs = invoke_node(&node, ctx).await.unwrap();
+        assert!(outputs.contains_key(&port("sleepy", "value")));
     }
 }
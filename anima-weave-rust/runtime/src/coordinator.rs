@@ -0,0 +1,81 @@
+//! 执行协调器：在图范围内落实节点的执行模式
+//!
+//! `Concurrent` 节点执行时持有共享许可，`Sequential` 节点持有独占许可，
+//! 因此 Sequential 节点运行时图中没有其他节点在运行。
+//! 许可按请求顺序发放：Sequential 节点排队后，之后到达的 Concurrent 节点会排在它后面，
+//! Sequential 节点不会被持续到达的并发执行饿死。
+
+use anima_weave_core::ExecutionMode;
+use std::sync::Arc;
+use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard, RwLock};
+
+/// 同一个图的所有节点 actor 共享一个协调器
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionCoordinator {
+    lock: Arc<RwLock<()>>,
+}
+
+/// 执行许可，drop 时释放
+#[derive(Debug)]
+pub enum ExecutionPermit {
+    Shared(OwnedRwLockReadGuard<()>),
+    Exclusive(OwnedRwLockWriteGuard<()>),
+}
+
+impl ExecutionCoordinator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 立即获取许可，需要等待时返回 None
+    pub fn try_acquire(&self, mode: ExecutionMode) -> Option<ExecutionPermit> {
+        match mode {
+            ExecutionMode::Concurrent => self
+                .lock
+                .clone()
+                .try_read_owned()
+                .ok()
+                .map(ExecutionPermit::Shared),
+            ExecutionMode::Sequential => self
+                .lock
+                .clone()
+                .try_write_owned()
+                .ok()
+                .map(ExecutionPermit::Exclusive),
+        }
+    }
+
+    /// 等待并获取许可
+    pub async fn acquire(&self, mode: ExecutionMode) -> ExecutionPermit {
+        match mode {
+            ExecutionMode::Concurrent => {
+                ExecutionPermit::Shared(self.lock.clone().read_owned().await)
+            }
+            ExecutionMode::Sequential => {
+                ExecutionPermit::Exclusive(self.lock.clone().write_owned().await)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sequential_excludes_others() {
+        let coordinator = ExecutionCoordinator::new();
+
+        let shared = coordinator.try_acquire(ExecutionMode::Concurrent).unwrap();
+        assert!(coordinator.try_acquire(ExecutionMode::Concurrent).is_some());
+        assert!(coordinator.try_acquire(ExecutionMode::Sequential).is_none());
+        drop(shared);
+
+        let exclusive = coordinator.try_acquire(ExecutionMode::Sequential).unwrap();
+        assert!(coordinator.try_acquire(ExecutionMode::Concurrent).is_none());
+        assert!(coordinator.try_acquire(ExecutionMode::Sequential).is_none());
+        drop(exclusive);
+
+        assert!(coordinator.try_acquire(ExecutionMode::Concurrent).is_some());
+    }
+}
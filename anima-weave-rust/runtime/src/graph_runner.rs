@@ -1,106 +1,389 @@
-use crate::actor::{SimpleNodeActor, TriggerExecutionMessage};
-use crate::status_tracker::{SetExpectedNodesCommand, SetShutdownHookCommand, SimpleStatusTracker};
-use anima_weave_core::graph::PortRef;
-use anima_weave_core::{Graph, Node, NodeName, PortName};
-use anima_weave_node::create_node_by_type;
+use crate::actor::FinishRunMessage;
+use crate::actor::{DataInputMessage, deliver_input};
+use crate::actor::{
+    DownstreamConnections, DownstreamEdge, GetNodeStatusQuery, SimpleNodeActor,
+    TriggerExecutionMessage,
+};
+use crate::coordinator::ExecutionCoordinator;
+use crate::quiescence::InFlightCounter;
+use crate::run::{NodeRunStatus, RunCancellation, RunContext, RunResult};
+use crate::status_tracker::{
+    FinishRunQuery, GetSystemStatsQuery, SimpleStatusTracker, StartRunCommand,
+};
+use crate::streaming::StreamingConfig;
+use anima_weave_core::graph::{Connection, NodeRef, PortRef};
+use anima_weave_core::label::label_type_name;
+use anima_weave_core::{
+    ERROR_PORT, FailurePolicy, Graph, NodeImpl, NodeInfo, NodeName, PortDef, PortName,
+    SemanticLabel,
+};
+use anima_weave_node::{can_convert_label, create_node_with_config, verify_registrations};
 use anyhow::{Result, anyhow};
 use kameo::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
 
 /// 图运行器 - 根据验证通过的图创建和管理 actor
+///
+/// 多次 `run` 可以同时进行：每次运行有自己的运行 id、在途计数和取消标记，
+/// 节点按运行 id 分开缓存输入
 pub struct GraphRunner {
     actors: HashMap<NodeName, ActorRef<SimpleNodeActor>>,
     status_tracker: Option<ActorRef<SimpleStatusTracker>>,
     source_nodes: Vec<NodeName>, // 没有输入连接的节点
+    /// 图输入端口，由 `run` 的参数提供值
+    graph_inputs: Vec<PortRef>,
+    /// 所有节点共享的执行协调器，落实 Sequential 节点的独占执行
+    coordinator: ExecutionCoordinator,
+    /// 运行级别的失败策略，节点实例可以覆盖
+    failure_policy: FailurePolicy,
+    /// 流式模式配置，None 为单次运行模式
+    streaming: Option<StreamingConfig>,
 }
 
 impl GraphRunner {
-    /// 根据图创建 GraphRunner
-    pub async fn build_from_graph(
+    /// 根据图创建 GraphRunner，使用默认失败策略（跳过下游）
+    pub async fn build_from_graph(graph: Graph) -> Result<Self> {
+        Self::build_with_failure_policy(graph, FailurePolicy::default()).await
+    }
+
+    /// 根据图创建 GraphRunner，指定运行级别的失败策略
+    ///
+    /// 节点实例的 `failure_policy` 优先；没有指定、但 `error` 端口有连接的节点使用
+    /// [`FailurePolicy::RouteError`]
+    pub async fn build_with_failure_policy(
         graph: Graph,
-        shutdown_hook: Option<Box<dyn Fn() + Send + Sync + 'static>>,
+        failure_policy: FailurePolicy,
     ) -> Result<Self> {
-        // 验证图
-        graph.validate()?;
+        Self::build(graph, failure_policy, None, ExecutionCoordinator::new()).await
+    }
+
+    /// 根据图创建 GraphRunner，节点与其他运行器共享执行协调器
+    ///
+    /// 组合节点（如 map）的多个内部运行器共享一个协调器，Sequential 节点
+    /// 不会与其他运行器中的节点同时执行
+    pub async fn build_with_coordinator(
+        graph: Graph,
+        coordinator: ExecutionCoordinator,
+    ) -> Result<Self> {
+        Self::build(graph, FailurePolicy::default(), None, coordinator).await
+    }
+
+    /// 根据图创建流式 GraphRunner，用 [`Self::run_stream`] 投递输入序列
+    pub async fn build_streaming(graph: Graph, config: StreamingConfig) -> Result<Self> {
+        Self::build(
+            graph,
+            FailurePolicy::default(),
+            Some(config),
+            ExecutionCoordinator::new(),
+        )
+        .await
+    }
+
+    async fn build(
+        graph: Graph,
+        failure_policy: FailurePolicy,
+        streaming: Option<StreamingConfig>,
+        coordinator: ExecutionCoordinator,
+    ) -> Result<Self> {
+        verify_registrations()?;
+
+        // 按实例配置创建节点，并结合端口信息验证图
+        let node_impls = Self::create_node_impls(&graph)?;
+        let node_infos: HashMap<NodeName, &'static NodeInfo> = node_impls
+            .iter()
+            .map(|(name, node_impl)| (name.clone(), node_impl.info()))
+            .collect();
+        graph.validate_with_nodes(&node_infos, can_convert_label)?;
 
         // 1. 启动状态追踪器
         let tracker_ref = Actor::spawn(SimpleStatusTracker::new());
 
-        // 如果提供了关机钩子，设置它
-        if let Some(hook) = shutdown_hook {
-            tracker_ref
-                .tell(SetShutdownHookCommand { hook })
-                .await
-                .map_err(|e| anyhow!("Failed to set shutdown hook: {}", e))?;
-        }
-
         let mut runner = Self {
             actors: HashMap::new(),
             status_tracker: Some(tracker_ref.clone()),
             source_nodes: Vec::new(),
+            graph_inputs: graph.inputs.clone(),
+            coordinator,
+            failure_policy,
+            streaming,
         };
 
         // 2. 创建 actor 实例
-        runner.create_actors(&graph).await?;
+        runner.create_actors(&graph, node_impls).await?;
 
         // 3. 设置连接
-        runner.setup_connections(&graph).await?;
+        runner.setup_connections(&graph, &node_infos).await?;
 
-        // 4. 将期望节点列表发送给状态追踪器
-        let expected_nodes: Vec<NodeName> = graph.nodes.iter().map(|n| n.name.clone()).collect();
-        tracker_ref
-            .tell(SetExpectedNodesCommand {
-                nodes: expected_nodes,
-            })
+        Ok(runner)
+    }
+
+    /// 图输入端口，见 [`Graph::inputs`]
+    pub fn graph_inputs(&self) -> &[PortRef] {
+        &self.graph_inputs
+    }
+
+    /// 运行图：投递图输入、启动所有源节点，等待运行静止
+    ///
+    /// `inputs` 必须恰好覆盖 [`Self::graph_inputs`]。运行静止（没有在途消息、
+    /// 没有节点在执行）后返回汇点输出、各节点最终状态和错误；
+    /// 没有执行过的节点报告为 [`NodeRunStatus::Blocked`]，附带它还在等待的端口
+    pub async fn run(&self, inputs: HashMap<PortRef, Box<dyn SemanticLabel>>) -> Result<RunResult> {
+        self.run_with_cancellation(inputs, RunCancellation::new())
             .await
-            .map_err(|e| anyhow!("Failed to set expected nodes: {}", e))?;
+    }
 
-        Ok(runner)
+    /// 使用给定取消标记的单次运行，标记被取消后不再启动新的节点执行
+    ///
+    /// 组合节点用它让内部运行随外层运行一起取消
+    pub async fn run_with_cancellation(
+        &self,
+        inputs: HashMap<PortRef, Box<dyn SemanticLabel>>,
+        cancellation: RunCancellation,
+    ) -> Result<RunResult> {
+        self.validate_inputs(&inputs)?;
+        self.run_waves(std::iter::once(inputs), cancellation).await
     }
 
-    /// 启动所有没有输入端口的节点
-    pub async fn launch(&self) -> Result<()> {
+    /// 流式运行：在同一次运行中依次投递一串图输入，每个元素同时触发一次源节点
+    ///
+    /// 需要用 [`Self::build_streaming`] 创建。节点邮箱满时投递会等待，
+    /// 输入序列按图的处理速度送入。汇点输出的全部值见 [`RunResult::streams`]
+    pub async fn run_stream(
+        &self,
+        waves: impl IntoIterator<Item = HashMap<PortRef, Box<dyn SemanticLabel>>>,
+    ) -> Result<RunResult> {
+        if self.streaming.is_none() {
+            return Err(anyhow!(
+                "run_stream requires a runner built with GraphRunner::build_streaming"
+            ));
+        }
+        let waves: Vec<_> = waves.into_iter().collect();
+        for inputs in &waves {
+            self.validate_inputs(inputs)?;
+        }
+        self.run_waves(waves, RunCancellation::new()).await
+    }
+
+    /// 检查一组输入恰好覆盖图输入
+    fn validate_inputs(&self, inputs: &HashMap<PortRef, Box<dyn SemanticLabel>>) -> Result<()> {
+        let expected: HashSet<&PortRef> = self.graph_inputs.iter().collect();
+        if let Some(port) = inputs.keys().find(|port| !expected.contains(port)) {
+            return Err(anyhow!(
+                "{}:{} is not a graph input",
+                port.node_name,
+                port.port_name
+            ));
+        }
+        if let Some(port) = self
+            .graph_inputs
+            .iter()
+            .find(|port| !inputs.contains_key(port))
+        {
+            return Err(anyhow!(
+                "Missing graph input {}:{}",
+                port.node_name,
+                port.port_name
+            ));
+        }
+        Ok(())
+    }
+
+    /// 在一次运行中依次投递各组输入并启动源节点，等待运行静止后收集结果
+    async fn run_waves(
+        &self,
+        waves: impl IntoIterator<Item = HashMap<PortRef, Box<dyn SemanticLabel>>>,
+        cancellation: RunCancellation,
+    ) -> Result<RunResult> {
+        let tracker = self
+            .status_tracker
+            .as_ref()
+            .ok_or_else(|| anyhow!("GraphRunner has no status tracker"))?;
+        let in_flight = InFlightCounter::new();
+        let run = RunContext::tracked(Uuid::new_v4().to_string(), in_flight.clone())
+            .with_cancellation(cancellation);
+        tracker
+            .tell(StartRunCommand {
+                run_id: run.id.clone(),
+            })
+            .await
+            .map_err(|e| anyhow!("Failed to start run: {}", e))?;
+
+        let execution_id = run.id.clone();
         log::info!(
             "Launching {} source nodes: {:?}",
             self.source_nodes.len(),
             self.source_nodes
         );
+        for inputs in waves {
+            if run.is_cancelled() {
+                break;
+            }
+            for (port, data) in inputs {
+                let actor_ref = self
+                    .actors
+                    .get(&port.node_name)
+                    .ok_or_else(|| anyhow!("Input node '{}' not found", port.node_name))?;
+                let message = DataInputMessage {
+                    from_port: port.clone(),
+                    to_port: port.clone(),
+                    data,
+                    execution_id: execution_id.clone(),
+                    run: run.clone(),
+                };
+                // 流式运行等待输入节点的队列有空位，反压一直传到这里
+                run.begin();
+                deliver_input(actor_ref, message, self.streaming.is_some())
+                    .await
+                    .map_err(|e| {
+                        run.end();
+                        anyhow!(
+                            "Failed to deliver input {}:{}: {}",
+                            port.node_name,
+                            port.port_name,
+                            e
+                        )
+                    })?;
+            }
 
-        // 启动所有源节点
-        for node_name in &self.source_nodes {
-            let actor_ref = self
-                .actors
-                .get(node_name)
-                .ok_or_else(|| anyhow!("Source node '{}' not found", node_name))?;
+            for node_name in &self.source_nodes {
+                let actor_ref = self
+                    .actors
+                    .get(node_name)
+                    .ok_or_else(|| anyhow!("Source node '{}' not found", node_name))?;
+
+                let trigger_message = TriggerExecutionMessage {
+                    execution_id: execution_id.clone(),
+                    run: run.clone(),
+                };
 
-            let trigger_message = TriggerExecutionMessage {
-                execution_id: "initial".to_string(),
+                run.begin();
+                actor_ref.tell(trigger_message).await.map_err(|e| {
+                    run.end();
+                    anyhow!("Failed to start node {}: {}", node_name, e)
+                })?;
+            }
+        }
+
+        // 等待运行静止：此后不会再有节点执行，所有状态事件都已发给状态追踪器
+        in_flight.wait_idle().await;
+        let mut result = tracker
+            .ask(FinishRunQuery {
+                run_id: run.id.clone(),
+            })
+            .await
+            .map_err(|e| anyhow!("Failed to collect run result: {}", e))?;
+        result.cancelled = run.is_cancelled();
+
+        // 通知所有节点运行结束，丢弃残留输入，并收集未执行节点等待的端口
+        for (node_name, actor_ref) in &self.actors {
+            let waiting_for = actor_ref
+                .ask(FinishRunMessage {
+                    run_id: run.id.clone(),
+                })
+                .await
+                .map_err(|e| anyhow!("Failed to finish run on node {}: {}", node_name, e))?;
+            if result.nodes.contains_key(node_name) {
+                continue;
+            }
+            let status = if result.cancelled {
+                NodeRunStatus::Cancelled
+            } else {
+                NodeRunStatus::Blocked { waiting_for }
             };
+            result.nodes.insert(node_name.clone(), status);
+        }
+        Ok(result)
+    }
 
-            actor_ref
-                .tell(trigger_message)
+    /// 还留有状态的运行数：状态追踪器中尚未收集结果的运行，加上各节点缓存着输入的运行
+    ///
+    /// 所有运行都已返回后应当为 0
+    pub async fn pending_runs(&self) -> Result<usize> {
+        let mut pending = match self.status_tracker {
+            Some(ref tracker) => {
+                tracker
+                    .ask(GetSystemStatsQuery)
+                    .await
+                    .map_err(|e| anyhow!("Failed to query status tracker: {}", e))?
+                    .active_runs
+            }
+            None => 0,
+        };
+        for (node_name, actor_ref) in &self.actors {
+            let status = actor_ref
+                .ask(GetNodeStatusQuery)
                 .await
-                .map_err(|e| anyhow!("Failed to start node {}: {}", node_name, e))?;
+                .map_err(|e| anyhow!("Failed to query node {}: {}", node_name, e))?;
+            pending += status.pending_runs;
         }
+        Ok(pending)
+    }
 
-        Ok(())
+    /// 停止所有节点 actor，等待各节点的 `on_stop` 完成
+    pub async fn shutdown(&self) {
+        for actor_ref in self.actors.values() {
+            let _ = actor_ref.stop_gracefully().await;
+        }
+        for actor_ref in self.actors.values() {
+            actor_ref.wait_for_shutdown().await;
+        }
+        if let Some(ref tracker) = self.status_tracker {
+            let _ = tracker.stop_gracefully().await;
+        }
     }
 
     /// 创建所有 actor 实例（不设置连接）
-    async fn create_actors(&mut self, graph: &Graph) -> Result<()> {
+    async fn create_actors(
+        &mut self,
+        graph: &Graph,
+        mut node_impls: HashMap<NodeName, NodeImpl>,
+    ) -> Result<()> {
         for node_ref in &graph.nodes {
-            let node_impl = Self::create_node_impl(&node_ref.node_type)?;
+            let node_impl = node_impls
+                .remove(&node_ref.name)
+                .ok_or_else(|| anyhow!("Node '{}' was not created", node_ref.name))?;
 
-            // 获取该节点的输入端口（从连接中推导）
-            let connected_input_ports: Vec<PortRef> = graph
+            // 获取该节点的输入端口（从连接中推导），图输入由 run 的参数提供；
+            // 扇入端口记录各来源，图输入的来源是端口自身
+            let mut connected_input_ports: Vec<PortRef> = Vec::new();
+            let mut fan_in_sources: HashMap<PortName, Vec<PortRef>> = HashMap::new();
+            let data_sources = graph
                 .data_connections
                 .iter()
-                .filter(|conn| conn.to.node_name == node_ref.name)
-                .map(|conn| conn.to.clone())
-                .collect();
+                .map(|conn| (&conn.from, &conn.to))
+                .chain(graph.inputs.iter().map(|input| (input, input)))
+                .filter(|(_, to)| to.node_name == node_ref.name);
+            for (from, to) in data_sources {
+                if !connected_input_ports.contains(to) {
+                    connected_input_ports.push(to.clone());
+                }
+                let fan_in = node_impl
+                    .info()
+                    .input_ports
+                    .iter()
+                    .any(|port| port.name == to.port_name && port.fan_in.is_some());
+                if fan_in {
+                    fan_in_sources
+                        .entry(to.port_name.clone())
+                        .or_default()
+                        .push(from.clone());
+                }
+            }
+
+            // 控制输入端口的来源
+            let mut control_sources: HashMap<PortName, Vec<PortRef>> = HashMap::new();
+            for conn in &graph.control_connections {
+                if conn.to.node_name == node_ref.name {
+                    control_sources
+                        .entry(conn.to.port_name.clone())
+                        .or_default()
+                        .push(conn.from.clone());
+                }
+            }
 
-            // 如果节点没有输入连接，它就是源节点
-            if connected_input_ports.is_empty() {
+            // 如果节点没有任何输入连接和图输入，它就是源节点
+            if connected_input_ports.is_empty() && control_sources.is_empty() {
                 self.source_nodes.push(node_ref.name.clone());
                 log::info!("Found source node: {}", node_ref.name);
             }
@@ -110,14 +393,31 @@ impl GraphRunner {
                 node_impl,
                 connected_input_ports,
                 HashMap::new(), // 下游连接稍后通过消息设置
-            );
+            )
+            .with_control_sources(control_sources)
+            .with_fan_in_sources(fan_in_sources)
+            .with_coordinator(self.coordinator.clone())
+            .with_failure_policy(self.node_failure_policy(graph, node_ref));
+            if let Some(ref retry) = node_ref.retry {
+                actor = actor.with_retry_policy(retry.clone());
+            }
+            if let Some(timeout) = node_ref.timeout() {
+                actor = actor.with_timeout(timeout);
+            }
 
             // 如果有状态追踪器，设置它
             if let Some(ref tracker) = self.status_tracker {
                 actor = actor.with_status_tracker(tracker.clone());
             }
 
-            let actor_ref = Actor::spawn(actor);
+            // 流式模式：端口队列加有界邮箱
+            let actor_ref = match self.streaming {
+                Some(config) => Actor::spawn_with_mailbox(
+                    actor.with_queue_capacity(config.queue_capacity),
+                    mailbox::bounded(config.mailbox_capacity.max(1)),
+                ),
+                None => Actor::spawn(actor),
+            };
             self.actors.insert(node_ref.name.clone(), actor_ref);
         }
 
@@ -125,25 +425,37 @@ impl GraphRunner {
     }
 
     /// 设置 actor 之间的连接
-    async fn setup_connections(&self, graph: &Graph) -> Result<()> {
+    ///
+    /// 数据连接两端的语义标签不同时，在这里确定转换目标，投递时由上游节点转换
+    async fn setup_connections(
+        &self,
+        graph: &Graph,
+        node_infos: &HashMap<NodeName, &'static NodeInfo>,
+    ) -> Result<()> {
         // 构建下游连接映射
-        let mut downstream_map: HashMap<
-            NodeName,
-            HashMap<PortName, Vec<(ActorRef<SimpleNodeActor>, PortRef)>>,
-        > = HashMap::new();
+        let mut downstream_map: HashMap<NodeName, DownstreamConnections> = HashMap::new();
 
-        for conn in &graph.data_connections {
+        let connections = graph
+            .data_connections
+            .iter()
+            .map(|conn| (conn, Self::edge_conversion(node_infos, conn)))
+            .chain(graph.control_connections.iter().map(|conn| (conn, None)));
+        for (conn, convert_to) in connections {
             let to_actor = self
                 .actors
                 .get(&conn.to.node_name)
                 .ok_or_else(|| anyhow!("Target node '{}' not found", conn.to.node_name))?;
 
+            let mut edge = DownstreamEdge::new(to_actor.clone(), conn.to.clone());
+            if let Some(target_type) = convert_to {
+                edge = edge.with_conversion(target_type);
+            }
             downstream_map
                 .entry(conn.from.node_name.clone())
-                .or_insert_with(HashMap::new)
+                .or_default()
                 .entry(conn.from.port_name.clone())
-                .or_insert_with(Vec::new)
-                .push((to_actor.clone(), conn.to.clone()));
+                .or_default()
+                .push(edge);
         }
 
         // 为每个 actor 发送连接设置消息
@@ -159,9 +471,495 @@ impl GraphRunner {
         Ok(())
     }
 
-    /// 根据节点类型创建节点实现
-    fn create_node_impl(node_type: &str) -> Result<Box<dyn Node>> {
-        // 使用 O(1) factory 查找
-        create_node_by_type(node_type).ok_or_else(|| anyhow!("Unknown node type: {}", node_type))
+    /// 数据连接需要的标签转换：输入端口声明的语义标签与输出端口不同时返回前者
+    fn edge_conversion(
+        node_infos: &HashMap<NodeName, &'static NodeInfo>,
+        conn: &Connection,
+    ) -> Option<&'static str> {
+        let find_port = |port: &PortRef, ports: fn(&'static NodeInfo) -> &'static [PortDef]| {
+            node_infos
+                .get(&port.node_name)
+                .and_then(|info| ports(info).iter().find(|def| def.name == port.port_name))
+        };
+        let from = find_port(&conn.from, |info| &info.output_ports)
+            .or_else(|| (conn.from.port_name == ERROR_PORT).then(PortDef::error_output))?;
+        let to = find_port(&conn.to, |info| &info.input_ports)?;
+
+        let from_label = from.port_type.semantic_label();
+        let to_label = to.port_type.semantic_label();
+        (label_type_name(from_label) != label_type_name(to_label)).then_some(to_label)
+    }
+
+    /// 节点实例的失败策略
+    fn node_failure_policy(&self, graph: &Graph, node_ref: &NodeRef) -> FailurePolicy {
+        if let Some(policy) = node_ref.failure_policy {
+            return policy;
+        }
+        let error_port_connected = graph
+            .data_connections
+            .iter()
+            .any(|conn| conn.from.node_name == node_ref.name && conn.from.port_name == ERROR_PORT);
+        if error_port_connected {
+            FailurePolicy::RouteError
+        } else {
+            self.failure_policy
+        }
+    }
+
+    /// 根据节点类型和实例配置创建所有节点实现
+    fn create_node_impls(graph: &Graph) -> Result<HashMap<NodeName, NodeImpl>> {
+        graph
+            .nodes
+            .iter()
+            .map(|node_ref| {
+                create_node_with_config(&node_ref.node_type, &node_ref.config)
+                    .map(|node_impl| (node_ref.name.clone(), node_impl))
+                    .map_err(|e| anyhow!("Failed to create node {}: {}", node_ref.name, e))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anima_weave_core::{
+        ArrayLabel, ErrorLabel, ExecutionMode, FanIn, Node, NodeContext, NodeError, NodeErrorKind,
+    };
+    use anima_weave_node::NodeRegistration;
+    use anima_weave_vessels::NumberLabel;
+    use once_cell::sync::Lazy;
+
+    fn port(node_name: &str, port_name: &str) -> PortRef {
+        PortRef {
+            node_name: node_name.to_string(),
+            port_name: port_name.to_string(),
+        }
+    }
+
+    fn number(value: f64) -> Box<dyn SemanticLabel> {
+        Box::new(NumberLabel { value })
+    }
+
+    /// (a + b) + c，`sum.result` 为汇点输出
+    fn sum_graph() -> Graph {
+        Graph {
+            nodes: vec![
+                NodeRef::new("add", "AddNode"),
+                NodeRef::new("sum", "AddNode"),
+            ],
+            data_connections: vec![Connection {
+                from: port("add", "result"),
+                to: port("sum", "a"),
+            }],
+            control_connections: vec![],
+            inputs: vec![port("add", "a"), port("add", "b"), port("sum", "b")],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_collects_sink_outputs() {
+        let runner = GraphRunner::build_from_graph(sum_graph()).await.unwrap();
+
+        let inputs = HashMap::from([
+            (port("add", "a"), number(1.0)),
+            (port("add", "b"), number(2.0)),
+            (port("sum", "b"), number(4.0)),
+        ]);
+        let result = runner.run(inputs).await.unwrap();
+        runner.shutdown().await;
+
+        assert!(result.is_success());
+        assert_eq!(result.outputs.len(), 1);
+        let sum = result.output("sum", "result").unwrap();
+        assert_eq!(
+            sum.as_any().downcast_ref::<NumberLabel>().unwrap().value,
+            7.0
+        );
+        assert_eq!(result.status("add"), Some(&NodeRunStatus::Succeeded));
+        assert_eq!(result.status("sum"), Some(&NodeRunStatus::Succeeded));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_runs_are_isolated() {
+        let runner = std::sync::Arc::new(GraphRunner::build_from_graph(sum_graph()).await.unwrap());
+
+        let runs: Vec<_> = (0..8)
+            .map(|i| {
+                let runner = runner.clone();
+                let i = i as f64;
+                tokio::spawn(async move {
+                    let inputs = HashMap::from([
+                        (port("add", "a"), number(i)),
+                        (port("add", "b"), number(i * 10.0)),
+                        (port("sum", "b"), number(i * 100.0)),
+                    ]);
+                    runner.run(inputs).await
+                })
+            })
+            .collect();
+        let mut results = Vec::new();
+        for run in runs {
+            results.push(run.await.unwrap());
+        }
+        runner.shutdown().await;
+
+        for (i, result) in results.into_iter().enumerate() {
+            let result = result.unwrap();
+            assert!(result.is_success());
+            let sum = result.output("sum", "result").unwrap();
+            assert_eq!(
+                sum.as_any().downcast_ref::<NumberLabel>().unwrap().value,
+                i as f64 * 111.0
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_stream() {
+        let config = StreamingConfig::default()
+            .with_queue_capacity(16)
+            .with_mailbox_capacity(2);
+        let runner = GraphRunner::build_streaming(sum_graph(), config)
+            .await
+            .unwrap();
+
+        let waves = (0..100).map(|i| {
+            let i = i as f64;
+            HashMap::from([
+                (port("add", "a"), number(i)),
+                (port("add", "b"), number(i * 10.0)),
+                (port("sum", "b"), number(i * 100.0)),
+            ])
+        });
+        let result = runner.run_stream(waves).await.unwrap();
+        runner.shutdown().await;
+
+        assert!(result.is_success());
+        let values: Vec<f64> = result
+            .stream("sum", "result")
+            .iter()
+            .map(|label| label.as_any().downcast_ref::<NumberLabel>().unwrap().value)
+            .collect();
+        let expected: Vec<f64> = (0..100).map(|i| i as f64 * 111.0).collect();
+        assert_eq!(values, expected);
+
+        // 单次运行模式的 runner 不接受输入序列
+        let runner = GraphRunner::build_from_graph(sum_graph()).await.unwrap();
+        assert!(runner.run_stream(Vec::new()).await.is_err());
+        runner.shutdown().await;
+    }
+
+    static SLOW_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "SlowTestNode",
+        description: "测试用节点，等待片刻后原样输出",
+        input_ports: vec![PortDef::required_data::<NumberLabel>("value")],
+        output_ports: vec![PortDef::output_data::<NumberLabel>("value")],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    #[derive(Debug)]
+    struct SlowTestNode;
+
+    impl Node for SlowTestNode {
+        fn info(&self) -> &'static NodeInfo {
+            &SLOW_NODE_INFO
+        }
+
+        fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+            let value = ctx.input::<NumberLabel>("value")?;
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            ctx.output("value", value)
+        }
+    }
+
+    inventory::submit! {
+        NodeRegistration::new("SlowTestNode", || Box::new(SlowTestNode))
+    }
+
+    #[tokio::test]
+    async fn test_run_stream_waits_on_full_queues() {
+        // sum.a 经过慢节点，sum.b 直接来自输入：sum.b 的队列总是先满
+        let graph = Graph {
+            nodes: vec![
+                NodeRef::new("slow", "SlowTestNode"),
+                NodeRef::new("sum", "AddNode"),
+            ],
+            data_connections: vec![Connection {
+                from: port("slow", "value"),
+                to: port("sum", "a"),
+            }],
+            control_connections: vec![],
+            inputs: vec![port("slow", "value"), port("sum", "b")],
+        };
+        let config = StreamingConfig::default()
+            .with_queue_capacity(1)
+            .with_mailbox_capacity(1);
+        let runner = GraphRunner::build_streaming(graph, config).await.unwrap();
+
+        let waves = (0..20).map(|i| {
+            let i = i as f64;
+            HashMap::from([
+                (port("slow", "value"), number(i)),
+                (port("sum", "b"), number(i * 100.0)),
+            ])
+        });
+        let result = runner.run_stream(waves).await.unwrap();
+        runner.shutdown().await;
+
+        // 没有 token 被丢弃，每一组输入都在同一次执行中配对
+        assert!(result.is_success());
+        let values: Vec<f64> = result
+            .stream("sum", "result")
+            .iter()
+            .map(|label| label.as_any().downcast_ref::<NumberLabel>().unwrap().value)
+            .collect();
+        let expected: Vec<f64> = (0..20).map(|i| i as f64 * 101.0).collect();
+        assert_eq!(values, expected);
+    }
+
+    /// 失败的命令节点 -> sum.a，sum.b 为图输入
+    async fn run_failing_graph(
+        policy: FailurePolicy,
+        command_policy: Option<FailurePolicy>,
+    ) -> RunResult {
+        let mut command = NodeRef::new("command", "CommandNode").with_config(serde_json::json!({
+            "program": "false",
+            "fail_on_nonzero_exit": true
+        }));
+        command.failure_policy = command_policy;
+        let graph = Graph {
+            nodes: vec![command, NodeRef::new("sum", "AddNode")],
+            data_connections: vec![Connection {
+                from: port("command", "exit_code"),
+                to: port("sum", "a"),
+            }],
+            control_connections: vec![],
+            inputs: vec![port("sum", "b")],
+        };
+        let runner = GraphRunner::build_with_failure_policy(graph, policy)
+            .await
+            .unwrap();
+
+        let inputs = HashMap::from([(port("sum", "b"), number(1.0))]);
+        let result = runner.run(inputs).await.unwrap();
+        runner.shutdown().await;
+
+        assert!(!result.is_success());
+        assert_eq!(result.status("command"), Some(&NodeRunStatus::Failed));
+        assert!(result.error("command").is_some());
+        result
+    }
+
+    #[tokio::test]
+    async fn test_failure_policies() {
+        let result = run_failing_graph(FailurePolicy::SkipDownstream, None).await;
+        assert_eq!(result.status("sum"), Some(&NodeRunStatus::Skipped));
+        assert!(!result.cancelled);
+        assert!(result.outputs.is_empty());
+
+        let result = run_failing_graph(FailurePolicy::FailFast, None).await;
+        assert!(result.cancelled);
+        assert_eq!(result.status("sum"), Some(&NodeRunStatus::Cancelled));
+
+        // 节点实例覆盖运行级别策略，未连接的 error 端口作为汇点输出
+        let result =
+            run_failing_graph(FailurePolicy::FailFast, Some(FailurePolicy::RouteError)).await;
+        assert!(!result.cancelled);
+        assert_eq!(result.status("sum"), Some(&NodeRunStatus::Skipped));
+        let error = result.output("command", ERROR_PORT).unwrap();
+        let error = error.as_any().downcast_ref::<ErrorLabel>().unwrap();
+        assert_eq!(error.node_name, "command");
+        assert_eq!(error.error.kind(), NodeErrorKind::Fatal);
+    }
+
+    #[tokio::test]
+    async fn test_run_requires_graph_inputs() {
+        let runner = GraphRunner::build_from_graph(sum_graph()).await.unwrap();
+
+        let inputs = HashMap::from([(port("add", "a"), number(1.0))]);
+        let err = runner.run(inputs).await.unwrap_err();
+        assert!(err.to_string().contains("Missing graph input"), "{}", err);
+        runner.shutdown().await;
+    }
+
+    static GATHER_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "GatherTestNode",
+        description: "测试用扇入节点，按连接顺序输出收集到的数字",
+        input_ports: vec![
+            PortDef::required_data::<NumberLabel>("values").with_fan_in(FanIn::Collect),
+        ],
+        output_ports: vec![
+            PortDef::output_data::<NumberLabel>("first"),
+            PortDef::output_data::<NumberLabel>("count"),
+        ],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    #[derive(Debug)]
+    struct GatherTestNode;
+
+    impl Node for GatherTestNode {
+        fn info(&self) -> &'static NodeInfo {
+            &GATHER_NODE_INFO
+        }
+
+        fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+            let values = ctx.input::<ArrayLabel>("values")?;
+            let values = values
+                .items_as::<NumberLabel>()
+                .map_err(|e| NodeError::invalid_input("values", e.to_string()))?;
+            ctx.output("first", values[0].clone())?;
+            ctx.output(
+                "count",
+                NumberLabel {
+                    value: values.len() as f64,
+                },
+            )
+        }
+    }
+
+    inventory::submit! {
+        NodeRegistration::new("GatherTestNode", || Box::new(GatherTestNode))
+    }
+
+    #[tokio::test]
+    async fn test_fan_in_gathers_branches() {
+        let graph = Graph {
+            nodes: vec![
+                NodeRef::new("left", "AddNode"),
+                NodeRef::new("right", "AddNode"),
+                NodeRef::new("gather", "GatherTestNode"),
+            ],
+            data_connections: vec![
+                Connection {
+                    from: port("right", "result"),
+                    to: port("gather", "values"),
+                },
+                Connection {
+                    from: port("left", "result"),
+                    to: port("gather", "values"),
+                },
+            ],
+            control_connections: vec![],
+            inputs: vec![
+                port("left", "a"),
+                port("left", "b"),
+                port("right", "a"),
+                port("right", "b"),
+                port("gather", "values"),
+            ],
+        };
+        let runner = GraphRunner::build_from_graph(graph).await.unwrap();
+
+        let inputs = HashMap::from([
+            (port("left", "a"), number(1.0)),
+            (port("left", "b"), number(1.0)),
+            (port("right", "a"), number(5.0)),
+            (port("right", "b"), number(5.0)),
+            (port("gather", "values"), number(0.0)),
+        ]);
+        let result = runner.run(inputs).await.unwrap();
+        runner.shutdown().await;
+
+        // 按连接顺序收集，图输入排在数据连接之后
+        assert!(result.is_success());
+        let value = |port_name| {
+            let label = result.output("gather", port_name).unwrap();
+            label.as_any().downcast_ref::<NumberLabel>().unwrap().value
+        };
+        assert_eq!(value("first"), 10.0);
+        assert_eq!(value("count"), 3.0);
+    }
+
+    static MARK_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "MarkTestNode",
+        description: "测试用节点，控制输入激活时输出 1",
+        input_ports: vec![PortDef::control_input("trigger")],
+        output_ports: vec![PortDef::output_data::<NumberLabel>("marked")],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    #[derive(Debug)]
+    struct MarkTestNode;
+
+    impl Node for MarkTestNode {
+        fn info(&self) -> &'static NodeInfo {
+            &MARK_NODE_INFO
+        }
+
+        fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+            ctx.output("marked", NumberLabel { value: 1.0 })
+        }
+    }
+
+    inventory::submit! {
+        NodeRegistration::new("MarkTestNode", || Box::new(MarkTestNode))
+    }
+
+    fn script(script: &str, output_label: &str) -> serde_json::Value {
+        serde_json::json!({
+            "inputs": [{"name": "number", "label": "NumberLabel"}],
+            "outputs": [{"name": "result", "label": output_label}],
+            "script": script,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_if_node_skips_untaken_branch() {
+        let connect = |from: (&str, &str), to: (&str, &str)| Connection {
+            from: port(from.0, from.1),
+            to: port(to.0, to.1),
+        };
+        let graph = Graph {
+            nodes: vec![
+                NodeRef::new("is_even", "ScriptNode")
+                    .with_config(script("number % 2.0 == 0.0", "BoolLabel")),
+                NodeRef::new("branch", "IfNode")
+                    .with_config(serde_json::json!({"label": "NumberLabel"})),
+                NodeRef::new("half", "ScriptNode")
+                    .with_config(script("number / 2.0", "NumberLabel")),
+                NodeRef::new("triple", "ScriptNode")
+                    .with_config(script("number * 3.0 + 1.0", "NumberLabel")),
+                NodeRef::new("gather", "GatherTestNode"),
+                NodeRef::new("odd", "MarkTestNode"),
+            ],
+            data_connections: vec![
+                connect(("is_even", "result"), ("branch", "condition")),
+                connect(("branch", "then_value"), ("half", "number")),
+                connect(("branch", "else_value"), ("triple", "number")),
+                connect(("half", "result"), ("gather", "values")),
+                connect(("triple", "result"), ("gather", "values")),
+            ],
+            control_connections: vec![connect(("branch", "else"), ("odd", "trigger"))],
+            inputs: vec![port("is_even", "number"), port("branch", "value")],
+        };
+        let runner = GraphRunner::build_from_graph(graph).await.unwrap();
+        let run = |value: f64| {
+            runner.run(HashMap::from([
+                (port("is_even", "number"), number(value)),
+                (port("branch", "value"), number(value)),
+            ]))
+        };
+        let first = |result: &RunResult| {
+            let label = result.output("gather", "first").unwrap();
+            label.as_any().downcast_ref::<NumberLabel>().unwrap().value
+        };
+
+        // 偶数：else 分支上的节点被跳过，汇合节点只收到 then 分支的结果
+        let result = run(4.0).await.unwrap();
+        assert!(result.is_success());
+        assert_eq!(first(&result), 2.0);
+        assert_eq!(result.status("triple"), Some(&NodeRunStatus::Skipped));
+        assert_eq!(result.status("odd"), Some(&NodeRunStatus::Skipped));
+        assert!(result.output("odd", "marked").is_none());
+
+        // 奇数：then 分支被跳过，控制输入连到 else 的节点执行
+        let result = run(3.0).await.unwrap();
+        assert!(result.is_success());
+        assert_eq!(first(&result), 10.0);
+        assert_eq!(result.status("half"), Some(&NodeRunStatus::Skipped));
+        assert_eq!(result.status("odd"), Some(&NodeRunStatus::Succeeded));
+        runner.shutdown().await;
     }
 }
@@ -1,20 +1,32 @@
 pub mod actor;
+pub mod coordinator;
+pub mod map;
+pub mod quiescence;
+pub mod run;
 pub mod status_tracker;
+pub mod streaming;
 
 /// 简化版 GraphRunner —— 负责：
 /// 1. 根据 Graph 创建 SimpleNodeActor 并连线
-/// 2. 提供 launch(start_node) 运行入口
+/// 2. 提供 run(inputs) 运行入口，等待运行结束并收集结果
+///
 /// 该实现目前仅满足 CLI 演示需求，后续可逐步增强。
 pub mod graph_runner;
 
 // 重新导出主要类型
 pub use actor::{
-    DataInputMessage, ExecutionId, GetNodeStatusQuery, NodeStatus, SetDownstreamConnectionsMessage,
-    SimpleNodeActor,
+    DataInputMessage, DownstreamConnections, DownstreamEdge, ExecutionId, FinishRunMessage,
+    GetNodeStatusQuery, NodeStatus, SetDownstreamConnectionsMessage, SimpleNodeActor,
+    SkippedInputMessage, TriggerExecutionMessage,
 };
+pub use coordinator::{ExecutionCoordinator, ExecutionPermit};
 pub use graph_runner::GraphRunner;
+pub use map::{MapBody, MapConfig, MapNode};
+pub use quiescence::InFlightCounter;
+pub use run::{EdgeError, NodeRunStatus, RunCancellation, RunContext, RunId, RunResult};
+pub use streaming::StreamingConfig;
 
 pub use status_tracker::{
-    GetNodeStatsQuery, GetSystemStatsQuery, NodeExecutionStats, NodeStatusEvent, ResetStatsCommand,
-    SimpleStatusTracker, SystemStats,
+    FinishRunQuery, GetNodeStatsQuery, GetSystemStatsQuery, NodeExecutionStats, NodeStatusEvent,
+    ResetStatsCommand, SimpleStatusTracker, SinkOutputMessage, StartRunCommand, SystemStats,
 };
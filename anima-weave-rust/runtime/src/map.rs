@@ -0,0 +1,702 @@
+//! Map Node - 对数组的每个元素运行一个节点或子图
+//!
+//! 元素的处理方式来自节点实例配置（`NodeRef::config`），可以是单个节点：
+//!
+//! ```json
+//! {
+//!   "node": "EnhancePromptNode",
+//!   "config": {},
+//!   "input": "prompt",
+//!   "output": "result",
+//!   "concurrency": 4
+//! }
+//! ```
+//!
+//! 也可以是子图，`input` / `output` 为子图中的端口：
+//!
+//! ```json
+//! {
+//!   "graph": { "nodes": [...], "data_connections": [...] },
+//!   "input": { "node_name": "enhance", "port_name": "prompt" },
+//!   "output": { "node_name": "format", "port_name": "result" }
+//! }
+//! ```
+//!
+//! `items` 端口收到 [`ArrayLabel`]，每个元素作为 `input` 的值运行一次，同时运行的元素
+//! 不超过 `concurrency` 个；`output` 必须是汇点输出。各元素的输出按元素顺序收集到
+//! `results` 端口。
+//!
+//! 元素失败时按节点所在运行的失败策略处理：
+//! - `FailFast`：不再启动新的元素，节点以该元素的错误失败
+//! - `SkipDownstream`：失败的元素不计入 `results`
+//! - `RouteError`：失败的元素不计入 `results`，错误按元素顺序收集到 `errors` 端口
+//!
+//! 错误的 `details` 中带有元素下标 `index`。
+//!
+//! 每个元素的运行使用节点所在运行取消标记的子标记：外层运行被取消后不再启动新的元素，
+//! 内部运行也不再启动新的节点，节点以 `Cancelled` 错误结束；执行被放弃时（例如超时）同样如此。
+//! 元素内节点的 `FailFast` 只取消这个元素。
+
+use crate::coordinator::ExecutionCoordinator;
+use crate::graph_runner::GraphRunner;
+use crate::run::{RunCancellation, RunResult};
+use anima_weave_core::graph::NodeRef;
+use anima_weave_core::{
+    ArrayLabel, AsyncNode, ErrorLabel, ExecutionMode, FailurePolicy, Graph, NodeContext, NodeError,
+    NodeFuture, NodeImpl, NodeInfo, NodeState, PortDef, PortName, PortRef, SemanticLabel,
+};
+use anima_weave_node::NodeRegistration;
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::{OnceCell, mpsc};
+
+/// 单节点形式中节点实例的名称
+const BODY_NODE: &str = "body";
+
+/// map 节点配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct MapConfig {
+    #[serde(flatten)]
+    pub body: MapBody,
+    /// 同时运行的元素数上限
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+}
+
+fn default_concurrency() -> usize {
+    4
+}
+
+/// 对每个元素运行的内容
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum MapBody {
+    /// 单个节点，`input` / `output` 为它的端口名
+    Node {
+        node: String,
+        #[serde(default)]
+        config: Value,
+        input: PortName,
+        output: PortName,
+    },
+    /// 子图，图输入只能是 `input`
+    Graph {
+        graph: Graph,
+        input: PortRef,
+        output: PortRef,
+    },
+}
+
+impl MapBody {
+    /// 统一为子图形式：(子图, 元素输入端口, 结果输出端口)
+    fn into_graph(self) -> Result<(Graph, PortRef, PortRef)> {
+        match self {
+            MapBody::Node {
+                node,
+                config,
+                input,
+                output,
+            } => {
+                let port = |port_name| PortRef {
+                    node_name: BODY_NODE.to_string(),
+                    port_name,
+                };
+                let (input, output) = (port(input), port(output));
+                let graph = Graph {
+                    nodes: vec![NodeRef::new(BODY_NODE, node).with_config(config)],
+                    data_connections: vec![],
+                    control_connections: vec![],
+                    inputs: vec![input.clone()],
+                };
+                Ok((graph, input, output))
+            }
+            MapBody::Graph {
+                mut graph,
+                input,
+                output,
+            } => {
+                if graph.inputs.iter().any(|port| port != &input) {
+                    return Err(anyhow!(
+                        "Map graph can only have {}:{} as graph input",
+                        input.node_name,
+                        input.port_name
+                    ));
+                }
+                graph.inputs = vec![input.clone()];
+                Ok((graph, input, output))
+            }
+        }
+    }
+}
+
+static MAP_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+    name: "MapNode",
+    description: "对数组的每个元素运行一个节点或子图，按顺序收集结果",
+    input_ports: vec![PortDef::required_data::<ArrayLabel>("items")],
+    output_ports: vec![
+        PortDef::output_data::<ArrayLabel>("results"),
+        PortDef::output_data::<ArrayLabel>("errors"),
+    ],
+    mode: ExecutionMode::Concurrent,
+});
+
+/// map 节点实现
+pub struct MapNode {
+    graph: Graph,
+    input: PortRef,
+    output: PortRef,
+    concurrency: usize,
+    /// 运行元素的图运行器，每个并发槽位一个，第一次执行时创建
+    ///
+    /// 同一个节点 actor 逐个执行，共享一个运行器时单节点的 body 无法并发。
+    /// 各运行器共享一个执行协调器，body 中的 Sequential 节点不会与其他槽位同时执行。
+    /// 不使用外层运行的协调器：map 节点执行时持有它的共享许可，内部的 Sequential 节点
+    /// 会一直等待
+    runners: OnceCell<Vec<Arc<GraphRunner>>>,
+}
+
+impl std::fmt::Debug for MapNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MapNode")
+            .field("input", &self.input)
+            .field("output", &self.output)
+            .field("concurrency", &self.concurrency)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MapNode {
+    pub fn new(config: MapConfig) -> Result<Self> {
+        let (graph, input, output) = config.body.into_graph()?;
+        Ok(Self {
+            graph,
+            input,
+            output,
+            concurrency: config.concurrency.max(1),
+            runners: OnceCell::new(),
+        })
+    }
+
+    /// NodeConstructor::Configured 入口
+    fn from_config(config: &Value) -> Result<NodeImpl> {
+        let config: MapConfig = serde_json::from_value(config.clone())
+            .map_err(|e| anyhow!("Invalid MapNode config: {}", e))?;
+        Ok(NodeImpl::Async(Arc::new(Self::new(config)?)))
+    }
+
+    async fn runners(&self) -> Result<&[Arc<GraphRunner>], NodeError> {
+        self.runners
+            .get_or_try_init(|| async {
+                let coordinator = ExecutionCoordinator::new();
+                let mut runners = Vec::with_capacity(self.concurrency);
+                for _ in 0..self.concurrency {
+                    let runner = GraphRunner::build_with_coordinator(
+                        self.graph.clone(),
+                        coordinator.clone(),
+                    )
+                    .await?;
+                    runners.push(Arc::new(runner));
+                }
+                Ok::<_, anyhow::Error>(runners)
+            })
+            .await
+            .map(Vec::as_slice)
+            .map_err(|e| NodeError::fatal(format!("Invalid map body: {}", e)))
+    }
+
+    /// 按并发上限运行所有元素，结果按元素顺序排列
+    ///
+    /// `fail_fast` 时某个元素失败后不再启动新的元素，结果可能比元素少。
+    /// 外层运行被取消、或者本次执行被放弃（例如超时）时，不再启动新的元素，
+    /// 还在运行的元素不再启动新的节点，运行结束后照常清理
+    async fn run_items(
+        &self,
+        runners: &[Arc<GraphRunner>],
+        items: Vec<Box<dyn SemanticLabel>>,
+        fail_fast: bool,
+        cancellation: &RunCancellation,
+    ) -> Vec<Result<Box<dyn SemanticLabel>, NodeError>> {
+        // 空闲的运行器，取出一个才能启动元素，元素结束后放回
+        let (release, mut idle) = mpsc::channel(runners.len());
+        for runner in runners {
+            let _ = release.try_send(runner.clone());
+        }
+        let failed = Arc::new(AtomicBool::new(false));
+        // 本次执行的标记：外层运行取消或者执行被放弃时取消，元素的运行随之结束，
+        // 元素任务照常走完运行并清理运行状态，不在运行中途中止
+        let execution = cancellation.child();
+        let _cancel_on_drop = CancelOnDrop(execution.clone());
+        let mut tasks = Vec::new();
+        for (index, item) in items.into_iter().enumerate() {
+            let runner = tokio::select! {
+                runner = idle.recv() => runner,
+                _ = execution.cancelled() => None,
+            };
+            let Some(runner) = runner else {
+                break;
+            };
+            if fail_fast && failed.load(Ordering::SeqCst) {
+                break;
+            }
+            let (release, failed) = (release.clone(), failed.clone());
+            let (input, output) = (self.input.clone(), self.output.clone());
+            // 每个元素一个子标记：外层取消时随之取消，元素内 FailFast 的节点只取消这个元素
+            let cancellation = execution.child();
+            tasks.push(tokio::spawn(async move {
+                let result = run_item(&runner, input, item, &output, cancellation)
+                    .await
+                    .map_err(|error| error.with_details(json!({ "index": index })));
+                if result.is_err() {
+                    failed.store(true, Ordering::SeqCst);
+                }
+                let _ = release.send(runner).await;
+                result
+            }));
+        }
+
+        let mut results = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            results.push(
+                task.await.unwrap_or_else(|e| {
+                    Err(NodeError::fatal(format!("Map element panicked: {}", e)))
+                }),
+            );
+        }
+        results
+    }
+}
+
+/// 丢弃时取消标记，执行被放弃（例如超时）时让元素的运行结束
+struct CancelOnDrop(RunCancellation);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// 运行一个元素，取出结果端口的值
+async fn run_item(
+    runner: &GraphRunner,
+    input: PortRef,
+    item: Box<dyn SemanticLabel>,
+    output: &PortRef,
+    cancellation: RunCancellation,
+) -> Result<Box<dyn SemanticLabel>, NodeError> {
+    let result = runner
+        .run_with_cancellation(HashMap::from([(input, item)]), cancellation)
+        .await?;
+    match result.output(&output.node_name, &output.port_name) {
+        Some(label) if result.is_success() => Ok(label.clone_box()),
+        _ => Err(item_error(&result)),
+    }
+}
+
+/// 元素运行失败的原因：按节点名取第一个节点错误，其次是连接错误
+fn item_error(result: &RunResult) -> NodeError {
+    let node_error = result
+        .errors
+        .iter()
+        .min_by_key(|(node_name, _)| *node_name)
+        .map(|(_, error)| error.clone());
+    node_error.unwrap_or_else(|| match result.edge_errors.first() {
+        Some(edge_error) => NodeError::fatal(edge_error.to_string()),
+        None => NodeError::fatal("Map body produced no output"),
+    })
+}
+
+impl AsyncNode for MapNode {
+    fn info(&self) -> &'static NodeInfo {
+        &MAP_NODE_INFO
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a mut NodeContext) -> NodeFuture<'a> {
+        Box::pin(async move {
+            let items = ctx.input::<ArrayLabel>("items")?;
+            let runners = self.runners().await?;
+            let policy = ctx.failure_policy();
+            let cancellation = ctx.cancellation().clone();
+            let fail_fast = policy == FailurePolicy::FailFast;
+            let results = self
+                .run_items(runners, items.items, fail_fast, &cancellation)
+                .await;
+            if cancellation.is_cancelled() {
+                return Err(NodeError::cancelled("run cancelled"));
+            }
+
+            let mut outputs = Vec::new();
+            let mut errors: Vec<Box<dyn SemanticLabel>> = Vec::new();
+            for (index, result) in results.into_iter().enumerate() {
+                match (result, policy) {
+                    (Ok(label), _) => outputs.push(label),
+                    (Err(error), FailurePolicy::FailFast) => return Err(error),
+                    (Err(_), FailurePolicy::SkipDownstream) => {}
+                    (Err(error), FailurePolicy::RouteError) => {
+                        let node_name = format!("{}[{}]", ctx.node_name(), index);
+                        errors.push(Box::new(ErrorLabel::new(node_name, error)));
+                    }
+                }
+            }
+
+            ctx.output("results", ArrayLabel::new(outputs))?;
+            if !errors.is_empty() {
+                ctx.output("errors", ArrayLabel::new(errors))?;
+            }
+            Ok(())
+        })
+    }
+
+    fn on_stop<'a>(&'a self, _state: &'a NodeState) -> NodeFuture<'a> {
+        Box::pin(async {
+            for runner in self.runners.get().into_iter().flatten() {
+                runner.shutdown().await;
+            }
+            Ok(())
+        })
+    }
+}
+
+inventory::submit! {
+    NodeRegistration::configured("MapNode", MapNode::from_config)
+        .with_version(env!("CARGO_PKG_VERSION"))
+        .with_category("control")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run::NodeRunStatus;
+    use anima_weave_core::NodeErrorKind;
+    use anima_weave_core::graph::Connection;
+    use anima_weave_vessels::NumberLabel;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Duration;
+
+    static PROBE_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "MapProbeNode",
+        description: "测试用节点，输出两倍的输入并记录同时执行的数量",
+        input_ports: vec![PortDef::required_data::<NumberLabel>("value")],
+        output_ports: vec![PortDef::output_data::<NumberLabel>("doubled")],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    static RUNNING: AtomicUsize = AtomicUsize::new(0);
+    static MAX_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug)]
+    struct MapProbeNode;
+
+    impl AsyncNode for MapProbeNode {
+        fn info(&self) -> &'static NodeInfo {
+            &PROBE_NODE_INFO
+        }
+
+        fn execute<'a>(&'a self, ctx: &'a mut NodeContext) -> NodeFuture<'a> {
+            Box::pin(async move {
+                let value = ctx.input::<NumberLabel>("value")?.value;
+                let running = RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+                MAX_RUNNING.fetch_max(running, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                RUNNING.fetch_sub(1, Ordering::SeqCst);
+                ctx.output("doubled", NumberLabel { value: value * 2.0 })
+            })
+        }
+    }
+
+    inventory::submit! {
+        NodeRegistration::new_async("MapProbeNode", || Box::new(MapProbeNode))
+    }
+
+    static EXCLUSIVE_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "MapExclusiveNode",
+        description: "测试用 Sequential 节点，记录同时执行的数量",
+        input_ports: vec![PortDef::required_data::<NumberLabel>("value")],
+        output_ports: vec![PortDef::output_data::<NumberLabel>("value")],
+        mode: ExecutionMode::Sequential,
+    });
+
+    static EXCLUSIVE_RUNNING: AtomicUsize = AtomicUsize::new(0);
+    static EXCLUSIVE_MAX_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug)]
+    struct MapExclusiveNode;
+
+    impl AsyncNode for MapExclusiveNode {
+        fn info(&self) -> &'static NodeInfo {
+            &EXCLUSIVE_NODE_INFO
+        }
+
+        fn execute<'a>(&'a self, ctx: &'a mut NodeContext) -> NodeFuture<'a> {
+            Box::pin(async move {
+                let running = EXCLUSIVE_RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+                EXCLUSIVE_MAX_RUNNING.fetch_max(running, Ordering::SeqCst);
+                let value = ctx.input::<NumberLabel>("value")?;
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                EXCLUSIVE_RUNNING.fetch_sub(1, Ordering::SeqCst);
+                ctx.output("value", value)
+            })
+        }
+    }
+
+    inventory::submit! {
+        NodeRegistration::new_async("MapExclusiveNode", || Box::new(MapExclusiveNode))
+    }
+
+    static SLEEP_NODE_INFO: Lazy<NodeInfo> = Lazy::new(|| NodeInfo {
+        name: "MapSleepNode",
+        description: "测试用节点，记录开始执行的次数，等待片刻后原样输出",
+        input_ports: vec![PortDef::required_data::<NumberLabel>("value")],
+        output_ports: vec![PortDef::output_data::<NumberLabel>("value")],
+        mode: ExecutionMode::Concurrent,
+    });
+
+    static SLEEP_STARTED: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug)]
+    struct MapSleepNode;
+
+    impl AsyncNode for MapSleepNode {
+        fn info(&self) -> &'static NodeInfo {
+            &SLEEP_NODE_INFO
+        }
+
+        fn execute<'a>(&'a self, ctx: &'a mut NodeContext) -> NodeFuture<'a> {
+            Box::pin(async move {
+                SLEEP_STARTED.fetch_add(1, Ordering::SeqCst);
+                let value = ctx.input::<NumberLabel>("value")?;
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                ctx.output("value", value)
+            })
+        }
+    }
+
+    inventory::submit! {
+        NodeRegistration::new_async("MapSleepNode", || Box::new(MapSleepNode))
+    }
+
+    fn port(node_name: &str, port_name: &str) -> PortRef {
+        PortRef {
+            node_name: node_name.to_string(),
+            port_name: port_name.to_string(),
+        }
+    }
+
+    fn numbers(values: &[f64]) -> Box<dyn SemanticLabel> {
+        let items = values
+            .iter()
+            .map(|value| Box::new(NumberLabel { value: *value }) as Box<dyn SemanticLabel>)
+            .collect();
+        Box::new(ArrayLabel::new(items))
+    }
+
+    fn values(label: &dyn SemanticLabel) -> Vec<f64> {
+        let array = label.as_any().downcast_ref::<ArrayLabel>().unwrap();
+        array
+            .items_as::<NumberLabel>()
+            .unwrap()
+            .iter()
+            .map(|number| number.value)
+            .collect()
+    }
+
+    async fn run_map(map: NodeRef, items: &[f64]) -> RunResult {
+        let graph = Graph {
+            nodes: vec![map],
+            data_connections: vec![],
+            control_connections: vec![],
+            inputs: vec![port("map", "items")],
+        };
+        let runner = GraphRunner::build_from_graph(graph).await.unwrap();
+        let result = runner
+            .run(HashMap::from([(port("map", "items"), numbers(items))]))
+            .await
+            .unwrap();
+        runner.shutdown().await;
+        result
+    }
+
+    #[tokio::test]
+    async fn test_map_runs_items_in_order() {
+        let map = NodeRef::new("map", "MapNode").with_config(json!({
+            "node": "MapProbeNode",
+            "input": "value",
+            "output": "doubled",
+            "concurrency": 2
+        }));
+        let result = run_map(map, &[1.0, 2.0, 4.0, 5.0, 6.0, 7.0]).await;
+
+        assert!(result.is_success());
+        let doubled = values(result.output("map", "results").unwrap());
+        assert_eq!(doubled, vec![2.0, 4.0, 8.0, 10.0, 12.0, 14.0]);
+        assert_eq!(MAX_RUNNING.load(Ordering::SeqCst), 2);
+
+        // 子图形式：两个节点串联
+        let body = Graph {
+            nodes: vec![
+                NodeRef::new("first", "MapProbeNode"),
+                NodeRef::new("second", "MapProbeNode"),
+            ],
+            data_connections: vec![Connection {
+                from: port("first", "doubled"),
+                to: port("second", "value"),
+            }],
+            control_connections: vec![],
+            inputs: vec![],
+        };
+        let map = NodeRef::new("map", "MapNode").with_config(json!({
+            "graph": body,
+            "input": port("first", "value"),
+            "output": port("second", "doubled"),
+        }));
+        let result = run_map(map, &[1.0, 2.0]).await;
+        assert_eq!(
+            values(result.output("map", "results").unwrap()),
+            vec![4.0, 8.0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_map_failure_policies() {
+        let map = || {
+            NodeRef::new("map", "MapNode").with_config(json!({
+                "node": "ScriptNode",
+                "config": {
+                    "inputs": [{"name": "number", "label": "NumberLabel"}],
+                    "outputs": [{"name": "result", "label": "NumberLabel"}],
+                    "script": "if number == 3.0 { throw \"three is not allowed\" } number * 2.0"
+                },
+                "input": "number",
+                "output": "result"
+            }))
+        };
+        let items = [1.0, 3.0, 5.0];
+
+        // SkipDownstream：失败的元素不计入结果
+        let result = run_map(map(), &items).await;
+        assert_eq!(result.status("map"), Some(&NodeRunStatus::Succeeded));
+        assert_eq!(
+            values(result.output("map", "results").unwrap()),
+            vec![2.0, 10.0]
+        );
+        assert!(result.output("map", "errors").is_none());
+
+        // RouteError：错误按元素顺序收集
+        let result = run_map(map().with_failure_policy(FailurePolicy::RouteError), &items).await;
+        assert_eq!(
+            values(result.output("map", "results").unwrap()),
+            vec![2.0, 10.0]
+        );
+        let errors = result.output("map", "errors").unwrap();
+        let errors = errors.as_any().downcast_ref::<ArrayLabel>().unwrap();
+        let errors = errors.items_as::<ErrorLabel>().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].node_name, "map[1]");
+        assert_eq!(errors[0].error.details(), Some(&json!({"index": 1})));
+
+        // FailFast：map 节点失败，运行被取消
+        let result = run_map(map().with_failure_policy(FailurePolicy::FailFast), &items).await;
+        assert!(result.cancelled);
+        assert_eq!(result.status("map"), Some(&NodeRunStatus::Failed));
+        let error = result.error("map").unwrap();
+        assert!(error.message().contains("three is not allowed"));
+        assert_eq!(error.details(), Some(&json!({"index": 1})));
+    }
+
+    #[tokio::test]
+    async fn test_sequential_body_does_not_overlap_across_slots() {
+        let map = NodeRef::new("map", "MapNode").with_config(json!({
+            "node": "MapExclusiveNode",
+            "input": "value",
+            "output": "value",
+            "concurrency": 4
+        }));
+        let result = run_map(map, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).await;
+
+        assert!(result.is_success());
+        assert_eq!(
+            values(result.output("map", "results").unwrap()),
+            vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+        );
+        assert_eq!(EXCLUSIVE_MAX_RUNNING.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fail_fast_body_only_cancels_its_element() {
+        let mut body = NodeRef::new("body", "ScriptNode").with_config(json!({
+            "inputs": [{"name": "number", "label": "NumberLabel"}],
+            "outputs": [{"name": "result", "label": "NumberLabel"}],
+            "script": "if number == 3.0 { throw \"three is not allowed\" } number * 2.0"
+        }));
+        body.failure_policy = Some(FailurePolicy::FailFast);
+        let map = || {
+            let body = Graph {
+                nodes: vec![body.clone()],
+                data_connections: vec![],
+                control_connections: vec![],
+                inputs: vec![],
+            };
+            NodeRef::new("map", "MapNode").with_config(json!({
+                "graph": body,
+                "input": port("body", "number"),
+                "output": port("body", "result"),
+            }))
+        };
+        let items = [1.0, 3.0, 5.0];
+
+        // 元素内的 FailFast 不取消其他元素和外层运行，按外层策略处理失败的元素
+        let result = run_map(map(), &items).await;
+        assert!(!result.cancelled);
+        assert_eq!(result.status("map"), Some(&NodeRunStatus::Succeeded));
+        assert_eq!(
+            values(result.output("map", "results").unwrap()),
+            vec![2.0, 10.0]
+        );
+
+        let result = run_map(map().with_failure_policy(FailurePolicy::RouteError), &items).await;
+        assert!(!result.cancelled);
+        let errors = result.output("map", "errors").unwrap();
+        let errors = errors.as_any().downcast_ref::<ArrayLabel>().unwrap();
+        let errors = errors.items_as::<ErrorLabel>().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].error.message().contains("three is not allowed"));
+    }
+
+    #[tokio::test]
+    async fn test_map_stops_when_run_is_cancelled() {
+        let config = json!({
+            "node": "MapSleepNode",
+            "input": "value",
+            "output": "value",
+            "concurrency": 2
+        });
+        let map = MapNode::new(serde_json::from_value(config).unwrap()).unwrap();
+        let inputs = HashMap::from([(port("map", "items"), numbers(&[0.0; 20]))]);
+        let cancellation = RunCancellation::new();
+        let mut ctx =
+            NodeContext::new("map", map.info(), inputs).with_cancellation(cancellation.clone());
+
+        let cancel = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            cancellation.cancel();
+        });
+        let error = tokio::time::timeout(Duration::from_secs(1), map.execute(&mut ctx))
+            .await
+            .unwrap()
+            .unwrap_err();
+        cancel.await.unwrap();
+        assert_eq!(error.kind(), NodeErrorKind::Cancelled);
+
+        // 取消时在运行的两个元素之外没有再启动元素，元素的运行都已结束并清理
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(SLEEP_STARTED.load(Ordering::SeqCst), 2);
+        for runner in map.runners.get().unwrap() {
+            assert_eq!(runner.pending_runs().await.unwrap(), 0);
+        }
+        map.on_stop(&NodeState::new()).await.unwrap();
+    }
+}
@@ -0,0 +1,89 @@
+//! 运行静止检测
+//!
+//! 每次运行有自己的 [`InFlightCounter`]，随 [`RunContext`](crate::RunContext) 传递：
+//! 发送该运行的 `DataInputMessage`、`SkippedInputMessage` 或 `TriggerExecutionMessage`
+//! 之前计数加一，接收方处理完（包括执行节点和向下游发送输出）之后减一。下游消息总是在上游消息处理结束前计入，所以计数归零时
+//! 没有在途消息、没有节点在执行，也不会再有节点变为就绪——运行已静止。
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::Notify;
+
+/// 在途工作计数
+#[derive(Debug, Clone, Default)]
+pub struct InFlightCounter {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    count: AtomicUsize,
+    idle: Notify,
+}
+
+impl InFlightCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 一条消息即将发出
+    pub fn begin(&self) {
+        self.inner.count.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// 一条消息处理完毕，或者没能发出
+    pub fn end(&self) {
+        let previous = self.inner.count.fetch_sub(1, Ordering::SeqCst);
+        debug_assert!(previous > 0, "InFlightCounter::end without begin");
+        if previous == 1 {
+            self.inner.idle.notify_waiters();
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.inner.count.load(Ordering::SeqCst)
+    }
+
+    /// 等待计数归零
+    pub async fn wait_idle(&self) {
+        loop {
+            let idle = self.inner.idle.notified();
+            tokio::pin!(idle);
+            // 先注册等待再检查计数，避免错过检查和等待之间的通知
+            idle.as_mut().enable();
+            if self.count() == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_wait_idle() {
+        let counter = InFlightCounter::new();
+        counter.wait_idle().await;
+
+        counter.begin();
+        counter.begin();
+        let waiter = tokio::spawn({
+            let counter = counter.clone();
+            async move { counter.wait_idle().await }
+        });
+
+        counter.end();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+
+        counter.end();
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("counter became idle")
+            .unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+//! 一次图运行的结果
+
+use crate::quiescence::InFlightCounter;
+pub use anima_weave_core::RunCancellation;
+use anima_weave_core::{NodeError, NodeName, PortName, PortRef, SemanticLabel};
+use kameo::Reply;
+use std::collections::HashMap;
+
+/// 节点在一次运行中的最终状态
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NodeRunStatus {
+    Succeeded,
+    Failed,
+    /// 节点被跳过：控制输入未激活，或者上游失败、被跳过、没有产生输出
+    Skipped,
+    /// 运行静止时仍未执行：等待中的数据端口，以及尚无信号的控制端口
+    Blocked {
+        waiting_for: Vec<PortName>,
+    },
+    /// 运行被取消时尚未执行
+    Cancelled,
+}
+
+/// [`GraphRunner::run`](crate::GraphRunner::run) 的结果
+#[derive(Debug, Default, Reply)]
+pub struct RunResult {
+    /// 汇点输出：没有下游连接的数据输出端口上最后一次产生的值
+    pub outputs: HashMap<PortRef, Box<dyn SemanticLabel>>,
+    /// 汇点输出端口上产生的全部值，按产生顺序；流式运行时每个输入序列元素对应一个
+    pub streams: HashMap<PortRef, Vec<Box<dyn SemanticLabel>>>,
+    /// 每个节点的最终状态
+    pub nodes: HashMap<NodeName, NodeRunStatus>,
+    /// 失败节点最近一次的错误
+    pub errors: HashMap<NodeName, NodeError>,
+    /// 连接上的标签转换失败
+    pub edge_errors: Vec<EdgeError>,
+    /// 运行因 `FailFast` 策略被取消
+    pub cancelled: bool,
+}
+
+impl RunResult {
+    /// 没有节点失败，没有连接出错，也没有节点阻塞，运行没有被取消
+    pub fn is_success(&self) -> bool {
+        self.errors.is_empty()
+            && self.edge_errors.is_empty()
+            && !self.cancelled
+            && !self
+                .nodes
+                .values()
+                .any(|status| matches!(status, NodeRunStatus::Blocked { .. }))
+    }
+
+    pub fn output(&self, node_name: &str, port_name: &str) -> Option<&dyn SemanticLabel> {
+        self.outputs
+            .get(&PortRef {
+                node_name: node_name.to_string(),
+                port_name: port_name.to_string(),
+            })
+            .map(|label| label.as_ref())
+    }
+
+    /// 汇点输出端口上产生的全部值
+    pub fn stream(&self, node_name: &str, port_name: &str) -> &[Box<dyn SemanticLabel>] {
+        self.streams
+            .get(&PortRef {
+                node_name: node_name.to_string(),
+                port_name: port_name.to_string(),
+            })
+            .map_or(&[], Vec::as_slice)
+    }
+
+    pub fn status(&self, node_name: &str) -> Option<&NodeRunStatus> {
+        self.nodes.get(node_name)
+    }
+
+    pub fn error(&self, node_name: &str) -> Option<&NodeError> {
+        self.errors.get(node_name)
+    }
+}
+
+/// 连接错误：输出无法转换为下游输入端口声明的语义标签
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeError {
+    pub from_port: PortRef,
+    pub to_port: PortRef,
+    pub from_label: &'static str,
+    pub to_label: &'static str,
+    pub message: String,
+}
+
+impl std::fmt::Display for EdgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{} ({}) -> {}:{} ({}): {}",
+            self.from_port.node_name,
+            self.from_port.port_name,
+            self.from_label,
+            self.to_port.node_name,
+            self.to_port.port_name,
+            self.to_label,
+            self.message
+        )
+    }
+}
+
+/// 运行标识，区分同时通过同一个图的多次运行
+pub type RunId = String;
+
+/// 一次运行的上下文，随输入消息在节点之间传递
+///
+/// 节点按 `id` 分开缓存各次运行的输入；在途计数和取消标记只属于这次运行，
+/// 同一个图上的其他运行不受影响
+#[derive(Debug, Clone)]
+pub struct RunContext {
+    pub id: RunId,
+    in_flight: Option<InFlightCounter>,
+    cancellation: RunCancellation,
+}
+
+impl RunContext {
+    /// 不跟踪在途消息的运行，用于直接驱动单个 actor
+    pub fn new(id: impl Into<RunId>) -> Self {
+        Self {
+            id: id.into(),
+            in_flight: None,
+            cancellation: RunCancellation::new(),
+        }
+    }
+
+    /// 跟踪在途消息的运行：发给节点的每条输入消息都必须先调用 [`Self::begin`]
+    pub fn tracked(id: impl Into<RunId>, in_flight: InFlightCounter) -> Self {
+        Self {
+            in_flight: Some(in_flight),
+            ..Self::new(id)
+        }
+    }
+
+    /// 使用给定的取消标记，例如组合节点让内部运行随外层运行一起取消
+    pub fn with_cancellation(mut self, cancellation: RunCancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// 一条输入消息即将发出
+    pub fn begin(&self) {
+        if let Some(ref in_flight) = self.in_flight {
+            in_flight.begin();
+        }
+    }
+
+    /// 一条输入消息处理完毕，或者没能发出
+    pub fn end(&self) {
+        if let Some(ref in_flight) = self.in_flight {
+            in_flight.end();
+        }
+    }
+
+    pub fn cancellation(&self) -> &RunCancellation {
+        &self.cancellation
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+}
@@ -3,38 +3,57 @@ use kameo::error::ActorStopReason;
 use kameo::message::{Context, Message};
 use kameo::{Actor, Reply};
 use std::collections::HashMap;
-use std::collections::HashSet;
 use std::time::{Duration, SystemTime};
 
 use super::ExecutionId;
-use anima_weave_core::NodeName;
+use crate::run::{EdgeError, NodeRunStatus, RunId, RunResult};
+use anima_weave_core::{
+    ExecutionMode, NodeError, NodeErrorKind, NodeName, PortName, PortRef, SemanticLabel,
+};
 
 /// 简化版状态追踪器 - 收集节点执行统计
 pub struct SimpleStatusTracker {
     /// 节点执行统计
     node_stats: HashMap<NodeName, NodeExecutionStats>,
 
-    /// 剩余未完成节点集合
-    remaining_nodes: HashSet<NodeName>,
+    /// 进行中的运行的结果，由 StartRunCommand 开始、FinishRunQuery 取走
+    runs: HashMap<RunId, RunResult>,
 
     /// 系统级统计
     total_executions: u64,
     total_successes: u64,
     total_failures: u64,
     start_time: SystemTime,
-    shutdown_hook: Option<Box<dyn Fn() + Send + Sync + 'static>>,
 }
 
 /// 节点执行统计
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct NodeExecutionStats {
     pub total_executions: u64,
     pub successful_executions: u64,
     pub failed_executions: u64,
+    /// 因控制输入未激活而跳过的次数
+    pub skipped_executions: u64,
+    /// 执行前等待执行许可的次数
+    pub exclusivity_waits: u64,
+    /// 重试次数，不计入 total_executions
+    pub retries: u64,
+    /// 超时的尝试次数
+    pub timeouts: u64,
+    /// 输入队列已满、发送方等待的次数
+    pub backpressure_waits: u64,
+    /// 输出在连接上转换失败的次数
+    pub conversion_failures: u64,
+    /// 最近一次连接错误
+    pub last_edge_error: Option<EdgeError>,
     pub last_execution_time: Option<SystemTime>,
     pub total_execution_duration: Duration,
     pub min_execution_duration: Duration,
     pub max_execution_duration: Duration,
+    /// 按错误类别统计的失败次数
+    pub failures_by_kind: HashMap<NodeErrorKind, u64>,
+    /// 最近一次失败
+    pub last_error: Option<NodeError>,
 }
 
 impl NodeExecutionStats {
@@ -43,10 +62,19 @@ impl NodeExecutionStats {
             total_executions: 0,
             successful_executions: 0,
             failed_executions: 0,
+            skipped_executions: 0,
+            exclusivity_waits: 0,
+            retries: 0,
+            timeouts: 0,
+            backpressure_waits: 0,
+            conversion_failures: 0,
+            last_edge_error: None,
             last_execution_time: None,
             total_execution_duration: Duration::from_secs(0),
             min_execution_duration: Duration::from_secs(u64::MAX),
             max_execution_duration: Duration::from_secs(0),
+            failures_by_kind: HashMap::new(),
+            last_error: None,
         }
     }
 
@@ -67,41 +95,45 @@ impl NodeExecutionStats {
     }
 }
 
+impl Default for NodeExecutionStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SimpleStatusTracker {
     pub fn new() -> Self {
         Self {
             node_stats: HashMap::new(),
-            remaining_nodes: HashSet::new(),
+            runs: HashMap::new(),
             total_executions: 0,
             total_successes: 0,
             total_failures: 0,
             start_time: SystemTime::now(),
-            shutdown_hook: None,
         }
     }
 
-    /// 设置期望执行的节点列表
-    fn set_expected_nodes(&mut self, nodes: Vec<NodeName>) {
-        self.remaining_nodes = nodes.into_iter().collect();
+    /// 记录节点在本次运行中的最终状态
+    fn consume_node(&mut self, run_id: &RunId, node_name: &NodeName, status: NodeRunStatus) {
+        if let Some(run) = self.runs.get_mut(run_id) {
+            run.nodes.insert(node_name.clone(), status);
+        }
     }
 
-    /// 当节点首次成功或失败时，从 remaining_nodes 移除
-    fn consume_node(&mut self, node_name: &NodeName) {
-        self.remaining_nodes.remove(node_name);
-        if self.remaining_nodes.is_empty() {
-            log::info!("All nodes have finished at least once. Triggering shutdown hook.");
-            if let Some(hook) = &self.shutdown_hook {
-                (hook)();
-            }
+    /// 记录汇点输出
+    fn record_sink_output(&mut self, run_id: &RunId, port: PortRef, data: Box<dyn SemanticLabel>) {
+        if let Some(run) = self.runs.get_mut(run_id) {
+            run.streams
+                .entry(port.clone())
+                .or_default()
+                .push(data.clone_box());
+            run.outputs.insert(port, data);
         }
     }
 
     /// 记录节点执行开始
     fn record_execution_start(&mut self, node_name: NodeName, execution_id: ExecutionId) {
-        let stats = self
-            .node_stats
-            .entry(node_name.clone())
-            .or_insert_with(NodeExecutionStats::new);
+        let stats = self.node_stats.entry(node_name.clone()).or_default();
         stats.total_executions += 1;
         stats.last_execution_time = Some(SystemTime::now());
 
@@ -113,11 +145,12 @@ impl SimpleStatusTracker {
     /// 记录节点执行成功
     fn record_execution_success(
         &mut self,
+        run_id: &RunId,
         node_name: NodeName,
         execution_id: ExecutionId,
         duration: Duration,
     ) {
-        self.consume_node(&node_name);
+        self.consume_node(run_id, &node_name, NodeRunStatus::Succeeded);
         if let Some(stats) = self.node_stats.get_mut(&node_name) {
             stats.successful_executions += 1;
             stats.total_execution_duration += duration;
@@ -143,26 +176,156 @@ impl SimpleStatusTracker {
     /// 记录节点执行失败
     fn record_execution_failure(
         &mut self,
+        run_id: &RunId,
         node_name: NodeName,
         execution_id: ExecutionId,
-        error: String,
+        error: NodeError,
         duration: Duration,
     ) {
-        self.consume_node(&node_name);
+        if let Some(run) = self.runs.get_mut(run_id) {
+            run.errors.insert(node_name.clone(), error.clone());
+        }
+        self.consume_node(run_id, &node_name, NodeRunStatus::Failed);
+        log::error!(
+            "Node {} failed execution {} in {:?} ({}): {}",
+            node_name,
+            execution_id,
+            duration,
+            error.kind(),
+            error
+        );
+
         if let Some(stats) = self.node_stats.get_mut(&node_name) {
             stats.failed_executions += 1;
             stats.total_execution_duration += duration;
+            *stats.failures_by_kind.entry(error.kind()).or_default() += 1;
+            stats.last_error = Some(error);
         }
 
         self.total_failures += 1;
+    }
 
-        log::error!(
-            "Node {} failed execution {} in {:?}: {}",
+    /// 记录节点被跳过
+    fn record_execution_skipped(
+        &mut self,
+        run_id: &RunId,
+        node_name: NodeName,
+        execution_id: ExecutionId,
+        reason: String,
+    ) {
+        self.consume_node(run_id, &node_name, NodeRunStatus::Skipped);
+        self.node_stats
+            .entry(node_name.clone())
+            .or_default()
+            .skipped_executions += 1;
+
+        log::info!(
+            "Node {} skipped execution {}: {}",
             node_name,
             execution_id,
-            duration,
+            reason
+        );
+    }
+
+    /// 记录节点等待执行许可
+    fn record_exclusivity_wait(
+        &mut self,
+        node_name: NodeName,
+        execution_id: ExecutionId,
+        mode: ExecutionMode,
+    ) {
+        self.node_stats
+            .entry(node_name.clone())
+            .or_default()
+            .exclusivity_waits += 1;
+
+        log::info!(
+            "Node {} ({}) waiting for exclusivity before execution {}",
+            node_name,
+            mode,
+            execution_id
+        );
+    }
+
+    /// 记录一次尝试失败后的重试
+    fn record_retry(
+        &mut self,
+        node_name: NodeName,
+        execution_id: ExecutionId,
+        attempt: u32,
+        error: NodeError,
+        backoff: Duration,
+    ) {
+        log::warn!(
+            "Node {} attempt #{} of execution {} failed ({}), retrying in {:?}: {}",
+            node_name,
+            attempt,
+            execution_id,
+            error.kind(),
+            backoff,
             error
         );
+
+        let stats = self.node_stats.entry(node_name).or_default();
+        stats.retries += 1;
+        stats.last_error = Some(error);
+    }
+
+    /// 记录一次尝试超时
+    fn record_timeout(
+        &mut self,
+        node_name: NodeName,
+        execution_id: ExecutionId,
+        attempt: u32,
+        timeout: Duration,
+    ) {
+        log::warn!(
+            "Node {} attempt #{} of execution {} timed out after {:?}",
+            node_name,
+            attempt,
+            execution_id,
+            timeout
+        );
+
+        self.node_stats.entry(node_name).or_default().timeouts += 1;
+    }
+
+    /// 记录输入队列已满，发送方等待
+    fn record_queue_full(&mut self, node_name: NodeName, port_name: PortName, capacity: usize) {
+        log::debug!(
+            "Node {} queue on {} is full (capacity {}), sender waits",
+            node_name,
+            port_name,
+            capacity
+        );
+
+        self.node_stats
+            .entry(node_name)
+            .or_default()
+            .backpressure_waits += 1;
+    }
+
+    /// 记录连接上的转换失败，计入产生输出的节点
+    fn record_edge_error(
+        &mut self,
+        run_id: &RunId,
+        node_name: NodeName,
+        execution_id: ExecutionId,
+        error: EdgeError,
+    ) {
+        log::error!(
+            "Edge conversion failed in execution {} of {}: {}",
+            execution_id,
+            node_name,
+            error
+        );
+
+        if let Some(run) = self.runs.get_mut(run_id) {
+            run.edge_errors.push(error.clone());
+        }
+        let stats = self.node_stats.entry(node_name).or_default();
+        stats.conversion_failures += 1;
+        stats.last_edge_error = Some(error);
     }
 
     /// 获取系统整体统计
@@ -180,6 +343,7 @@ impl SimpleStatusTracker {
             },
             uptime,
             active_nodes: self.node_stats.len(),
+            active_runs: self.runs.len(),
         }
     }
 
@@ -226,20 +390,68 @@ impl Actor for SimpleStatusTracker {
 #[derive(Debug, Clone)]
 pub enum NodeStatusEvent {
     ExecutionStarted {
+        run_id: RunId,
         node_name: NodeName,
         execution_id: ExecutionId,
     },
     ExecutionCompleted {
+        run_id: RunId,
         node_name: NodeName,
         execution_id: ExecutionId,
         duration: Duration,
     },
     ExecutionFailed {
+        run_id: RunId,
         node_name: NodeName,
         execution_id: ExecutionId,
-        error: String,
+        error: NodeError,
         duration: Duration,
     },
+    ExecutionSkipped {
+        run_id: RunId,
+        node_name: NodeName,
+        execution_id: ExecutionId,
+        reason: String,
+    },
+    /// 节点已就绪，但在等待执行许可：Sequential 节点等待其他节点结束，
+    /// 或 Concurrent 节点等待正在运行的 Sequential 节点
+    WaitingForExclusivity {
+        run_id: RunId,
+        node_name: NodeName,
+        execution_id: ExecutionId,
+        mode: ExecutionMode,
+    },
+    /// 一次尝试因可重试的错误失败，将在退避后重试
+    RetryScheduled {
+        run_id: RunId,
+        node_name: NodeName,
+        execution_id: ExecutionId,
+        attempt: u32,
+        error: NodeError,
+        backoff: Duration,
+    },
+    /// 一次尝试超时
+    AttemptTimedOut {
+        run_id: RunId,
+        node_name: NodeName,
+        execution_id: ExecutionId,
+        attempt: u32,
+        timeout: Duration,
+    },
+    /// 流式模式下输入端口队列已满，新到达的 token 暂存，发送方等待队列腾出位置
+    InputQueueFull {
+        run_id: RunId,
+        node_name: NodeName,
+        port_name: PortName,
+        capacity: usize,
+    },
+    /// 节点的输出无法转换为下游输入端口的语义标签，该下游收到跳过消息
+    EdgeConversionFailed {
+        run_id: RunId,
+        node_name: NodeName,
+        execution_id: ExecutionId,
+        error: EdgeError,
+    },
 }
 
 impl Message<NodeStatusEvent> for SimpleStatusTracker {
@@ -252,25 +464,79 @@ impl Message<NodeStatusEvent> for SimpleStatusTracker {
     ) -> Self::Reply {
         match event {
             NodeStatusEvent::ExecutionStarted {
+                run_id: _,
                 node_name,
                 execution_id,
             } => {
                 self.record_execution_start(node_name, execution_id);
             }
             NodeStatusEvent::ExecutionCompleted {
+                run_id,
                 node_name,
                 execution_id,
                 duration,
             } => {
-                self.record_execution_success(node_name, execution_id, duration);
+                self.record_execution_success(&run_id, node_name, execution_id, duration);
             }
             NodeStatusEvent::ExecutionFailed {
+                run_id,
                 node_name,
                 execution_id,
                 error,
                 duration,
             } => {
-                self.record_execution_failure(node_name, execution_id, error, duration);
+                self.record_execution_failure(&run_id, node_name, execution_id, error, duration);
+            }
+            NodeStatusEvent::ExecutionSkipped {
+                run_id,
+                node_name,
+                execution_id,
+                reason,
+            } => {
+                self.record_execution_skipped(&run_id, node_name, execution_id, reason);
+            }
+            NodeStatusEvent::WaitingForExclusivity {
+                run_id: _,
+                node_name,
+                execution_id,
+                mode,
+            } => {
+                self.record_exclusivity_wait(node_name, execution_id, mode);
+            }
+            NodeStatusEvent::RetryScheduled {
+                run_id: _,
+                node_name,
+                execution_id,
+                attempt,
+                error,
+                backoff,
+            } => {
+                self.record_retry(node_name, execution_id, attempt, error, backoff);
+            }
+            NodeStatusEvent::AttemptTimedOut {
+                run_id: _,
+                node_name,
+                execution_id,
+                attempt,
+                timeout,
+            } => {
+                self.record_timeout(node_name, execution_id, attempt, timeout);
+            }
+            NodeStatusEvent::InputQueueFull {
+                run_id: _,
+                node_name,
+                port_name,
+                capacity,
+            } => {
+                self.record_queue_full(node_name, port_name, capacity);
+            }
+            NodeStatusEvent::EdgeConversionFailed {
+                run_id,
+                node_name,
+                execution_id,
+                error,
+            } => {
+                self.record_edge_error(&run_id, node_name, execution_id, error);
             }
         }
     }
@@ -289,6 +555,8 @@ pub struct SystemStats {
     pub success_rate: f64,
     pub uptime: Duration,
     pub active_nodes: usize,
+    /// 已开始、尚未收集结果的运行数
+    pub active_runs: usize,
 }
 
 impl Message<GetSystemStatsQuery> for SimpleStatusTracker {
@@ -343,44 +611,60 @@ impl Message<ResetStatsCommand> for SimpleStatusTracker {
     }
 }
 
-/// 设置期望节点列表命令
+/// 开始一次运行，此后该运行的状态事件计入它的结果
 #[derive(Debug)]
-pub struct SetExpectedNodesCommand {
-    pub nodes: Vec<NodeName>,
+pub struct StartRunCommand {
+    pub run_id: RunId,
 }
 
-impl Message<SetExpectedNodesCommand> for SimpleStatusTracker {
+impl Message<StartRunCommand> for SimpleStatusTracker {
     type Reply = ();
 
     async fn handle(
         &mut self,
-        cmd: SetExpectedNodesCommand,
+        cmd: StartRunCommand,
         _ctx: &mut Context<Self, Self::Reply>,
     ) -> Self::Reply {
-        self.set_expected_nodes(cmd.nodes);
+        self.runs.insert(cmd.run_id, RunResult::default());
     }
 }
 
-/// 设置关机钩子命令
-pub struct SetShutdownHookCommand {
-    pub hook: Box<dyn Fn() + Send + Sync + 'static>,
+/// 结束一次运行，取走已记录的结果
+///
+/// 只在运行静止后发送：此时该运行的所有状态事件都已进入追踪器的邮箱
+#[derive(Debug)]
+pub struct FinishRunQuery {
+    pub run_id: RunId,
 }
 
-impl std::fmt::Debug for SetShutdownHookCommand {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("SetShutdownHookCommand")
-            .finish_non_exhaustive()
+impl Message<FinishRunQuery> for SimpleStatusTracker {
+    type Reply = RunResult;
+
+    async fn handle(
+        &mut self,
+        query: FinishRunQuery,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> Self::Reply {
+        self.runs.remove(&query.run_id).unwrap_or_default()
     }
 }
 
-impl Message<SetShutdownHookCommand> for SimpleStatusTracker {
+/// 汇点输出：没有下游连接的数据输出端口产生的值
+#[derive(Debug)]
+pub struct SinkOutputMessage {
+    pub run_id: RunId,
+    pub port: PortRef,
+    pub data: Box<dyn SemanticLabel>,
+}
+
+impl Message<SinkOutputMessage> for SimpleStatusTracker {
     type Reply = ();
 
     async fn handle(
         &mut self,
-        cmd: SetShutdownHookCommand,
+        message: SinkOutputMessage,
         _ctx: &mut Context<Self, Self::Reply>,
     ) -> Self::Reply {
-        self.shutdown_hook = Some(cmd.hook);
+        self.record_sink_output(&message.run_id, message.port, message.data);
     }
 }
@@ -0,0 +1,36 @@
+//! 流式运行配置
+//!
+//! 流式模式下一次运行可以投递一串输入：节点的每个数据输入端口是有界 FIFO 队列，
+//! 每次执行从每个端口消费一个 token。端口队列已满时 token 不会被丢弃，发送方等到
+//! token 进入队列才继续；节点 actor 也使用有界邮箱。下游处理不过来时上游的发送会等待，
+//! 反压一直传到投递输入的 [`GraphRunner::run_stream`](crate::GraphRunner::run_stream)。
+
+/// 流式运行配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamingConfig {
+    /// 每个数据输入端口队列最多缓存的 token 数
+    pub queue_capacity: usize,
+    /// 节点 actor 的邮箱容量
+    pub mailbox_capacity: usize,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            queue_capacity: 64,
+            mailbox_capacity: 64,
+        }
+    }
+}
+
+impl StreamingConfig {
+    pub fn with_queue_capacity(mut self, capacity: usize) -> Self {
+        self.queue_capacity = capacity;
+        self
+    }
+
+    pub fn with_mailbox_capacity(mut self, capacity: usize) -> Self {
+        self.mailbox_capacity = capacity;
+        self
+    }
+}
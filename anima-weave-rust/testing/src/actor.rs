@@ -0,0 +1,170 @@
+//! 在真实 actor 中运行节点
+//!
+//! 每个输出端口连接一个收集节点，被测节点的输出通过正常的下游投递到达收集节点。
+
+use crate::harness::NodeHarness;
+use anima_weave_core::{Node, NodeContext, NodeError, NodeImpl, NodeInfo, PortRef, SemanticLabel};
+use anima_weave_node::{PortSpec, static_node_info};
+use anima_weave_runtime::{
+    DataInputMessage, DownstreamConnections, DownstreamEdge, GetNodeStatsQuery, GetNodeStatusQuery,
+    RunContext, SimpleNodeActor, SimpleStatusTracker, TriggerExecutionMessage,
+};
+use kameo::actor::{Actor, ActorRef};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+type Captured = Arc<Mutex<HashMap<String, Box<dyn SemanticLabel>>>>;
+
+const CAPTURE_PORT: &str = "value";
+
+/// 把收到的标签记录到对应输出端口名下
+#[derive(Debug)]
+struct CaptureNode {
+    info: &'static NodeInfo,
+    output_port: String,
+    captured: Captured,
+}
+
+impl Node for CaptureNode {
+    fn info(&self) -> &'static NodeInfo {
+        self.info
+    }
+
+    fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+        let label = ctx
+            .raw_input(CAPTURE_PORT)
+            .ok_or_else(|| NodeError::fatal("capture node received no value"))?;
+        self.captured
+            .lock()
+            .unwrap()
+            .insert(self.output_port.clone(), label.clone_box());
+        Ok(())
+    }
+}
+
+pub(crate) async fn run_in_actor(
+    harness: &NodeHarness,
+) -> (
+    Result<(), NodeError>,
+    HashMap<String, Box<dyn SemanticLabel>>,
+) {
+    let info = harness.node.info();
+    let captured = Captured::default();
+    let tracker = SimpleStatusTracker::spawn(SimpleStatusTracker::new());
+
+    let mut captures = Vec::new();
+    let mut downstream = DownstreamConnections::new();
+    for port in &info.output_ports {
+        let spec = PortSpec {
+            name: CAPTURE_PORT.to_string(),
+            label: port.port_type.semantic_label().to_string(),
+            required: true,
+            fan_in: None,
+        };
+        let capture_info = static_node_info("HarnessCapture", "收集被测节点的输出", &[spec], &[])
+            .expect("output label is registered");
+        let capture_name = format!("{}.capture.{}", harness.node_name, port.name);
+        let capture_port = PortRef {
+            node_name: capture_name.clone(),
+            port_name: CAPTURE_PORT.to_string(),
+        };
+        let capture = CaptureNode {
+            info: capture_info,
+            output_port: port.name.clone(),
+            captured: captured.clone(),
+        };
+        let capture_ref = SimpleNodeActor::spawn(SimpleNodeActor::new(
+            capture_name,
+            NodeImpl::Sync(Arc::new(capture)),
+            vec![capture_port.clone()],
+            HashMap::new(),
+        ));
+        downstream.insert(
+            port.name.clone(),
+            vec![DownstreamEdge::new(capture_ref.clone(), capture_port)],
+        );
+        captures.push(capture_ref);
+    }
+
+    let actor = SimpleNodeActor::new(
+        harness.node_name.clone(),
+        harness.node.clone(),
+        harness.inputs.keys().cloned().collect(),
+        downstream,
+    )
+    .with_status_tracker(tracker.clone())
+    .with_state(harness.state.clone());
+    let actor_ref = SimpleNodeActor::spawn(actor);
+
+    let result = match actor_ref.wait_for_startup_result().await {
+        Ok(()) => execute(harness, &actor_ref, &tracker).await,
+        Err(error) => Err(NodeError::fatal(error)),
+    };
+
+    for capture in &captures {
+        let _ = capture.ask(GetNodeStatusQuery).await;
+        stop(capture).await;
+    }
+    stop(&tracker).await;
+
+    let outputs = std::mem::take(&mut *captured.lock().unwrap());
+    (result, outputs)
+}
+
+/// 投递输入，等待执行和 `on_stop` 完成，从状态追踪器取回执行结果
+async fn execute(
+    harness: &NodeHarness,
+    actor_ref: &ActorRef<SimpleNodeActor>,
+    tracker: &ActorRef<SimpleStatusTracker>,
+) -> Result<(), NodeError> {
+    let run = RunContext::new("harness");
+    let delivered = if harness.inputs.is_empty() {
+        actor_ref
+            .ask(TriggerExecutionMessage {
+                execution_id: "harness".to_string(),
+                run: run.clone(),
+            })
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        let mut delivered = Ok(());
+        for (port, label) in &harness.inputs {
+            let message = DataInputMessage {
+                from_port: PortRef {
+                    node_name: "harness".to_string(),
+                    port_name: port.port_name.clone(),
+                },
+                to_port: port.clone(),
+                data: label.clone_box(),
+                execution_id: "harness".to_string(),
+                run: run.clone(),
+            };
+            delivered = actor_ref.ask(message).await.map_err(|e| e.to_string());
+            if delivered.is_err() {
+                break;
+            }
+        }
+        delivered
+    };
+    delivered.map_err(|e| NodeError::fatal(format!("Failed to deliver input: {}", e)))?;
+
+    actor_ref.stop_gracefully().await.ok();
+    let stopped = actor_ref.wait_for_shutdown_result().await;
+
+    let stats = tracker
+        .ask(GetNodeStatsQuery {
+            node_name: harness.node_name.clone(),
+        })
+        .await
+        .ok()
+        .flatten();
+    match stats.and_then(|stats| stats.last_error) {
+        Some(error) => Err(error),
+        None => stopped.map_err(NodeError::fatal),
+    }
+}
+
+async fn stop<A: Actor>(actor_ref: &ActorRef<A>) {
+    let _ = actor_ref.stop_gracefully().await;
+    actor_ref.wait_for_shutdown().await;
+}
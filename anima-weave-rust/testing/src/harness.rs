@@ -0,0 +1,380 @@
+use anima_weave_core::{
+    AsyncNode, Node, NodeContext, NodeDataInputs, NodeError, NodeErrorKind, NodeImpl, NodeInfo,
+    NodeState, PortRef, SemanticLabel, SignalLabel,
+};
+use anima_weave_node::{create_node_by_type, create_node_with_config, label_from_json};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 节点测试工具，见 crate 文档
+#[derive(Debug)]
+pub struct NodeHarness {
+    pub(crate) node: NodeImpl,
+    pub(crate) node_name: String,
+    pub(crate) inputs: NodeDataInputs,
+    pub(crate) state: NodeState,
+    pub(crate) require_all_outputs: bool,
+}
+
+impl NodeHarness {
+    /// 测试同步节点
+    pub fn new(node: impl Node + 'static) -> Self {
+        Self::from_impl(NodeImpl::Sync(Arc::new(node)))
+    }
+
+    /// 测试异步节点
+    pub fn new_async(node: impl AsyncNode + 'static) -> Self {
+        Self::from_impl(NodeImpl::Async(Arc::new(node)))
+    }
+
+    pub fn from_impl(node: NodeImpl) -> Self {
+        Self {
+            node_name: node.node_type().to_string(),
+            node,
+            inputs: NodeDataInputs::new(),
+            state: NodeState::new(),
+            require_all_outputs: true,
+        }
+    }
+
+    /// 测试已注册的节点类型
+    pub fn from_type(node_type: &str) -> Self {
+        let node = create_node_by_type(node_type)
+            .unwrap_or_else(|| panic!("Node type '{}' is not registered", node_type));
+        Self::from_impl(node)
+    }
+
+    /// 测试需要实例配置的节点类型
+    pub fn from_config(node_type: &str, config: Value) -> Self {
+        let node = create_node_with_config(node_type, &config)
+            .unwrap_or_else(|e| panic!("Failed to create {}: {}", node_type, e));
+        Self::from_impl(node)
+    }
+
+    /// 节点实例名，默认为节点类型名
+    pub fn named(mut self, node_name: impl Into<String>) -> Self {
+        self.node_name = node_name.into();
+        self
+    }
+
+    /// 设置输入，值按端口声明的语义标签从 JSON 构造
+    pub fn input(self, port_name: &str, value: impl Serialize) -> Self {
+        let label_type = self.input_port_label(port_name);
+        let value = serde_json::to_value(value)
+            .unwrap_or_else(|e| panic!("Input '{}' is not serializable: {}", port_name, e));
+        let json = match value {
+            Value::Object(_) => value,
+            other => json!({ "value": other }),
+        };
+        let label = label_from_json(label_type, &json).unwrap_or_else(|e| {
+            panic!(
+                "Cannot build {} for input '{}' from {}: {}",
+                label_type, port_name, json, e
+            )
+        });
+        self.insert_input(port_name, label)
+    }
+
+    /// 直接设置输入标签（不做转换，节点会收到原始标签）
+    pub fn input_label(self, port_name: &str, label: impl SemanticLabel) -> Self {
+        self.input_port_label(port_name);
+        self.insert_input(port_name, Box::new(label))
+    }
+
+    /// 使用给定的节点状态，可以预置状态或在运行后检查状态
+    pub fn with_state(mut self, state: NodeState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// 不要求每个声明的输出端口都有输出（条件输出的节点）
+    pub fn allow_missing_outputs(mut self) -> Self {
+        self.require_all_outputs = false;
+        self
+    }
+
+    /// 直接运行节点：依次调用 `on_start`、`execute`、`on_stop`
+    ///
+    /// 异步节点会在临时的 tokio runtime 上运行，已在 runtime 中时请用 [`Self::run_async`]
+    pub fn run(self) -> NodeRun {
+        match &self.node {
+            NodeImpl::Sync(node) => {
+                let node = node.clone();
+                let mut ctx = self.context();
+                let result = node
+                    .on_start(&self.state)
+                    .and_then(|_| node.execute(&mut ctx))
+                    .and_then(|_| node.on_stop(&self.state));
+                self.finish(result, ctx)
+            }
+            NodeImpl::Async(_) => tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("failed to build tokio runtime")
+                .block_on(self.run_async()),
+        }
+    }
+
+    /// 在当前 runtime 中直接运行节点
+    pub async fn run_async(self) -> NodeRun {
+        let mut ctx = self.context();
+        let result = match &self.node {
+            NodeImpl::Sync(node) => node
+                .on_start(&self.state)
+                .and_then(|_| node.execute(&mut ctx))
+                .and_then(|_| node.on_stop(&self.state)),
+            NodeImpl::Async(node) => {
+                async {
+                    node.on_start(&self.state).await?;
+                    node.execute(&mut ctx).await?;
+                    node.on_stop(&self.state).await
+                }
+                .await
+            }
+        };
+        self.finish(result, ctx)
+    }
+
+    /// 在真实的 `SimpleNodeActor` 中运行节点，输出经由下游连接收集
+    pub async fn run_in_actor(self) -> NodeRun {
+        let (result, outputs) = crate::actor::run_in_actor(&self).await;
+        NodeRun::new(&self, result, outputs)
+    }
+
+    fn context(&self) -> NodeContext {
+        let mut inputs: NodeDataInputs = self
+            .inputs
+            .iter()
+            .map(|(port, label)| (port.clone(), label.clone_box()))
+            .collect();
+
+        // 与 runtime 一致：没有输入的可选端口使用默认值
+        for port in &self.info().input_ports {
+            if let (false, Some(default)) = (port.required, &port.default) {
+                inputs
+                    .entry(PortRef {
+                        node_name: self.node_name.clone(),
+                        port_name: port.name.clone(),
+                    })
+                    .or_insert_with(|| default.clone());
+            }
+        }
+        NodeContext::new(self.node_name.clone(), self.node.info(), inputs)
+            .with_state(self.state.clone())
+    }
+
+    fn finish(&self, result: Result<(), NodeError>, ctx: NodeContext) -> NodeRun {
+        let mut outputs: HashMap<String, Box<dyn SemanticLabel>> = ctx
+            .into_outputs()
+            .into_iter()
+            .map(|(port, label)| (port.port_name, label))
+            .collect();
+
+        // 与 runtime 一致：执行成功时，未写入的控制输出发出激活信号
+        if result.is_ok() {
+            for port in self
+                .info()
+                .output_ports
+                .iter()
+                .filter(|port| port.is_control())
+            {
+                outputs
+                    .entry(port.name.clone())
+                    .or_insert_with(|| Box::new(SignalLabel::ACTIVE));
+            }
+        }
+        NodeRun::new(self, result, outputs)
+    }
+
+    fn info(&self) -> &'static NodeInfo {
+        self.node.info()
+    }
+
+    fn input_port_label(&self, port_name: &str) -> &'static str {
+        self.info()
+            .input_ports
+            .iter()
+            .find(|port| port.name == port_name)
+            .map(|port| port.port_type.semantic_label())
+            .unwrap_or_else(|| {
+                panic!(
+                    "Node {} has no input port '{}'",
+                    self.info().name,
+                    port_name
+                )
+            })
+    }
+
+    fn insert_input(mut self, port_name: &str, label: Box<dyn SemanticLabel>) -> Self {
+        let port = PortRef {
+            node_name: self.node_name.clone(),
+            port_name: port_name.to_string(),
+        };
+        self.inputs.insert(port, label);
+        self
+    }
+}
+
+/// 一次运行的结果
+///
+/// 断言方法失败时 panic，成功时返回自身以便链式调用
+#[derive(Debug)]
+pub struct NodeRun {
+    node_type: &'static str,
+    result: Result<(), NodeError>,
+    outputs: HashMap<String, Box<dyn SemanticLabel>>,
+}
+
+impl NodeRun {
+    pub(crate) fn new(
+        harness: &NodeHarness,
+        result: Result<(), NodeError>,
+        outputs: HashMap<String, Box<dyn SemanticLabel>>,
+    ) -> Self {
+        let run = Self {
+            node_type: harness.info().name,
+            result,
+            outputs,
+        };
+        run.check_outputs(harness.info(), harness.require_all_outputs);
+        run
+    }
+
+    /// 对照 NodeInfo 检查输出端口和标签类型
+    fn check_outputs(&self, info: &NodeInfo, require_all: bool) {
+        for (port_name, label) in &self.outputs {
+            let port = info
+                .output_ports
+                .iter()
+                .find(|port| &port.name == port_name)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Node {} wrote undeclared output '{}'",
+                        self.node_type, port_name
+                    )
+                });
+            assert_eq!(
+                label.get_semantic_label_type(),
+                port.port_type.semantic_label(),
+                "Node {} output '{}' has the wrong label",
+                self.node_type,
+                port_name
+            );
+        }
+
+        if require_all && self.result.is_ok() {
+            for port in &info.output_ports {
+                assert!(
+                    self.outputs.contains_key(&port.name),
+                    "Node {} did not produce declared output '{}'",
+                    self.node_type,
+                    port.name
+                );
+            }
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+
+    pub fn error(&self) -> Option<&NodeError> {
+        self.result.as_ref().err()
+    }
+
+    pub fn outputs(&self) -> &HashMap<String, Box<dyn SemanticLabel>> {
+        &self.outputs
+    }
+
+    /// 断言执行成功
+    pub fn assert_ok(&self) -> &Self {
+        if let Err(error) = &self.result {
+            panic!(
+                "Node {} failed ({}): {}",
+                self.node_type,
+                error.kind(),
+                error
+            );
+        }
+        self
+    }
+
+    /// 断言执行失败且错误类别符合预期
+    pub fn assert_error(&self, kind: NodeErrorKind) -> &Self {
+        match &self.result {
+            Ok(()) => panic!("Node {} succeeded, expected {} error", self.node_type, kind),
+            Err(error) => assert_eq!(
+                error.kind(),
+                kind,
+                "Node {} failed with unexpected error: {}",
+                self.node_type,
+                error
+            ),
+        }
+        self
+    }
+
+    /// 断言输出值
+    ///
+    /// 与标签的 JSON 形式比较；期望值不是对象时，和单字段标签的字段值比较
+    pub fn assert_output(&self, port_name: &str, expected: impl Serialize) -> &Self {
+        let expected = serde_json::to_value(expected).expect("expected value is serializable");
+        let actual = self.output_json(port_name);
+        let actual = match actual {
+            Value::Object(map) if map.len() == 1 && !expected.is_object() => {
+                map.into_iter().next().unwrap().1
+            }
+            other => other,
+        };
+        assert_eq!(
+            actual, expected,
+            "Node {} output '{}' mismatch",
+            self.node_type, port_name
+        );
+        self
+    }
+
+    /// 断言某个输出端口没有输出
+    pub fn assert_no_output(&self, port_name: &str) -> &Self {
+        assert!(
+            !self.outputs.contains_key(port_name),
+            "Node {} unexpectedly produced output '{}'",
+            self.node_type,
+            port_name
+        );
+        self
+    }
+
+    /// 输出标签
+    pub fn output_label(&self, port_name: &str) -> &dyn SemanticLabel {
+        self.assert_ok();
+        self.outputs
+            .get(port_name)
+            .unwrap_or_else(|| panic!("Node {} produced no output '{}'", self.node_type, port_name))
+            .as_ref()
+    }
+
+    /// 类型化读取输出标签
+    pub fn output<T: SemanticLabel + Clone>(&self, port_name: &str) -> T {
+        let label = self.output_label(port_name);
+        label
+            .as_any()
+            .downcast_ref::<T>()
+            .cloned()
+            .unwrap_or_else(|| {
+                panic!(
+                    "Output '{}' is {}, not {}",
+                    port_name,
+                    label.get_semantic_label_type(),
+                    T::semantic_label_type()
+                )
+            })
+    }
+
+    /// 输出标签的 JSON 形式
+    pub fn output_json(&self, port_name: &str) -> Value {
+        self.output_label(port_name).to_json()
+    }
+}
@@ -0,0 +1,106 @@
+//! `anima-weave-testing` Crate: 节点单元测试工具
+//!
+//! ```ignore
+//! use anima_weave_testing::NodeHarness;
+//!
+//! NodeHarness::new(AddNode)
+//!     .input("a", 5.0)
+//!     .input("b", 3.0)
+//!     .run()
+//!     .assert_output("result", 8.0);
+//! ```
+//!
+//! 输入值按端口声明的语义标签从 JSON 构造，基本类型会包装成 `{"value": ..}`，
+//! 适用于 `NumberLabel`、`StringLabel` 这类单字段标签；其他标签可以传 JSON 对象，
+//! 或者用 [`NodeHarness::input_label`] 直接传标签。
+//!
+//! 运行结束后会对照 `NodeInfo` 检查输出：不允许写入未声明的端口，
+//! 默认要求每个声明的输出端口都有输出（见 [`NodeHarness::allow_missing_outputs`]）。
+
+mod actor;
+mod harness;
+
+pub use harness::{NodeHarness, NodeRun};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anima_weave_core::{NodeErrorKind, semantic_label};
+    use anima_weave_node::node;
+
+    semantic_label! {
+        HarnessNumber(value: f64) {}
+    }
+
+    #[node(name = "HarnessAddNode")]
+    fn add(a: HarnessNumber, b: HarnessNumber) -> HarnessNumber {
+        HarnessNumber {
+            value: a.value + b.value,
+        }
+    }
+
+    #[node(name = "HarnessSplitNode", outputs = ["int", "frac"])]
+    async fn split(x: HarnessNumber) -> (HarnessNumber, HarnessNumber) {
+        let int = x.value.trunc();
+        (
+            HarnessNumber { value: int },
+            HarnessNumber {
+                value: x.value - int,
+            },
+        )
+    }
+
+    #[test]
+    fn test_run_and_assert_outputs() {
+        let run = NodeHarness::new(HarnessAddNode)
+            .input("a", 5.0)
+            .input("b", 3.0)
+            .run();
+        run.assert_ok().assert_output("result", 8.0);
+        assert_eq!(run.output::<HarnessNumber>("result").value, 8.0);
+
+        NodeHarness::new_async(HarnessSplitNode)
+            .input("x", 2.5)
+            .run()
+            .assert_output("int", 2.0)
+            .assert_output("frac", serde_json::json!({ "value": 0.5 }));
+    }
+
+    #[test]
+    fn test_missing_input_is_invalid_input() {
+        let run = NodeHarness::from_type("HarnessAddNode")
+            .input("a", 1.0)
+            .run();
+        run.assert_error(NodeErrorKind::InvalidInput);
+        assert_eq!(run.error().unwrap().port(), Some("b"));
+    }
+
+    #[test]
+    #[should_panic(expected = "has no input port 'c'")]
+    fn test_undeclared_input_panics() {
+        NodeHarness::new(HarnessAddNode).input("c", 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_run_in_actor() {
+        NodeHarness::new(HarnessAddNode)
+            .named("sum")
+            .input("a", 1.0)
+            .input("b", 2.0)
+            .run_in_actor()
+            .await
+            .assert_output("result", 3.0);
+
+        let run = NodeHarness::new_async(HarnessSplitNode)
+            .input("x", 1.25)
+            .run_in_actor()
+            .await;
+        run.assert_output("int", 1.0).assert_output("frac", 0.25);
+
+        NodeHarness::new(HarnessAddNode)
+            .input("a", 1.0)
+            .run_in_actor()
+            .await
+            .assert_error(NodeErrorKind::InvalidInput);
+    }
+}
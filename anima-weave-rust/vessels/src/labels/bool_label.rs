@@ -0,0 +1,47 @@
+use anima_weave_core::semantic_label;
+
+semantic_label! {
+    /// Bool语义标签 - 布尔值类型
+    ///
+    /// 根据数学定义1：ℒ = {Int, Bool, String, ...}
+    /// Bool是条件判断的结果类型，分支节点按它选择分支
+    ///
+    /// # 字段
+    ///
+    /// * `value` - 存储的布尔值
+    ///
+    /// # 转换
+    ///
+    /// * `StringLabel` - 转换为 `"true"` / `"false"`
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use anima_weave_vessels::BoolLabel;
+    /// use anima_weave_core::SemanticLabel;
+    ///
+    /// let flag = BoolLabel { value: true };
+    /// let result = flag.try_convert_to("super::StringLabel");
+    /// assert!(result.is_ok());
+    /// ```
+    BoolLabel(value: bool) {
+        super::StringLabel => |this| super::StringLabel { value: this.value.to_string() },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StringLabel;
+    use anima_weave_core::SemanticLabel;
+
+    #[test]
+    fn test_bool_to_string_conversion() {
+        let flag = BoolLabel { value: false };
+        assert_eq!(flag.get_semantic_label_type(), "BoolLabel");
+
+        let converted = flag.try_convert_to("super::StringLabel").unwrap();
+        let string = converted.as_any().downcast_ref::<StringLabel>().unwrap();
+        assert_eq!(string.value, "false");
+    }
+}
@@ -1,7 +1,9 @@
+pub mod bool_label;
 pub mod number_label;
 pub mod prompt_label;
 pub mod string_label;
 
+pub use bool_label::*;
 pub use number_label::*;
 pub use prompt_label::*;
 pub use string_label::*;
@@ -1,12 +1,18 @@
 pub mod labels;
 pub mod nodes;
 
-pub use labels::{number_label::NumberLabel, prompt_label::PromptLabel, string_label::StringLabel};
+pub use labels::{
+    bool_label::BoolLabel, number_label::NumberLabel, prompt_label::PromptLabel,
+    string_label::StringLabel,
+};
 
-pub use nodes::{AddNode, RandomNode, StartNode};
+pub use nodes::{
+    AddNode, CommandConfig, CommandNode, IfConfig, IfNode, RandomNode, ScriptConfig, ScriptNode,
+    StartNode, SwitchConfig, SwitchNode,
+};
 
 pub use anima_weave_node::{
-    Node, create_node_by_type, create_node_factory, get_registered_node_types,
+    AsyncNode, Node, NodeImpl, create_node_by_type, create_node_factory, get_registered_node_types,
 };
 
 #[cfg(test)]
@@ -107,6 +113,97 @@ mod integration_tests {
         }
     }
 
+    #[anima_weave_node::node(name = "DivModTestNode", outputs = ["quotient", "remainder"])]
+    fn div_mod(
+        a: NumberLabel,
+        b: Option<NumberLabel>,
+    ) -> Result<(NumberLabel, NumberLabel), anyhow::Error> {
+        let b = b.map(|b| b.value).unwrap_or(1.0);
+        if b == 0.0 {
+            anyhow::bail!("division by zero");
+        }
+        Ok((
+            NumberLabel {
+                value: (a.value / b).floor(),
+            },
+            NumberLabel { value: a.value % b },
+        ))
+    }
+
+    #[anima_weave_node::node(
+        name = "EchoTestNode",
+        version = "2.1.0",
+        category = "text",
+        tags = ["echo"],
+        author = "tests"
+    )]
+    async fn echo(text: StringLabel) -> StringLabel {
+        text
+    }
+
+    #[test]
+    fn test_node_macro() {
+        use anima_weave_core::{NodeContext, NodeDataInputs, PortRef};
+
+        let node = DivModTestNode::new();
+        let info = node.info();
+        assert_eq!(info.name, "DivModTestNode");
+        assert!(info.input_ports[0].required);
+        assert!(!info.input_ports[1].required);
+        assert_eq!(info.output_ports[0].name, "quotient");
+        assert_eq!(info.output_ports[1].name, "remainder");
+
+        let port = |port_name: &str| PortRef {
+            node_name: "dm".to_string(),
+            port_name: port_name.to_string(),
+        };
+        let mut inputs = NodeDataInputs::new();
+        inputs.insert(port("a"), Box::new(NumberLabel { value: 7.0 }));
+        inputs.insert(port("b"), Box::new(NumberLabel { value: 2.0 }));
+        let mut ctx = NodeContext::new("dm", info, inputs);
+        node.execute(&mut ctx).unwrap();
+
+        let outputs = ctx.into_outputs();
+        let value = |port_name: &str| {
+            outputs[&port(port_name)]
+                .as_any()
+                .downcast_ref::<NumberLabel>()
+                .unwrap()
+                .value
+        };
+        assert_eq!(value("quotient"), 3.0);
+        assert_eq!(value("remainder"), 1.0);
+
+        let mut inputs = NodeDataInputs::new();
+        inputs.insert(port("a"), Box::new(NumberLabel { value: 7.0 }));
+        inputs.insert(port("b"), Box::new(NumberLabel { value: 0.0 }));
+        let mut ctx = NodeContext::new("dm", info, inputs);
+        let err = node.execute(&mut ctx).unwrap_err();
+        assert!(err.to_string().contains("division by zero"));
+
+        // 宏生成的节点会自动注册，async fn 注册为异步节点
+        assert!(create_node_by_type("DivModTestNode").is_some());
+        assert!(create_node_by_type("EchoTestNode").unwrap().is_async());
+    }
+
+    #[test]
+    fn test_registration_metadata() {
+        use anima_weave_node::{get_node_registration, verify_registrations};
+
+        verify_registrations().unwrap();
+
+        let echo = get_node_registration("EchoTestNode").unwrap().metadata;
+        assert_eq!(echo.version, "2.1.0");
+        assert_eq!(echo.category, "text");
+        assert_eq!(echo.tags, ["echo"]);
+        assert_eq!(echo.author, "tests");
+
+        // 未指定版本时取所在 crate 的版本
+        let add = get_node_registration("AddNode").unwrap().metadata;
+        assert_eq!(add.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(add.category, "math");
+    }
+
     #[test]
     fn test_node_registration_system() {
         // 测试节点注册系统
@@ -0,0 +1,329 @@
+//! Branch Nodes - 条件分支节点
+//!
+//! `IfNode` 按 `condition` 在 `then` / `else` 之间选择，`SwitchNode` 按 `key` 选择
+//! 同名的分支，没有匹配时选择 `default`。每个分支是一个控制输出：选中的分支发出激活信号，
+//! 其余分支发出非激活信号，控制输入连到这些分支的下游节点被跳过，跳过继续沿下游传播。
+//!
+//! 节点实例配置了 `label` 时还带有数据输入 `value`，转发到选中分支的 `{分支}_value`
+//! 输出；其余分支的数据输出不写入，其下游同样被跳过：
+//!
+//! ```json
+//! { "cases": ["text", "image"], "label": "PromptLabel" }
+//! ```
+
+use crate::labels::{BoolLabel, StringLabel};
+use anima_weave_core::{ERROR_PORT, NodeContext, NodeError, NodeImpl, SignalLabel};
+use anima_weave_node::{Node, NodeInfo, NodeRegistration, PortSpec, static_node_info};
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// 转发的数据输入端口
+const VALUE_PORT: &str = "value";
+/// SwitchNode 没有匹配的分支时选择的分支
+const DEFAULT_BRANCH: &str = "default";
+
+/// if 节点配置
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IfConfig {
+    /// 转发数据的标签类型，不设置时只有控制输出
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// switch 节点配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct SwitchConfig {
+    /// 分支名，与 `key` 的值匹配
+    pub cases: Vec<String>,
+    /// 转发数据的标签类型，不设置时只有控制输出
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+fn port(name: impl Into<String>, label: &str) -> PortSpec {
+    PortSpec {
+        name: name.into(),
+        label: label.to_string(),
+        required: true,
+        fan_in: None,
+    }
+}
+
+/// 分支节点的端口：条件输入加可选的 `value`，每个分支一个控制输出和可选的数据输出
+fn branch_info(
+    name: &str,
+    description: &str,
+    condition: PortSpec,
+    branches: &[String],
+    label: Option<&str>,
+) -> Result<&'static NodeInfo> {
+    let signal = <SignalLabel as anima_weave_core::SemanticLabel>::semantic_label_type();
+    let mut inputs = vec![condition];
+    let mut outputs: Vec<PortSpec> = branches
+        .iter()
+        .map(|branch| port(branch.as_str(), signal))
+        .collect();
+    if let Some(label) = label {
+        inputs.push(port(VALUE_PORT, label));
+        outputs.extend(
+            branches
+                .iter()
+                .map(|branch| port(format!("{}_{}", branch, VALUE_PORT), label)),
+        );
+    }
+    static_node_info(name, description, &inputs, &outputs)
+}
+
+/// 选中 `taken` 分支：向每个分支写入控制信号，有 `value` 输入时转发到选中的分支
+fn take_branch(ctx: &mut NodeContext, branches: &[String], taken: &str) -> Result<(), NodeError> {
+    for branch in branches {
+        let signal = if branch == taken {
+            SignalLabel::ACTIVE
+        } else {
+            SignalLabel::INACTIVE
+        };
+        ctx.output(branch, signal)?;
+    }
+    if ctx
+        .info()
+        .input_ports
+        .iter()
+        .any(|port| port.name == VALUE_PORT)
+    {
+        let value = ctx.input_label(VALUE_PORT)?;
+        ctx.output_boxed(&format!("{}_{}", taken, VALUE_PORT), value)?;
+    }
+    Ok(())
+}
+
+/// if 节点实现
+#[derive(Debug)]
+pub struct IfNode {
+    info: &'static NodeInfo,
+    branches: Vec<String>,
+}
+
+impl IfNode {
+    pub fn new(config: IfConfig) -> Result<Self> {
+        let branches = vec!["then".to_string(), "else".to_string()];
+        let info = branch_info(
+            "IfNode",
+            "按条件在 then / else 分支之间选择，未选中的分支被跳过",
+            port("condition", "BoolLabel"),
+            &branches,
+            config.label.as_deref(),
+        )?;
+        Ok(Self { info, branches })
+    }
+
+    /// NodeConstructor::Configured 入口，没有配置时只有控制输出
+    fn from_config(config: &Value) -> Result<NodeImpl> {
+        let config = if config.is_null() {
+            IfConfig::default()
+        } else {
+            serde_json::from_value(config.clone())
+                .map_err(|e| anyhow!("Invalid IfNode config: {}", e))?
+        };
+        Ok(NodeImpl::Sync(Arc::new(Self::new(config)?)))
+    }
+}
+
+impl Node for IfNode {
+    fn info(&self) -> &'static NodeInfo {
+        self.info
+    }
+
+    fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+        let condition = ctx.input::<BoolLabel>("condition")?;
+        let taken = if condition.value { "then" } else { "else" };
+        take_branch(ctx, &self.branches, taken)
+    }
+}
+
+/// switch 节点实现
+#[derive(Debug)]
+pub struct SwitchNode {
+    info: &'static NodeInfo,
+    /// 各分支，`default` 在最后
+    branches: Vec<String>,
+}
+
+impl SwitchNode {
+    pub fn new(config: SwitchConfig) -> Result<Self> {
+        let mut seen = HashSet::new();
+        for case in &config.cases {
+            if case.is_empty() || case == DEFAULT_BRANCH || case == ERROR_PORT {
+                return Err(anyhow!("Invalid switch case name '{}'", case));
+            }
+            if !seen.insert(case.as_str()) {
+                return Err(anyhow!("Duplicate switch case '{}'", case));
+            }
+        }
+        // 转发数据时每个分支还有 `{分支}_value` 输出，分支名不能与之重名
+        if config.label.is_some() {
+            for case in &config.cases {
+                if let Some(branch) = case.strip_suffix(&format!("_{}", VALUE_PORT))
+                    && (branch == DEFAULT_BRANCH || seen.contains(branch))
+                {
+                    return Err(anyhow!(
+                        "Switch case '{}' conflicts with the value output of case '{}'",
+                        case,
+                        branch
+                    ));
+                }
+            }
+        }
+
+        let mut branches = config.cases.clone();
+        branches.push(DEFAULT_BRANCH.to_string());
+        let info = branch_info(
+            "SwitchNode",
+            "按 key 选择同名分支，没有匹配时选择 default，未选中的分支被跳过",
+            port("key", "StringLabel"),
+            &branches,
+            config.label.as_deref(),
+        )?;
+        Ok(Self { info, branches })
+    }
+
+    /// NodeConstructor::Configured 入口
+    fn from_config(config: &Value) -> Result<NodeImpl> {
+        let config: SwitchConfig = serde_json::from_value(config.clone())
+            .map_err(|e| anyhow!("Invalid SwitchNode config: {}", e))?;
+        Ok(NodeImpl::Sync(Arc::new(Self::new(config)?)))
+    }
+}
+
+impl Node for SwitchNode {
+    fn info(&self) -> &'static NodeInfo {
+        self.info
+    }
+
+    fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+        let key = ctx.input::<StringLabel>("key")?;
+        let cases = &self.branches[..self.branches.len() - 1];
+        let taken = if cases.contains(&key.value) {
+            key.value.as_str()
+        } else {
+            DEFAULT_BRANCH
+        };
+        take_branch(ctx, &self.branches, taken)
+    }
+}
+
+inventory::submit! {
+    NodeRegistration::configured("IfNode", IfNode::from_config)
+        .with_version(env!("CARGO_PKG_VERSION"))
+        .with_category("control")
+}
+
+inventory::submit! {
+    NodeRegistration::configured("SwitchNode", SwitchNode::from_config)
+        .with_version(env!("CARGO_PKG_VERSION"))
+        .with_category("control")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::labels::NumberLabel;
+    use anima_weave_core::{NodeDataInputs, PortRef, SemanticLabel};
+    use anima_weave_node::create_node_with_config;
+    use serde_json::json;
+
+    fn run(
+        node_type: &str,
+        config: Value,
+        inputs: Vec<(&str, Box<dyn SemanticLabel>)>,
+    ) -> Result<NodeContext, NodeError> {
+        let NodeImpl::Sync(node) = create_node_with_config(node_type, &config).unwrap() else {
+            panic!("{} should be a sync node", node_type);
+        };
+        let inputs: NodeDataInputs = inputs
+            .into_iter()
+            .map(|(port_name, label)| {
+                let port = PortRef {
+                    node_name: "branch".to_string(),
+                    port_name: port_name.to_string(),
+                };
+                (port, label)
+            })
+            .collect();
+        let mut ctx = NodeContext::new("branch", node.info(), inputs);
+        node.execute(&mut ctx)?;
+        Ok(ctx)
+    }
+
+    fn signal(ctx: &NodeContext, port_name: &str) -> Option<bool> {
+        let label = ctx.outputs().get(&ctx.port_ref(port_name))?;
+        Some(label.as_any().downcast_ref::<SignalLabel>()?.active)
+    }
+
+    #[test]
+    fn test_if_node_takes_one_branch() {
+        let ctx = run(
+            "IfNode",
+            Value::Null,
+            vec![("condition", Box::new(BoolLabel { value: false }))],
+        )
+        .unwrap();
+        assert_eq!(signal(&ctx, "then"), Some(false));
+        assert_eq!(signal(&ctx, "else"), Some(true));
+
+        // 配置了 label 时转发 value，未选中分支的数据输出不写入
+        let ctx = run(
+            "IfNode",
+            json!({ "label": "NumberLabel" }),
+            vec![
+                ("condition", Box::new(BoolLabel { value: true })),
+                ("value", Box::new(NumberLabel { value: 7.0 })),
+            ],
+        )
+        .unwrap();
+        assert_eq!(signal(&ctx, "then"), Some(true));
+        let value = &ctx.outputs()[&ctx.port_ref("then_value")];
+        assert_eq!(
+            value.as_any().downcast_ref::<NumberLabel>().unwrap().value,
+            7.0
+        );
+        assert!(!ctx.outputs().contains_key(&ctx.port_ref("else_value")));
+    }
+
+    #[test]
+    fn test_switch_node_matches_case_or_default() {
+        let config = json!({ "cases": ["a", "b"] });
+        let key = |value: &str| -> Vec<(&str, Box<dyn SemanticLabel>)> {
+            vec![(
+                "key",
+                Box::new(StringLabel {
+                    value: value.to_string(),
+                }),
+            )]
+        };
+
+        let ctx = run("SwitchNode", config.clone(), key("b")).unwrap();
+        assert_eq!(signal(&ctx, "a"), Some(false));
+        assert_eq!(signal(&ctx, "b"), Some(true));
+        assert_eq!(signal(&ctx, "default"), Some(false));
+
+        let ctx = run("SwitchNode", config, key("c")).unwrap();
+        assert_eq!(signal(&ctx, "b"), Some(false));
+        assert_eq!(signal(&ctx, "default"), Some(true));
+
+        for cases in [json!(["a", "a"]), json!(["default"]), json!(["error"])] {
+            let err = create_node_with_config("SwitchNode", &json!({ "cases": cases }));
+            assert!(err.is_err());
+        }
+
+        // 分支名与生成的 `{分支}_value` 输出重名
+        for cases in [json!(["a", "a_value"]), json!(["default_value"])] {
+            let config = json!({ "cases": cases, "label": "NumberLabel" });
+            assert!(create_node_with_config("SwitchNode", &config).is_err());
+        }
+        let config = json!({ "cases": ["a_value"], "label": "NumberLabel" });
+        assert!(create_node_with_config("SwitchNode", &config).is_ok());
+    }
+}
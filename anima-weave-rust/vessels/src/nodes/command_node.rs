@@ -0,0 +1,498 @@
+//! Command Node - 每次执行运行一次外部程序
+//!
+//! 配置来自节点实例（`NodeRef::config`）：
+//!
+//! ```json
+//! {
+//!   "program": "grep",
+//!   "args": ["-c", "{pattern}"],
+//!   "env": { "LC_ALL": "C" },
+//!   "stdin": "text",
+//!   "inputs": [
+//!     { "name": "pattern", "label": "StringLabel" },
+//!     { "name": "text", "label": "StringLabel" }
+//!   ],
+//!   "cwd": "/tmp",
+//!   "timeout_ms": 5000,
+//!   "fail_on_nonzero_exit": true,
+//!   "mode": "Sequential"
+//! }
+//! ```
+//!
+//! - `args` 和 `env` 的值中 `{端口名}` 会被替换为输入值，`stdin` 指定写入标准输入的端口
+//! - 输出端口固定为 `stdout`/`stderr`（StringLabel）和 `exit_code`（NumberLabel），
+//!   可以通过 `outputs` 改名或设为 null 关闭
+//! - `mode` 为 `Sequential` 时命令独占执行，适合不能并行运行的工具，默认 `Concurrent`
+//! - 输入值取标签的 JSON 形式：单字段标签取该字段，字符串原样使用，其余按 JSON 文本
+
+use anima_weave_core::{NodeContext, NodeError, NodeImpl};
+use anima_weave_node::{
+    ExecutionMode, Node, NodeInfo, NodeRegistration, PortSpec, static_node_info_with_mode,
+};
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::labels::{NumberLabel, StringLabel};
+
+/// 命令节点配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommandConfig {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// 写入标准输入的输入端口
+    #[serde(default)]
+    pub stdin: Option<String>,
+    #[serde(default)]
+    pub inputs: Vec<PortSpec>,
+    #[serde(default)]
+    pub outputs: CommandOutputs,
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+    /// 等待命令退出并读完输出的时限，超时或运行取消时结束命令所在的整个进程组
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// 退出码非 0 时视为执行失败
+    #[serde(default)]
+    pub fail_on_nonzero_exit: bool,
+    #[serde(default)]
+    pub mode: ExecutionMode,
+}
+
+fn default_timeout_ms() -> u64 {
+    30_000
+}
+
+/// 输出端口名，None 表示不输出
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CommandOutputs {
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    pub exit_code: Option<String>,
+}
+
+impl Default for CommandOutputs {
+    fn default() -> Self {
+        Self {
+            stdout: Some("stdout".to_string()),
+            stderr: Some("stderr".to_string()),
+            exit_code: Some("exit_code".to_string()),
+        }
+    }
+}
+
+/// 命令节点实现
+#[derive(Debug)]
+pub struct CommandNode {
+    info: &'static NodeInfo,
+    config: CommandConfig,
+}
+
+impl CommandNode {
+    pub fn new(config: CommandConfig) -> Result<Self> {
+        let port_names: Vec<&str> = config
+            .inputs
+            .iter()
+            .map(|port| port.name.as_str())
+            .collect();
+        if let Some(stdin) = &config.stdin
+            && !port_names.contains(&stdin.as_str())
+        {
+            return Err(anyhow!("stdin port '{}' is not declared in inputs", stdin));
+        }
+
+        let outputs = [
+            (&config.outputs.stdout, "StringLabel"),
+            (&config.outputs.stderr, "StringLabel"),
+            (&config.outputs.exit_code, "NumberLabel"),
+        ]
+        .into_iter()
+        .filter_map(|(name, label)| {
+            name.as_ref().map(|name| PortSpec {
+                name: name.clone(),
+                label: label.to_string(),
+                required: true,
+                fan_in: None,
+            })
+        })
+        .collect::<Vec<_>>();
+
+        let description = format!("运行外部命令 {}", config.program);
+        let info = static_node_info_with_mode(
+            "CommandNode",
+            &description,
+            &config.inputs,
+            &outputs,
+            config.mode,
+        )?;
+        Ok(Self { info, config })
+    }
+
+    /// NodeConstructor::Configured 入口
+    fn from_config(config: &Value) -> Result<NodeImpl> {
+        let config: CommandConfig = serde_json::from_value(config.clone())
+            .map_err(|e| anyhow!("Invalid CommandNode config: {}", e))?;
+        Ok(NodeImpl::Sync(Arc::new(Self::new(config)?)))
+    }
+}
+
+/// 把 `{端口名}` 替换为输入值
+///
+/// 模板只扫描一遍，替换进来的值不会再被当作模板；不是输入端口的 `{...}` 原样保留
+fn render(template: &str, values: &HashMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        match placeholder
+            .find('}')
+            .and_then(|end| Some((end, values.get(&placeholder[1..end])?)))
+        {
+            Some((end, value)) => {
+                rendered.push_str(value);
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+impl Node for CommandNode {
+    fn info(&self) -> &'static NodeInfo {
+        self.info
+    }
+
+    fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+        let mut values = HashMap::new();
+        for port in &self.info.input_ports {
+            if ctx.has_input(&port.name) {
+                let value = label_text(ctx.input_label(&port.name)?.to_json());
+                values.insert(port.name.clone(), value);
+            }
+        }
+
+        let mut command = Command::new(&self.config.program);
+        command
+            .args(self.config.args.iter().map(|arg| render(arg, &values)))
+            .envs(
+                self.config
+                    .env
+                    .iter()
+                    .map(|(key, value)| (key, render(value, &values))),
+            )
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(cwd) = &self.config.cwd {
+            command.current_dir(cwd);
+        }
+
+        // 命令在自己的进程组中运行，超时或取消时连同它启动的后台进程一起结束
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("Failed to run '{}'", self.config.program))?;
+
+        // 标准输入和输出都在独立线程中处理，避免管道缓冲区写满导致死锁
+        let stdin_data = self
+            .config
+            .stdin
+            .as_ref()
+            .and_then(|port| values.get(port).cloned())
+            .unwrap_or_default();
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let stdin_writer = std::thread::spawn(move || stdin.write_all(stdin_data.as_bytes()));
+        let stdout_reader = read_to_end(child.stdout.take().expect("stdout is piped"));
+        let stderr_reader = read_to_end(child.stderr.take().expect("stderr is piped"));
+
+        // 等待命令退出并读完输出：后台进程可能在命令退出后仍占着输出管道，
+        // 读取同样受超时限制
+        let deadline = Instant::now() + Duration::from_millis(self.config.timeout_ms);
+        let mut status = None;
+        let status = loop {
+            if status.is_none() {
+                status = child.try_wait().context("Failed to wait for command")?;
+            }
+            if let Some(status) = status
+                && stdout_reader.is_finished()
+                && stderr_reader.is_finished()
+            {
+                break status;
+            }
+            if ctx.cancellation().is_cancelled() {
+                kill(&mut child);
+                return Err(NodeError::cancelled(format!(
+                    "Command '{}' stopped: run cancelled",
+                    self.config.program
+                )));
+            }
+            if Instant::now() >= deadline {
+                kill(&mut child);
+                return Err(NodeError::transient(format!(
+                    "Command '{}' timed out after {}ms",
+                    self.config.program, self.config.timeout_ms
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+
+        // 程序不读取标准输入时写入会失败，这不算错误
+        let _ = stdin_writer.join();
+        let stdout = stdout_reader.join().unwrap_or_default();
+        let stderr = stderr_reader.join().unwrap_or_default();
+        let exit_code = status.code().unwrap_or(-1);
+
+        if self.config.fail_on_nonzero_exit && !status.success() {
+            return Err(NodeError::fatal(format!(
+                "Command '{}' exited with {}: {}",
+                self.config.program,
+                status,
+                stderr.trim()
+            ))
+            .with_details(serde_json::json!({ "exit_code": exit_code, "stderr": stderr })));
+        }
+
+        let outputs = &self.config.outputs;
+        if let Some(port) = &outputs.stdout {
+            ctx.output(port, StringLabel { value: stdout })?;
+        }
+        if let Some(port) = &outputs.stderr {
+            ctx.output(port, StringLabel { value: stderr })?;
+        }
+        if let Some(port) = &outputs.exit_code {
+            ctx.output(
+                port,
+                NumberLabel {
+                    value: exit_code as f64,
+                },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 结束命令所在的进程组，读取输出的线程在管道关闭后随之退出
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    // SAFETY: killpg 只向进程组发送信号，不涉及内存
+    unsafe {
+        libc::killpg(child.id() as libc::pid_t, libc::SIGKILL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn read_to_end(mut reader: impl Read + Send + 'static) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = reader.read_to_end(&mut buffer);
+        String::from_utf8_lossy(&buffer).into_owned()
+    })
+}
+
+/// 标签 JSON -> 命令行文本
+fn label_text(json: Value) -> String {
+    let value = match json {
+        Value::Object(map) if map.len() == 1 => map.into_iter().next().unwrap().1,
+        other => other,
+    };
+    match value {
+        Value::String(text) => text,
+        other => other.to_string(),
+    }
+}
+
+inventory::submit! {
+    NodeRegistration::configured("CommandNode", CommandNode::from_config)
+        .with_version(env!("CARGO_PKG_VERSION"))
+        .with_category("system")
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use anima_weave_core::{
+        NodeDataInputs, NodeDataOutputs, NodeErrorKind, PortRef, RunCancellation, SemanticLabel,
+    };
+    use serde_json::json;
+
+    fn run(config: Value, text: &str) -> Result<NodeDataOutputs> {
+        run_with_cancellation(config, text, RunCancellation::new())
+    }
+
+    fn run_with_cancellation(
+        config: Value,
+        text: &str,
+        cancellation: RunCancellation,
+    ) -> Result<NodeDataOutputs> {
+        let node = CommandNode::new(serde_json::from_value(config)?)?;
+        let mut inputs = NodeDataInputs::new();
+        inputs.insert(
+            PortRef {
+                node_name: "cmd".to_string(),
+                port_name: "text".to_string(),
+            },
+            Box::new(StringLabel {
+                value: text.to_string(),
+            }),
+        );
+        let mut ctx = NodeContext::new("cmd", node.info(), inputs).with_cancellation(cancellation);
+        node.execute(&mut ctx)?;
+        Ok(ctx.into_outputs())
+    }
+
+    fn output<'a>(outputs: &'a NodeDataOutputs, port: &str) -> &'a dyn SemanticLabel {
+        outputs[&PortRef {
+            node_name: "cmd".to_string(),
+            port_name: port.to_string(),
+        }]
+            .as_ref()
+    }
+
+    fn text(label: &dyn SemanticLabel) -> &str {
+        &label.as_any().downcast_ref::<StringLabel>().unwrap().value
+    }
+
+    #[test]
+    fn test_args_env_and_stdin() {
+        let outputs = run(
+            json!({
+                "program": "sh",
+                "args": ["-c", "printf '%s %s ' \"$1\" \"$GREETING\"; cat", "sh", "{text}"],
+                "env": { "GREETING": "hello {text}" },
+                "stdin": "text",
+                "inputs": [{ "name": "text", "label": "StringLabel" }],
+            }),
+            "world",
+        )
+        .unwrap();
+
+        assert_eq!(text(output(&outputs, "stdout")), "world hello world world");
+        let exit_code = output(&outputs, "exit_code")
+            .as_any()
+            .downcast_ref::<NumberLabel>()
+            .unwrap();
+        assert_eq!(exit_code.value, 0.0);
+    }
+
+    #[test]
+    fn test_render_substitutes_each_placeholder_once() {
+        let values = HashMap::from([
+            ("text".to_string(), "{pattern}".to_string()),
+            ("pattern".to_string(), "x".to_string()),
+        ]);
+
+        // 输入值中的占位符不会再被替换，未知的占位符原样保留
+        assert_eq!(
+            render("{text}-{pattern}-{other}{", &values),
+            "{pattern}-x-{other}{"
+        );
+    }
+
+    #[test]
+    fn test_nonzero_exit() {
+        let config = json!({
+            "program": "sh",
+            "args": ["-c", "echo oops >&2; exit 3"],
+            "inputs": [{ "name": "text", "label": "StringLabel" }],
+            "outputs": { "stdout": null, "stderr": "error" },
+        });
+
+        let outputs = run(config.clone(), "").unwrap();
+        assert_eq!(text(output(&outputs, "error")), "oops\n");
+        assert_eq!(outputs.len(), 2);
+
+        let mut failing = config;
+        failing["fail_on_nonzero_exit"] = json!(true);
+        let err = run(failing, "").unwrap_err();
+        assert!(err.to_string().contains("oops"), "{}", err);
+        let err = NodeError::from(err);
+        assert_eq!(err.details().unwrap()["exit_code"], 3);
+    }
+
+    #[test]
+    fn test_timeout_and_cwd() {
+        let err = run(
+            json!({
+                "program": "sleep",
+                "args": ["5"],
+                "inputs": [{ "name": "text", "label": "StringLabel" }],
+                "timeout_ms": 50,
+            }),
+            "",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{}", err);
+
+        let outputs = run(
+            json!({
+                "program": "pwd",
+                "inputs": [{ "name": "text", "label": "StringLabel" }],
+                "cwd": "/",
+            }),
+            "",
+        )
+        .unwrap();
+        assert_eq!(text(output(&outputs, "stdout")), "/\n");
+    }
+
+    #[test]
+    fn test_timeout_kills_background_processes() {
+        // 后台进程继承了输出管道，命令本身退出后读取仍受超时限制
+        let started = Instant::now();
+        let err = run(
+            json!({
+                "program": "sh",
+                "args": ["-c", "sleep 600 & echo hi"],
+                "inputs": [{ "name": "text", "label": "StringLabel" }],
+                "timeout_ms": 200,
+            }),
+            "",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_cancelled_run_stops_command() {
+        let cancellation = RunCancellation::new();
+        let canceller = cancellation.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            canceller.cancel();
+        });
+
+        let started = Instant::now();
+        let err = run_with_cancellation(
+            json!({
+                "program": "sleep",
+                "args": ["600"],
+                "inputs": [{ "name": "text", "label": "StringLabel" }],
+            }),
+            "",
+            cancellation,
+        )
+        .unwrap_err();
+        assert_eq!(NodeError::from(err).kind(), NodeErrorKind::Cancelled);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -1,7 +1,13 @@
 pub mod add_node;
+pub mod branch_node;
+pub mod command_node;
 pub mod random_node;
+pub mod script_node;
 pub mod start_node;
 
 pub use add_node::AddNode;
+pub use branch_node::{IfConfig, IfNode, SwitchConfig, SwitchNode};
+pub use command_node::{CommandConfig, CommandNode};
 pub use random_node::RandomNode;
+pub use script_node::{ScriptConfig, ScriptNode};
 pub use start_node::StartNode;
@@ -0,0 +1,297 @@
+//! Script Node - 图中内联定义的 Rhai 脚本节点
+//!
+//! 端口和脚本都来自节点实例配置（`NodeRef::config`）：
+//!
+//! ```json
+//! {
+//!   "inputs": [{ "name": "number", "label": "NumberLabel" }],
+//!   "outputs": [{ "name": "formatted", "label": "StringLabel" }],
+//!   "script": "`#${number}`",
+//!   "timeout_ms": 100
+//! }
+//! ```
+//!
+//! 标签与脚本值的映射：
+//! - 输入端口以同名变量出现在脚本中；只有一个字段的标签直接映射为该字段的值，
+//!   其余标签映射为对象，未连接的可选端口为 `()`
+//! - 脚本的结果若是以输出端口名为键的对象，按端口分别输出；
+//!   只有一个输出端口时也可以直接返回该端口的值
+//! - 非对象的输出值视为 `{ value: ... }`
+
+use anima_weave_core::{NodeContext, NodeError, NodeImpl};
+use anima_weave_node::{
+    Node, NodeInfo, NodeRegistration, PortSpec, label_from_json, static_node_info,
+};
+use anyhow::{Result, anyhow};
+use rhai::{AST, Dynamic, Engine, EvalAltResult, Scope};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 脚本节点配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScriptConfig {
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub inputs: Vec<PortSpec>,
+    #[serde(default)]
+    pub outputs: Vec<PortSpec>,
+    /// Rhai 脚本，最后一个表达式的值即为输出
+    pub script: String,
+    /// 单次执行的时间上限
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_timeout_ms() -> u64 {
+    1000
+}
+
+/// 脚本节点实现
+#[derive(Debug)]
+pub struct ScriptNode {
+    info: &'static NodeInfo,
+    ast: AST,
+    timeout: Duration,
+}
+
+impl ScriptNode {
+    /// 由配置创建脚本节点，脚本在此时编译
+    pub fn new(config: ScriptConfig) -> Result<Self> {
+        let description = if config.description.is_empty() {
+            "内联 Rhai 脚本节点"
+        } else {
+            &config.description
+        };
+        let info = static_node_info("ScriptNode", description, &config.inputs, &config.outputs)?;
+        let ast = Engine::new()
+            .compile(&config.script)
+            .map_err(|e| anyhow!("Script compile error: {}", e))?;
+
+        Ok(Self {
+            info,
+            ast,
+            timeout: Duration::from_millis(config.timeout_ms),
+        })
+    }
+
+    /// NodeConstructor::Configured 入口
+    fn from_config(config: &Value) -> Result<NodeImpl> {
+        let config: ScriptConfig = serde_json::from_value(config.clone())
+            .map_err(|e| anyhow!("Invalid ScriptNode config: {}", e))?;
+        Ok(NodeImpl::Sync(Arc::new(Self::new(config)?)))
+    }
+
+    /// 带执行时间上限的脚本引擎
+    fn engine(&self) -> Engine {
+        let mut engine = Engine::new();
+        let deadline = Instant::now() + self.timeout;
+        engine.on_progress(move |_| {
+            if Instant::now() >= deadline {
+                Some(Dynamic::UNIT)
+            } else {
+                None
+            }
+        });
+        engine
+    }
+
+    /// 把脚本结果拆分为 (端口名, 值)
+    fn split_outputs(&self, result: Value) -> Result<Vec<(String, Value)>> {
+        let ports = &self.info.output_ports;
+        if let Value::Object(map) = &result {
+            let is_port_map = !map.is_empty()
+                && map
+                    .keys()
+                    .all(|key| ports.iter().any(|port| &port.name == key));
+            if is_port_map {
+                return Ok(map.clone().into_iter().collect());
+            }
+        }
+
+        match ports.as_slice() {
+            [] if result.is_null() => Ok(Vec::new()),
+            [port] => Ok(vec![(port.name.clone(), result)]),
+            _ => Err(anyhow!(
+                "Script must return an object keyed by output port names"
+            )),
+        }
+    }
+}
+
+impl Node for ScriptNode {
+    fn info(&self) -> &'static NodeInfo {
+        self.info
+    }
+
+    fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+        let mut scope = Scope::new();
+        for port in &self.info.input_ports {
+            let value = if ctx.has_input(&port.name) {
+                let json = unwrap_single_field(ctx.input_label(&port.name)?.to_json());
+                rhai::serde::to_dynamic(json).map_err(|e| {
+                    NodeError::invalid_input(&port.name, format!("Input '{}': {}", port.name, e))
+                })?
+            } else {
+                Dynamic::UNIT
+            };
+            scope.push_dynamic(port.name.as_str(), value);
+        }
+
+        let result: Dynamic = self
+            .engine()
+            .eval_ast_with_scope(&mut scope, &self.ast)
+            .map_err(|e| match *e {
+                EvalAltResult::ErrorTerminated(..) => anyhow!(
+                    "Script exceeded its time limit of {}ms",
+                    self.timeout.as_millis()
+                ),
+                e => anyhow!("Script error: {}", e),
+            })?;
+        let result: Value = rhai::serde::from_dynamic(&result)
+            .map_err(|e| anyhow!("Script result is not serializable: {}", e))?;
+
+        for (port_name, value) in self.split_outputs(result)? {
+            let port = self
+                .info
+                .output_ports
+                .iter()
+                .find(|port| port.name == port_name)
+                .ok_or_else(|| anyhow!("Script produced unknown output '{}'", port_name))?;
+            let label = label_from_json(port.port_type.semantic_label(), &wrap_value(value))?;
+            ctx.output_boxed(&port_name, label)?;
+        }
+        Ok(())
+    }
+}
+
+/// 只有一个字段的标签映射为该字段的值
+fn unwrap_single_field(json: Value) -> Value {
+    match json {
+        Value::Object(map) if map.len() == 1 => map.into_iter().next().unwrap().1,
+        other => other,
+    }
+}
+
+/// 非对象值视为 `{ value: ... }`
+fn wrap_value(value: Value) -> Value {
+    match value {
+        Value::Object(_) => value,
+        other => Value::Object(Map::from_iter([("value".to_string(), other)])),
+    }
+}
+
+inventory::submit! {
+    NodeRegistration::configured("ScriptNode", ScriptNode::from_config)
+        .with_version(env!("CARGO_PKG_VERSION"))
+        .with_category("script")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::labels::{NumberLabel, StringLabel};
+    use anima_weave_core::{NodeDataInputs, PortRef, SemanticLabel};
+    use anima_weave_node::create_node_with_config;
+    use serde_json::json;
+
+    fn run(config: Value, number: f64) -> Result<anima_weave_core::NodeDataOutputs> {
+        let node = ScriptNode::new(serde_json::from_value(config)?)?;
+        let mut inputs = NodeDataInputs::new();
+        inputs.insert(
+            PortRef {
+                node_name: "script".to_string(),
+                port_name: "number".to_string(),
+            },
+            Box::new(NumberLabel { value: number }),
+        );
+        let mut ctx = NodeContext::new("script", node.info(), inputs);
+        node.execute(&mut ctx)?;
+        Ok(ctx.into_outputs())
+    }
+
+    fn output<'a>(
+        outputs: &'a anima_weave_core::NodeDataOutputs,
+        port: &str,
+    ) -> &'a dyn SemanticLabel {
+        outputs[&PortRef {
+            node_name: "script".to_string(),
+            port_name: port.to_string(),
+        }]
+            .as_ref()
+    }
+
+    #[test]
+    fn test_single_output_value() {
+        let outputs = run(
+            json!({
+                "inputs": [{ "name": "number", "label": "NumberLabel" }],
+                "outputs": [{ "name": "formatted", "label": "StringLabel" }],
+                "script": "`#${number}`",
+            }),
+            7.0,
+        )
+        .unwrap();
+
+        let formatted = output(&outputs, "formatted")
+            .as_any()
+            .downcast_ref::<StringLabel>()
+            .unwrap();
+        assert_eq!(formatted.value, "#7.0");
+    }
+
+    #[test]
+    fn test_multiple_outputs() {
+        let outputs = run(
+            json!({
+                "inputs": [{ "name": "number", "label": "NumberLabel" }],
+                "outputs": [
+                    { "name": "doubled", "label": "NumberLabel" },
+                    { "name": "text", "label": "StringLabel" }
+                ],
+                "script": "#{ doubled: number * 2.0, text: \"ok\" }",
+            }),
+            2.5,
+        )
+        .unwrap();
+
+        let doubled = output(&outputs, "doubled")
+            .as_any()
+            .downcast_ref::<NumberLabel>()
+            .unwrap();
+        assert_eq!(doubled.value, 5.0);
+        assert_eq!(outputs.len(), 2);
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let err = run(
+            json!({
+                "inputs": [{ "name": "number", "label": "NumberLabel" }],
+                "script": "loop {}",
+                "timeout_ms": 20,
+            }),
+            0.0,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("time limit"), "{}", err);
+    }
+
+    #[test]
+    fn test_invalid_config_is_rejected() {
+        assert!(create_node_with_config("ScriptNode", &json!({ "script": "1 +" })).is_err());
+        assert!(
+            create_node_with_config(
+                "ScriptNode",
+                &json!({
+                    "outputs": [{ "name": "x", "label": "NoSuchLabel" }],
+                    "script": "1",
+                })
+            )
+            .is_err()
+        );
+        assert!(create_node_with_config("ScriptNode", &json!({ "script": "1" })).is_ok());
+    }
+}
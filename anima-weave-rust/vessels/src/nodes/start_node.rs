@@ -3,9 +3,8 @@
 //! 使用新的Node trait接口实现
 
 use crate::labels::{NumberLabel, StringLabel};
-use anima_weave_core::{AnimaWeaveError, NodeDataInputs, NodeDataOutputs, PortRef};
-use anima_weave_node::{Node, NodeInfo, PortDef, register_node};
-use std::boxed::Box;
+use anima_weave_core::{NodeContext, NodeError};
+use anima_weave_node::{ExecutionMode, Node, NodeInfo, PortDef, register_node};
 
 /// 起始节点实现
 ///
@@ -50,6 +49,7 @@ static START_NODE_INFO: once_cell::sync::Lazy<NodeInfo> = once_cell::sync::Lazy:
             PortDef::output_data::<NumberLabel>("number_value"),
             PortDef::output_data::<StringLabel>("string_value"),
         ],
+        mode: ExecutionMode::Concurrent,
     }
 });
 
@@ -58,35 +58,27 @@ impl Node for StartNode {
         &START_NODE_INFO
     }
 
-    fn execute(&self, _inputs: NodeDataInputs) -> Result<NodeDataOutputs, AnimaWeaveError> {
-        log::debug!("StartNode executing");
-
-        let mut outputs = NodeDataOutputs::new();
+    fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+        log::debug!("StartNode {} executing", ctx.node_name());
 
         // 始终输出number_value
-        outputs.insert(
-            PortRef {
-                node_name: "start".to_string(),
-                port_name: "number_value".to_string(),
-            },
-            Box::new(NumberLabel {
+        ctx.output(
+            "number_value",
+            NumberLabel {
                 value: self.initial_number.unwrap_or(0.0),
-            }),
-        );
+            },
+        )?;
 
         // 始终输出string_value
-        outputs.insert(
-            PortRef {
-                node_name: "start".to_string(),
-                port_name: "string_value".to_string(),
-            },
-            Box::new(StringLabel {
+        ctx.output(
+            "string_value",
+            StringLabel {
                 value: self.initial_string.clone().unwrap_or_default(),
-            }),
-        );
+            },
+        )?;
 
-        log::debug!("StartNode produced {} outputs", outputs.len());
-        Ok(outputs)
+        log::debug!("StartNode produced {} outputs", ctx.outputs().len());
+        Ok(())
     }
 }
 
@@ -96,6 +88,7 @@ register_node!(StartNode);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use anima_weave_core::{NodeDataInputs, PortRef};
 
     #[test]
     fn test_start_node_basic() {
@@ -111,12 +104,12 @@ mod tests {
     #[test]
     fn test_start_node_execution() {
         let node = StartNode::new();
-        let inputs = NodeDataInputs::new();
+        let mut ctx = NodeContext::new("start", node.info(), NodeDataInputs::new());
 
-        let result = node.execute(inputs);
+        let result = node.execute(&mut ctx);
         assert!(result.is_ok());
 
-        let outputs = result.unwrap();
+        let outputs = ctx.into_outputs();
         assert!(outputs.contains_key(&PortRef {
             node_name: "start".to_string(),
             port_name: "number_value".to_string(),
@@ -137,11 +130,11 @@ mod tests {
     fn test_start_node_with_number() {
         let node = StartNode::with_number(42.0);
 
-        let inputs = NodeDataInputs::new();
-        let result = node.execute(inputs);
+        let mut ctx = NodeContext::new("start", node.info(), NodeDataInputs::new());
+        let result = node.execute(&mut ctx);
         assert!(result.is_ok());
 
-        let outputs = result.unwrap();
+        let outputs = ctx.into_outputs();
         assert!(outputs.contains_key(&PortRef {
             node_name: "start".to_string(),
             port_name: "number_value".to_string(),
@@ -166,11 +159,11 @@ mod tests {
     fn test_start_node_with_string() {
         let node = StartNode::with_string("hello".to_string());
 
-        let inputs = NodeDataInputs::new();
-        let result = node.execute(inputs);
+        let mut ctx = NodeContext::new("start", node.info(), NodeDataInputs::new());
+        let result = node.execute(&mut ctx);
         assert!(result.is_ok());
 
-        let outputs = result.unwrap();
+        let outputs = ctx.into_outputs();
         assert!(outputs.contains_key(&PortRef {
             node_name: "start".to_string(),
             port_name: "number_value".to_string(),
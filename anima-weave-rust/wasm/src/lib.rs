@@ -0,0 +1,239 @@
+//! `anima-weave-wasm` Crate: WASM 沙箱节点
+//!
+//! 用户贡献的节点以 WASM 模块 + 清单的形式发布，
+//! 在 wasmi 解释器中执行，受燃料和内存限制，没有任何宿主能力。
+//! 注册后与原生节点一样出现在节点工厂中。
+
+pub mod manifest;
+pub mod node;
+
+pub use manifest::{WasmLimits, WasmNodeManifest};
+pub use node::WasmNode;
+
+use anima_weave_core::{Node, NodeImpl};
+use anima_weave_node::{NodeRegistration, register_node};
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::sync::Arc;
+
+/// 将 WASM 节点注册到全局节点工厂，返回节点类型名
+pub fn register_wasm_node(node: WasmNode) -> Result<&'static str> {
+    let node = Arc::new(node);
+    let name = node.info().name;
+    register_node(NodeRegistration::dynamic(name, move || {
+        NodeImpl::Sync(node.clone())
+    }))?;
+    Ok(name)
+}
+
+/// 按清单文件加载并注册 WASM 节点
+pub fn load_wasm_node(manifest_path: &Path) -> Result<&'static str> {
+    let manifest = WasmNodeManifest::from_file(manifest_path)?;
+    let node = WasmNode::from_manifest(&manifest)
+        .with_context(|| format!("Failed to load WASM node {}", manifest_path.display()))?;
+    register_wasm_node(node)
+}
+
+/// 加载目录下所有 `*.node.json` 清单，目录不存在时返回空列表
+pub fn load_wasm_nodes_from_dir(dir: &Path) -> Result<Vec<&'static str>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read plugin directory {}", dir.display()))?
+    {
+        let path = entry?.path();
+        let is_manifest = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(manifest::MANIFEST_SUFFIX));
+        if is_manifest {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut loaded = Vec::new();
+    for path in paths {
+        let name = load_wasm_node(&path)?;
+        log::info!("Loaded WASM node {} from {}", name, path.display());
+        loaded.push(name);
+    }
+    Ok(loaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anima_weave_core::{NodeContext, NodeDataInputs, NodeDataOutputs, PortRef, semantic_label};
+    use anima_weave_node::{create_node_by_type, get_registered_node_types};
+
+    semantic_label! {
+        WasmNumberLabel(value: f64) {}
+    }
+
+    const RESPONSE: &str = r#"{"outputs":{"result":{"value":42.0}}}"#;
+
+    /// 忽略输入、返回固定 JSON 的模块，`body` 为 execute 函数体
+    fn module(body: &str) -> String {
+        format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (global $heap (mut i32) (i32.const 1024))
+                (data (i32.const 0) "{}")
+                (func (export "alloc") (param $len i32) (result i32)
+                    (local $ptr i32)
+                    global.get $heap
+                    local.set $ptr
+                    global.get $heap
+                    local.get $len
+                    i32.add
+                    global.set $heap
+                    local.get $ptr)
+                (func (export "execute") (param i32 i32) (result i64)
+                    {}))"#,
+            RESPONSE.replace('"', "\\\""),
+            body
+        )
+    }
+
+    fn manifest(name: &str, limits: WasmLimits) -> WasmNodeManifest {
+        let mut manifest: WasmNodeManifest = serde_json::from_value(serde_json::json!({
+            "name": name,
+            "module": "inline.wat",
+            "inputs": [{ "name": "x", "label": "WasmNumberLabel", "required": false }],
+            "outputs": [{ "name": "result", "label": "WasmNumberLabel" }],
+        }))
+        .unwrap();
+        manifest.limits = limits;
+        manifest
+    }
+
+    fn run(node: &WasmNode) -> Result<NodeDataOutputs> {
+        let mut inputs = NodeDataInputs::new();
+        inputs.insert(
+            PortRef {
+                node_name: "wasm".to_string(),
+                port_name: "x".to_string(),
+            },
+            Box::new(WasmNumberLabel { value: 1.0 }),
+        );
+        let mut ctx = NodeContext::new("wasm", node.info(), inputs);
+        node.execute(&mut ctx)?;
+        Ok(ctx.into_outputs())
+    }
+
+    #[test]
+    fn test_outputs_are_deserialized_to_labels() {
+        let body = format!("i64.const {}", RESPONSE.len());
+        let node = WasmNode::new(
+            &manifest("WasmConstNode", WasmLimits::default()),
+            module(&body).as_bytes(),
+        )
+        .unwrap();
+
+        let outputs = run(&node).unwrap();
+        let label = &outputs[&PortRef {
+            node_name: "wasm".to_string(),
+            port_name: "result".to_string(),
+        }];
+        let label = label.as_any().downcast_ref::<WasmNumberLabel>().unwrap();
+        assert_eq!(label.value, 42.0);
+    }
+
+    #[test]
+    fn test_fuel_limit() {
+        let limits = WasmLimits {
+            fuel: 10_000,
+            ..WasmLimits::default()
+        };
+        let node = WasmNode::new(
+            &manifest("WasmLoopNode", limits),
+            module("(loop $l (br $l)) i64.const 0").as_bytes(),
+        )
+        .unwrap();
+
+        let err = run(&node).unwrap_err();
+        assert!(err.to_string().contains("fuel"), "{}", err);
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let limits = WasmLimits {
+            max_memory_bytes: 64 * 1024,
+            ..WasmLimits::default()
+        };
+        let node = WasmNode::new(
+            &manifest("WasmGrowNode", limits),
+            module("i32.const 16 memory.grow drop i64.const 0").as_bytes(),
+        )
+        .unwrap();
+
+        assert!(run(&node).is_err());
+    }
+
+    #[test]
+    fn test_output_out_of_bounds() {
+        // 长度 4 GiB - 1，远超模块内存
+        let node = WasmNode::new(
+            &manifest("WasmHugeOutputNode", WasmLimits::default()),
+            module("i64.const 0xffffffff").as_bytes(),
+        )
+        .unwrap();
+
+        let err = run(&node).unwrap_err();
+        assert!(err.to_string().contains("out of bounds"), "{}", err);
+    }
+
+    #[test]
+    fn test_host_imports_are_not_available() {
+        let wat = r#"(module
+            (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (func (export "alloc") (param i32) (result i32) i32.const 0)
+            (func (export "execute") (param i32 i32) (result i64) i64.const 0))"#;
+        let node = WasmNode::new(
+            &manifest("WasmImportNode", WasmLimits::default()),
+            wat.as_bytes(),
+        )
+        .unwrap();
+
+        assert!(run(&node).is_err());
+    }
+
+    #[test]
+    fn test_unknown_label_is_rejected() {
+        let mut manifest = manifest("WasmBadLabelNode", WasmLimits::default());
+        manifest.outputs[0].label = "NoSuchLabel".to_string();
+
+        assert!(WasmNode::new(&manifest, module("i64.const 0").as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_load_from_manifest_dir() {
+        let dir = std::env::temp_dir().join(format!("anima-weave-wasm-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let body = format!("i64.const {}", RESPONSE.len());
+        std::fs::write(dir.join("const.wat"), module(&body)).unwrap();
+        std::fs::write(
+            dir.join("const.node.json"),
+            serde_json::json!({
+                "name": "WasmManifestNode",
+                "module": "const.wat",
+                "outputs": [{ "name": "result", "label": "WasmNumberLabel" }],
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let loaded = load_wasm_nodes_from_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded, vec!["WasmManifestNode"]);
+        assert!(get_registered_node_types().contains(&"WasmManifestNode"));
+        let node = create_node_by_type("WasmManifestNode").unwrap();
+        assert_eq!(node.info().output_ports[0].name, "result");
+    }
+}
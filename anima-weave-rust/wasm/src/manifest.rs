@@ -0,0 +1,74 @@
+//! WASM 节点清单
+//!
+//! 清单与模块放在一起，文件名以 `.node.json` 结尾：
+//!
+//! ```json
+//! {
+//!   "name": "DoubleNode",
+//!   "description": "数值翻倍",
+//!   "module": "double.wasm",
+//!   "inputs": [{ "name": "x", "label": "NumberLabel" }],
+//!   "outputs": [{ "name": "result", "label": "NumberLabel" }],
+//!   "limits": { "fuel": 10000000, "max_memory_bytes": 16777216 }
+//! }
+//! ```
+
+use anima_weave_node::PortSpec;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// 清单文件名后缀
+pub const MANIFEST_SUFFIX: &str = ".node.json";
+
+/// WASM 节点清单
+#[derive(Debug, Clone, Deserialize)]
+pub struct WasmNodeManifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 模块路径（`.wasm` 或 `.wat`），相对于清单所在目录
+    pub module: PathBuf,
+    #[serde(default)]
+    pub inputs: Vec<PortSpec>,
+    #[serde(default)]
+    pub outputs: Vec<PortSpec>,
+    #[serde(default)]
+    pub limits: WasmLimits,
+}
+
+/// 单次执行的资源限制
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct WasmLimits {
+    /// 燃料上限，约等于可执行的指令数，耗尽即中止（执行时间上限）
+    pub fuel: u64,
+    /// 线性内存上限（字节）
+    pub max_memory_bytes: usize,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            fuel: 10_000_000,
+            max_memory_bytes: 16 * 1024 * 1024,
+        }
+    }
+}
+
+impl WasmNodeManifest {
+    /// 读取清单，并把模块路径解析为绝对于清单目录的路径
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read manifest {}", path.display()))?;
+        let mut manifest: WasmNodeManifest = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid manifest {}", path.display()))?;
+
+        if manifest.module.is_relative()
+            && let Some(dir) = path.parent()
+        {
+            manifest.module = dir.join(&manifest.module);
+        }
+        Ok(manifest)
+    }
+}
@@ -0,0 +1,168 @@
+//! WasmNode - 在 wasmi 沙箱中执行的节点
+
+use crate::manifest::{WasmLimits, WasmNodeManifest};
+use anima_weave_core::{Node, NodeContext, NodeError, NodeInfo};
+use anima_weave_node::{label_from_json, static_node_info};
+use anyhow::{Context, Result, anyhow};
+use serde_json::{Map, Value, json};
+use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, TrapCode};
+
+/// 在 WASM 沙箱中执行的节点
+///
+/// 每次执行都会新建 Store 并重新实例化模块，执行之间不共享任何状态。
+/// Linker 不提供任何宿主函数，模块无法访问文件系统、网络或时钟。
+///
+/// 模块 ABI：
+/// - 导出 `memory`
+/// - 导出 `alloc(len: i32) -> i32`：宿主通过它申请输入缓冲区
+/// - 导出 `execute(ptr: i32, len: i32) -> i64`：返回 `(out_ptr << 32) | out_len`
+///
+/// 输入为 `{"inputs": {端口: 标签JSON}}`，
+/// 输出为 `{"outputs": {端口: 标签JSON}}` 或 `{"error": "..."}`
+#[derive(Debug)]
+pub struct WasmNode {
+    info: &'static NodeInfo,
+    engine: Engine,
+    module: Module,
+    limits: WasmLimits,
+}
+
+impl WasmNode {
+    /// 根据清单加载模块
+    pub fn from_manifest(manifest: &WasmNodeManifest) -> Result<Self> {
+        let bytes = std::fs::read(&manifest.module)
+            .with_context(|| format!("Failed to read module {}", manifest.module.display()))?;
+        Self::new(manifest, &bytes)
+    }
+
+    /// 由清单和模块字节（`.wasm` 二进制或 `.wat` 文本）创建节点
+    pub fn new(manifest: &WasmNodeManifest, module_bytes: &[u8]) -> Result<Self> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, module_bytes)
+            .map_err(|e| anyhow!("Invalid WASM module for {}: {}", manifest.name, e))?;
+
+        let info = static_node_info(
+            &manifest.name,
+            &manifest.description,
+            &manifest.inputs,
+            &manifest.outputs,
+        )?;
+
+        Ok(Self {
+            info,
+            engine,
+            module,
+            limits: manifest.limits,
+        })
+    }
+
+    /// 在新的沙箱实例中调用 `execute`，返回模块输出的原始字节
+    fn call(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(self.limits.max_memory_bytes)
+            .instances(1)
+            .trap_on_grow_failure(true)
+            .build();
+        let mut store: Store<StoreLimits> = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(self.limits.fuel).map_err(wasm_error)?;
+
+        let instance = Linker::new(&self.engine)
+            .instantiate_and_start(&mut store, &self.module)
+            .map_err(wasm_error)?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| anyhow!("WASM module does not export 'memory'"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(wasm_error)?;
+        let execute = instance
+            .get_typed_func::<(i32, i32), i64>(&store, "execute")
+            .map_err(wasm_error)?;
+
+        let input_len = i32::try_from(input.len()).context("Input too large")?;
+        let input_ptr = alloc.call(&mut store, input_len).map_err(wasm_error)?;
+        memory
+            .write(&mut store, input_ptr as u32 as usize, input)
+            .map_err(|e| anyhow!("Failed to write input: {}", e))?;
+
+        let packed = execute
+            .call(&mut store, (input_ptr, input_len))
+            .map_err(wasm_error)? as u64;
+        let output_ptr = (packed >> 32) as usize;
+        let output_len = (packed & 0xffff_ffff) as usize;
+
+        // 指针和长度来自模块，分配缓冲区之前先确认输出位于模块内存之内
+        let output_end = output_ptr
+            .checked_add(output_len)
+            .filter(|end| *end <= memory.data_size(&store))
+            .filter(|end| *end <= self.limits.max_memory_bytes);
+        if output_end.is_none() {
+            return Err(anyhow!(
+                "WASM output ({} bytes at {}) is out of bounds",
+                output_len,
+                output_ptr
+            ));
+        }
+
+        let mut output = vec![0; output_len];
+        memory
+            .read(&store, output_ptr, &mut output)
+            .map_err(|e| anyhow!("Failed to read output: {}", e))?;
+        Ok(output)
+    }
+}
+
+impl Node for WasmNode {
+    fn info(&self) -> &'static NodeInfo {
+        self.info
+    }
+
+    fn execute(&self, ctx: &mut NodeContext) -> Result<(), NodeError> {
+        let mut inputs = Map::new();
+        for port in &self.info.input_ports {
+            if ctx.has_input(&port.name) {
+                inputs.insert(port.name.clone(), ctx.input_label(&port.name)?.to_json());
+            } else if port.required {
+                return Err(NodeError::invalid_input(
+                    &port.name,
+                    format!("Missing required input '{}'", port.name),
+                ));
+            }
+        }
+
+        let request = json!({ "inputs": inputs }).to_string();
+        let response = self.call(request.as_bytes())?;
+        let response: Value =
+            serde_json::from_slice(&response).context("WASM node returned invalid JSON")?;
+
+        if let Some(error) = response.get("error") {
+            let message = error.as_str().map(str::to_string);
+            return Err(NodeError::fatal(
+                message.unwrap_or_else(|| error.to_string()),
+            ));
+        }
+
+        let outputs = response
+            .get("outputs")
+            .and_then(Value::as_object)
+            .ok_or_else(|| anyhow!("WASM node response has no 'outputs' object"))?;
+        for port in &self.info.output_ports {
+            if let Some(value) = outputs.get(&port.name) {
+                let label = label_from_json(port.port_type.semantic_label(), value)?;
+                ctx.output_boxed(&port.name, label)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn wasm_error(error: wasmi::Error) -> anyhow::Error {
+    if error.as_trap_code() == Some(TrapCode::OutOfFuel) {
+        anyhow!("WASM node exceeded its fuel limit")
+    } else {
+        anyhow!("WASM error: {}", error)
+    }
+}